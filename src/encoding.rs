@@ -0,0 +1,118 @@
+//! This module defines the string encodings used by the backblaze api in http headers and urls.
+//!
+//! Backblaze requires file names and file info values to be [percent-encoded][1] when they are
+//! placed in http headers or urls, so that arbitrary UTF-8 can be used. The functions in this
+//! module are used internally by this library, but are also exposed for code building its own
+//! requests.
+//!
+//!  [1]: https://www.backblaze.com/b2/docs/string_encoding.html
+
+/// Returns true if the byte is not encoded by [`percent_encode`].
+///
+///  [`percent_encode`]: fn.percent_encode.html
+fn is_b2_safe(b: u8) -> bool {
+    if b.is_ascii_alphanumeric() {
+        return true;
+    }
+    match b {
+        b'.' | b'_' | b'-' | b'/' | b'~' | b'!' | b'$' | b'\'' | b'(' | b')' | b'*' | b';' |
+        b'=' | b':' | b'@' => true,
+        _ => false
+    }
+}
+/// Returns true if the byte is an attr-char as defined by [RFC 5987][1].
+///
+///  [1]: https://tools.ietf.org/html/rfc5987#section-3.2.1
+fn is_attr_char(b: u8) -> bool {
+    if b.is_ascii_alphanumeric() {
+        return true;
+    }
+    match b {
+        b'!' | b'#' | b'$' | b'&' | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~' => true,
+        _ => false
+    }
+}
+fn encode_with(s: &str, safe: fn(u8) -> bool) -> String {
+    let mut res = String::with_capacity(s.len());
+    for &b in s.as_bytes() {
+        if safe(b) {
+            res.push(b as char);
+        } else {
+            res.push_str(&format!("%{:02X}", b));
+        }
+    }
+    res
+}
+fn hex_value(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
+}
+
+/// Percent-encodes a string in the way backblaze expects file names and file info values to be
+/// encoded.
+///
+/// ```rust
+///use backblaze_b2::encoding::percent_encode;
+///
+///assert_eq!(percent_encode("photos/cat.png"), "photos/cat.png");
+///assert_eq!(percent_encode("ø hello, world"), "%C3%B8%20hello%2C%20world");
+/// ```
+pub fn percent_encode(s: &str) -> String {
+    encode_with(s, is_b2_safe)
+}
+/// Decodes a percent-encoded string as returned by backblaze. As described in the backblaze
+/// documentation, a `+` is decoded as a space.
+///
+/// Returns `None` if the string contains an invalid escape, or if the decoded bytes are not valid
+/// UTF-8.
+///
+/// ```rust
+///use backblaze_b2::encoding::{percent_decode, percent_encode};
+///
+///assert_eq!(percent_decode("%C3%B8%20hello+world").unwrap(), "ø hello world");
+///assert_eq!(percent_decode("%C3").is_none(), true);
+///assert_eq!(percent_decode("%G0").is_none(), true);
+///
+///let emoji = "🐱 info";
+///assert_eq!(percent_decode(&percent_encode(emoji)).unwrap(), emoji);
+/// ```
+pub fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut res = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                if i + 2 >= bytes.len() {
+                    return None;
+                }
+                let hi = hex_value(bytes[i+1])?;
+                let lo = hex_value(bytes[i+2])?;
+                res.push(hi * 16 + lo);
+                i += 3;
+            },
+            b'+' => {
+                res.push(b' ');
+                i += 1;
+            },
+            b => {
+                res.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(res).ok()
+}
+/// Creates the `filename*` parameter of a `Content-Disposition` header as described in [RFC
+/// 5987][1]. This allows file names containing characters outside of ascii.
+///
+/// ```rust
+///use backblaze_b2::encoding::content_disposition_filename;
+///
+///assert_eq!(content_disposition_filename("résumé.pdf"),
+///           "filename*=UTF-8''r%C3%A9sum%C3%A9.pdf");
+/// ```
+///
+///  [1]: https://tools.ietf.org/html/rfc5987
+pub fn content_disposition_filename(file_name: &str) -> String {
+    format!("filename*=UTF-8''{}", encode_with(file_name, is_attr_char))
+}
@@ -30,6 +30,7 @@
 extern crate base64;
 extern crate serde;
 extern crate serde_json;
+extern crate sha1;
 extern crate core;
 
 #[macro_use]
@@ -38,11 +39,18 @@ extern crate serde_derive;
 extern crate hyper;
 
 pub mod raw;
+pub mod encoding;
+pub mod transfer;
+pub mod verify;
+pub mod retry;
+pub mod publish;
 
 use std::fmt;
+use std::time::Duration;
 use hyper::client::Response;
 
 header! { (B2AuthHeader, "Authorization") => [String] }
+header! { (RetryAfter, "Retry-After") => [u64] }
 
 /// The b2 api returns errors in a json-object, that can be deserialized into this struct. This
 /// struct is usually contained in a [`B2Error`].
@@ -52,7 +60,9 @@ header! { (B2AuthHeader, "Authorization") => [String] }
 pub struct B2ErrorMessage {
     code: String,
     message: String,
-    status: u32
+    status: u32,
+    #[serde(skip)]
+    retry_after: Option<u64>
 }
 
 /// An error caused while using any of the B2 apis. Errors returned by the b2 api are stored
@@ -86,7 +96,55 @@ pub enum B2Error {
     /// When the b2 website returns an error, it is stored in this variant.
     B2Error(hyper::status::StatusCode, B2ErrorMessage),
     /// This type is only returned if the b2 website is not following the api spec.
-    ApiInconsistency(String)
+    ApiInconsistency(String),
+    /// This type is returned if a request was rejected by this library before it was sent to
+    /// backblaze. The `field` is the name of the offending argument.
+    InvalidRequest { field: &'static str, reason: String },
+    /// This type is returned if backblaze returned a file from another bucket than the one the
+    /// file was expected to be in.
+    BucketMismatch { expected: String, actual: String },
+    /// This type is returned if backblaze responded with an error status, but the body was not
+    /// an error message, for example because a proxy replaced the response with an html page. The
+    /// `body` contains the start of the response body, and `retry_after` the amount of seconds
+    /// requested by the `Retry-After` header, if the response had one.
+    UnexpectedResponse {
+        status: hyper::status::StatusCode,
+        body: String,
+        retry_after: Option<u64>
+    },
+    /// This type is returned if this library determined that a file name has no file that the
+    /// requested operation can use, for example because it only has hide markers.
+    FileNotFound { file_name: String, reason: String },
+    /// This type is returned if a file was modified by someone else while this library was
+    /// performing several requests on it.
+    Conflict { file_name: String, reason: String },
+    /// This type is returned by the listing iterators if backblaze returned a cursor that does
+    /// not advance the listing, or if the listing needed more pages than its page limit. The
+    /// `cursor` is the last cursor returned by backblaze, and `pages` is the amount of pages
+    /// fetched.
+    PaginationNotAdvancing { cursor: String, pages: u64 },
+    /// This type is returned by [`retry_upload`] if the upload failed with an error that could be
+    /// retried, but the body could not be rewound to send it again. The contained error is the
+    /// error of the last attempt. The caller must create the body again to retry the upload.
+    ///
+    ///  [`retry_upload`]: retry/fn.retry_upload.html
+    UploadNotReplayable(Box<B2Error>),
+    /// This type is returned if a file of `size` bytes was passed to an upload that accepts at
+    /// most `limit` bytes, such as the [`MAX_SIMPLE_UPLOAD_SIZE`] of a single upload. The upload
+    /// is rejected before it is sent to backblaze.
+    ///
+    ///  [`MAX_SIMPLE_UPLOAD_SIZE`]: raw/upload/constant.MAX_SIMPLE_UPLOAD_SIZE.html
+    UploadTooLarge { size: u64, limit: u64 },
+    /// This type is returned if a [`FileSelector`] was passed to an api call that does not support
+    /// selecting the file that way. The `required` field describes what the call needs, such as
+    /// `"a file id"`.
+    ///
+    ///  [`FileSelector`]: raw/download/enum.FileSelector.html
+    UnsupportedFileSelector { required: &'static str },
+    /// This variant is not part of the public api. It exists so that adding more variants is not
+    /// a breaking change, so always include a wildcard arm when matching on this type.
+    #[doc(hidden)]
+    __Nonexhaustive
 }
 
 /// Load errors
@@ -98,26 +156,41 @@ impl B2Error {
     ///
     ///  [`should_obtain_new_authentication`]: #method.should_obtain_new_authentication
     pub fn is_service_unavilable(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let Some(status) = self.http_status() {
             status >= 500 && status <= 599
         } else { false }
     }
     /// Returns true if we are making too many requests.
     pub fn is_too_many_requests(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
-            status == 429
-        } else { false }
+        self.http_status() == Some(429)
     }
-    fn get_io_kind(&self) -> Option<::std::io::ErrorKind> {
+    /// Returns the http status of the response that caused the error, if the error was returned
+    /// by backblaze.
+    pub fn http_status(&self) -> Option<u32> {
+        match *self {
+            B2Error::B2Error(_, ref errm) => Some(errm.status),
+            B2Error::UnexpectedResponse { status, .. } => Some(u32::from(status.to_u16())),
+            _ => None
+        }
+    }
+    fn get_io_error(&self) -> Option<&::std::io::Error> {
         match self {
             &B2Error::IOError(ref ioe) => Some(ioe),
             &B2Error::HyperError(::hyper::error::Error::Io(ref ioe)) => Some(ioe),
             _ => None
-        }.map(|io| io.kind())
+        }
+    }
+    fn get_io_kind(&self) -> Option<::std::io::ErrorKind> {
+        self.get_io_error().map(|io| io.kind())
     }
     /// Returns true if any of the situtations described on the [B2 documentation][1] has occurred.
     /// When this function returns true, you should obtain a new [`B2Authorization`].
     ///
+    /// This function returns false for [`is_account_suspended`] and [`is_payment_required`], since
+    /// new authentication cannot fix those.
+    ///
+    ///  [`is_account_suspended`]: #method.is_account_suspended
+    ///  [`is_payment_required`]: #method.is_payment_required
     ///  [1]: https://www.backblaze.com/b2/docs/uploading.html
     ///  [`B2Authorization`]: raw/authorize/struct.B2Authorization.html
     pub fn should_obtain_new_authentication(&self) -> bool {
@@ -131,24 +204,115 @@ impl B2Error {
                 &::std::io::ErrorKind::TimedOut => true,
                 _ => false
             }
+        } else if self.is_account_suspended() || self.is_payment_required() {
+            false
         } else { self.is_authorization_issue() || self.is_service_unavilable() }
     }
     /// Returns true if you should be using some sort of exponential back off for future requests.
     pub fn should_back_off(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
-            match status {
-                408 => true, 429 => true, 503 => true,
-                _ => false
-            }
-        } else { false }
+        match self.http_status() {
+            Some(408) => true, Some(429) => true, Some(503) => true,
+            _ => false
+        }
+    }
+    /// Returns the delay requested by the `Retry-After` header, if backblaze sent one. This header
+    /// is usually sent along with [`is_too_many_requests`] and [`is_service_unavilable`].
+    ///
+    ///  [`is_too_many_requests`]: #method.is_too_many_requests
+    ///  [`is_service_unavilable`]: #method.is_service_unavilable
+    pub fn retry_after(&self) -> Option<Duration> {
+        match *self {
+            B2Error::B2Error(_, B2ErrorMessage { retry_after, .. }) |
+            B2Error::UnexpectedResponse { retry_after, .. } => retry_after.map(Duration::from_secs),
+            _ => None
+        }
+    }
+    /// Returns true if the error is an io error that is likely to go away if the request is
+    /// made again, such as a reset or timed out connection, or a response body that ended early.
+    pub fn is_transient_io_error(&self) -> bool {
+        match self.get_io_kind() {
+            Some(::std::io::ErrorKind::ConnectionReset) => true,
+            Some(::std::io::ErrorKind::ConnectionAborted) => true,
+            Some(::std::io::ErrorKind::BrokenPipe) => true,
+            Some(::std::io::ErrorKind::TimedOut) => true,
+            Some(::std::io::ErrorKind::Interrupted) => true,
+            Some(::std::io::ErrorKind::UnexpectedEof) => true,
+            // hyper reports a body that ends before its content length like this
+            Some(::std::io::ErrorKind::Other) =>
+                self.get_io_error().map_or(false, |ioe| ioe.to_string() == "early eof"),
+            _ => false
+        }
+    }
+}
+/// Connection errors
+impl B2Error {
+    /// Returns true if the tls connection could not be established, for example because the
+    /// certificate of the server could not be verified. This usually requires a change of
+    /// configuration, such as installing a certificate of a proxy intercepting tls connections,
+    /// so retrying the request is not useful.
+    ///
+    /// ```rust
+    ///extern crate backblaze_b2;
+    ///extern crate hyper;
+    ///use std::io;
+    ///use backblaze_b2::B2Error;
+    ///
+    ///# fn main() {
+    ///let cert_error = io::Error::new(io::ErrorKind::Other, "certificate verify failed");
+    ///let err = B2Error::HyperError(hyper::Error::Ssl(Box::new(cert_error)));
+    ///assert!(err.is_tls_error());
+    ///assert!(!err.is_dns_error());
+    ///
+    ///let lookup_error = io::Error::new(io::ErrorKind::Other,
+    ///                                  "failed to lookup address information: Name or service not known");
+    ///let err = B2Error::HyperError(hyper::Error::Io(lookup_error));
+    ///assert!(err.is_dns_error());
+    ///assert!(!err.is_tls_error());
+    ///# }
+    /// ```
+    pub fn is_tls_error(&self) -> bool {
+        match *self {
+            B2Error::HyperError(::hyper::error::Error::Ssl(_)) => true,
+            _ => false
+        }
+    }
+    /// Returns true if the host name of the server could not be resolved.
+    ///
+    /// This is a best-effort check. The standard library does not give dns errors a kind of
+    /// their own, so this checks that the message of the error starts with `failed to lookup
+    /// address`, which is the message used by the standard library resolver. Errors from a
+    /// custom resolver or a future version of the standard library may not be recognized.
+    pub fn is_dns_error(&self) -> bool {
+        match self.get_io_error() {
+            // the kind is `Other` on older versions of the standard library and unnameable
+            // on newer ones, so only the message is checked
+            Some(ioe) => ioe.to_string().starts_with("failed to lookup address"),
+            None => false
+        }
+    }
+    /// Returns true if an io operation timed out. Depending on the platform, a timeout set on the
+    /// connection is reported as either `TimedOut` or `WouldBlock`, so both are accepted.
+    ///
+    /// Hyper does not report in which phase of the request an io error happened, so this returns
+    /// true both when connecting timed out and when reading or writing on an established
+    /// connection timed out, including while reading a response body.
+    pub fn is_timeout(&self) -> bool {
+        match self.get_io_kind() {
+            Some(::std::io::ErrorKind::TimedOut) => true,
+            Some(::std::io::ErrorKind::WouldBlock) => true,
+            _ => false
+        }
     }
 }
 /// Authorization errors
 #[allow(unused_variables)]
 impl B2Error {
-    /// Returns true if the error is related to invalid credentials during authentication.
+    /// Returns true if the error is related to invalid credentials during authentication. This
+    /// includes suspended accounts, see also [`is_account_suspended`].
+    ///
+    ///  [`is_account_suspended`]: #method.is_account_suspended
     pub fn is_credentials_issue(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             match message.as_str() {
                 "B2 has not been enabled for this account" => true,
                 "User is in B2 suspend" => true,
@@ -159,12 +323,32 @@ impl B2Error {
             }
         } else { false }
     }
+    /// Returns true if the account has been suspended or disabled by backblaze. Obtaining new
+    /// authentication does not help in this case.
+    pub fn is_account_suspended(&self) -> bool {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
+            if code == "account_suspended" { return true; }
+            match message.as_str() {
+                "User is in B2 suspend" => true,
+                "B2 has been disabled for this account" => true,
+                "Account is suspended" => true,
+                _ => false
+            }
+        } else { false }
+    }
+    /// Returns true if the request failed because a payment to backblaze failed. Obtaining new
+    /// authentication does not help in this case.
+    pub fn is_payment_required(&self) -> bool {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
+            status == 402 || code == "payment_required"
+        } else { false }
+    }
     /// Returns true if the error is caused by the authentication being expired. Consider using the
     /// method [`should_obtain_new_authentication`] instead.
     ///
     ///  [`should_obtain_new_authentication`]: #method.should_obtain_new_authentication
     pub fn is_expired_authentication(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             if status == 401 && code == "expired_auth_token" {
                 return true;
             }
@@ -175,7 +359,7 @@ impl B2Error {
     /// including expired authentication tokens and invalid authorization tokens.
     pub fn is_authorization_issue(&self) -> bool {
         if self.is_expired_authentication() { return true; }
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             if message.starts_with("Account ") && message.ends_with(" does not exist") {
                 return true;
             }
@@ -193,13 +377,20 @@ impl B2Error {
             }
         } else { false }
     }
+    /// Returns true if the authorization token is valid, but the application key it was created
+    /// from lacks the capability needed for the call, such as `deleteFiles`.
+    pub fn is_missing_capability(&self) -> bool {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
+            status == 401 && code == "unauthorized" && !self.is_authorization_issue()
+        } else { false }
+    }
 }
 /// File errors
 #[allow(unused_variables)]
 impl B2Error {
     /// Returns true if the error is caused by a file name which is not allowed on the b2 server.
     pub fn is_invalid_file_name(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             match message.as_str() {
                 "File names must contain at least one character" => true,
                 "File names in UTF8 must be no more than 1000 bytes" => true,
@@ -215,7 +406,8 @@ impl B2Error {
     }
     /// Returns true if the error is related to a file that was not found.
     pub fn is_file_not_found(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::FileNotFound { .. } = self { return true; }
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             if code == "no_such_file" { return true; }
             if message.starts_with("Invalid fileId: ") { return true; }
             if message.starts_with("Not a valid file id: ") { return true; }
@@ -232,20 +424,20 @@ impl B2Error {
     }
     /// Returns true if the error is caused by an attempt to hide a hidden file.
     pub fn is_file_already_hidden(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             code == "already_hidden"
         } else { false }
     }
     /// Returns true if the error is caused by a request to download an interval of a file that is
     /// out of bounds.
     pub fn is_range_out_of_bounds(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             code == "range_not_satisfiable"
         } else { false }
     }
     /// Returns true if the error is caused by the sha1 of the uploaded file not matching.
     pub fn is_invalid_sha1(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             message == "Sha1 did not match data received"
         } else { false }
     }
@@ -255,7 +447,7 @@ impl B2Error {
 impl B2Error {
     /// Returns true if the error is caused by the account having reached the maximum bucket count.
     pub fn is_maximum_bucket_limit(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             if status == 400 && code == "too_many_buckets" {
                 return true;
             }
@@ -265,7 +457,7 @@ impl B2Error {
     /// Returns true if the error is caused by an attempt to create a bucket with a name of a
     /// pre-existing bucket.
     pub fn is_duplicate_bucket_name(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             if status == 400 && code == "duplicate_bucket_name" {
                 return true;
             }
@@ -275,7 +467,7 @@ impl B2Error {
     /// Returns true if the error is caused by an attempt to create a bucket with a name which is
     /// not allowed.
     pub fn is_invalid_bucket_name(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             if status == 400 {
                 match message.as_str() {
                     "bucketName must be at least 6 characters long" => true,
@@ -288,7 +480,7 @@ impl B2Error {
     }
     /// Returns true if the error is caused by requests to interact with buckets that do not exist.
     pub fn is_bucket_not_found(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             if message.starts_with("Bucket does not exist: ") { return true; }
             if message.starts_with("Invalid bucket id: ") { return true; }
             if message.starts_with("Invalid bucketId: ") { return true; }
@@ -308,22 +500,90 @@ impl B2Error {
 /// Various errors
 #[allow(unused_variables)]
 impl B2Error {
-    /// Returns true if a request used a ifRevisionIs header and the test failed.
+    /// Returns the name of the offending argument if this error was caused by a request that was
+    /// rejected before being sent to backblaze.
+    ///
+    /// ```rust
+    ///use backblaze_b2::B2Error;
+    ///
+    ///let err = B2Error::InvalidRequest { field: "file_id", reason: "not found".to_owned() };
+    ///assert_eq!(err.field(), Some("file_id"));
+    ///assert_eq!(B2Error::ApiInconsistency("bad".to_owned()).field(), None);
+    /// ```
+    pub fn field(&self) -> Option<&'static str> {
+        if let &B2Error::InvalidRequest { field, ref reason } = self {
+            Some(field)
+        } else { None }
+    }
+    /// Returns true if a file was found in another bucket than expected. See
+    /// [`get_file_info_in_bucket`].
+    ///
+    ///  [`get_file_info_in_bucket`]: raw/authorize/struct.B2Authorization.html#method.get_file_info_in_bucket
+    pub fn is_bucket_mismatch(&self) -> bool {
+        if let &B2Error::BucketMismatch { ref expected, ref actual } = self {
+            true
+        } else { false }
+    }
+    /// Returns true if a file could not be deleted or its retention could not be shortened,
+    /// because it is protected by file lock retention or a legal hold.
+    pub fn is_file_lock_violation(&self) -> bool {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
+            code == "file_lock_conflict" || (code == "access_denied" && (
+                    message.contains("retention") || message.contains("legal hold")))
+        } else { false }
+    }
+    /// Returns true if a request used a ifRevisionIs header and the test failed, or if a file
+    /// was modified by someone else during an operation consisting of several requests.
     pub fn is_conflict(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::Conflict { .. } = self { return true; }
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             status == 409
         } else { false }
     }
+    /// Returns true if a listing iterator stopped because backblaze returned a cursor that does
+    /// not advance the listing, or because the listing exceeded its page limit.
+    pub fn is_pagination_not_advancing(&self) -> bool {
+        if let &B2Error::PaginationNotAdvancing { ref cursor, pages } = self {
+            true
+        } else { false }
+    }
+    /// Returns true if an upload could have been retried, but its body could not be sent again.
+    /// See [`UploadNotReplayable`].
+    ///
+    ///  [`UploadNotReplayable`]: #variant.UploadNotReplayable
+    pub fn is_upload_not_replayable(&self) -> bool {
+        if let &B2Error::UploadNotReplayable(_) = self {
+            true
+        } else { false }
+    }
+    /// Returns true if a file was too large for the upload it was passed to. See
+    /// [`UploadTooLarge`].
+    ///
+    ///  [`UploadTooLarge`]: #variant.UploadTooLarge
+    pub fn is_upload_too_large(&self) -> bool {
+        if let &B2Error::UploadTooLarge { size, limit } = self {
+            true
+        } else { false }
+    }
+    /// Returns true if a file was selected in a way the api call does not support. See
+    /// [`UnsupportedFileSelector`].
+    ///
+    ///  [`UnsupportedFileSelector`]: #variant.UnsupportedFileSelector
+    pub fn is_unsupported_file_selector(&self) -> bool {
+        if let &B2Error::UnsupportedFileSelector { required } = self {
+            true
+        } else { false }
+    }
     /// Returns true if the usage cap on backblaze b2 has been exceeded.
     pub fn is_cap_exceeded(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             code == "cap_exceeded"
         } else { false }
     }
     /// Returns true if the error is caused by interacting with snapshot buckets in ways not
     /// allowed.
     pub fn is_snapshot_interaction_failure(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             match message.as_str() {
                 "Snapshot buckets are reserved for Backblaze use" => true,
                 "Allow snapshot header must be specified when deleting a file from a snapshot bucket" => true,
@@ -332,9 +592,17 @@ impl B2Error {
             }
         } else { false }
     }
+    /// Returns true if backblaze no longer supports the version of the api used by this library.
+    /// If this happens, this library must be updated.
+    pub fn is_api_version_unsupported(&self) -> bool {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
+            code == "unsupported_api_version" ||
+                message.to_lowercase().starts_with("unsupported api version")
+        } else { false }
+    }
     /// Returns true if the issue is regarding an invalid file prefix.
     pub fn is_prefix_issue(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             match message.as_str() {
                 "Prefix must not start with delimiter" => true,
                 "Prefix must be 1 or more characters long" => true,
@@ -344,7 +612,7 @@ impl B2Error {
     }
     /// Returns true if the issue is an invalid path delimiter.
     pub fn is_invalid_delimiter(&self) -> bool {
-        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status }) = self {
+        if let &B2Error::B2Error(_, B2ErrorMessage { ref code, ref message, status, .. }) = self {
             message == "Delimiter must be within acceptable list"
         } else { false }
     }
@@ -370,14 +638,59 @@ impl From<std::io::Error> for B2Error {
         B2Error::IOError(err)
     }
 }
+/// The maximum amount of bytes read from the body of a response containing an error.
+const MAX_ERROR_BODY: u64 = 64 * 1024;
+/// The maximum amount of bytes of an unexpected body stored in an error.
+const MAX_ERROR_SNIPPET: usize = 1024;
 impl B2Error {
     fn from_response(response: Response) -> B2Error {
         let status = response.status;
-        let b2err = serde_json::from_reader(response);
-        match b2err {
-            Ok(errm) =>
-                B2Error::B2Error(status, errm),
-            Err(json) => B2Error::from(json)
+        let retry_after = response.headers.get::<RetryAfter>().map(|header| header.0);
+        let mut err = B2Error::from_error_body(status, response);
+        match err {
+            B2Error::B2Error(_, ref mut errm) => errm.retry_after = retry_after,
+            B2Error::UnexpectedResponse { retry_after: ref mut field, .. } => *field = retry_after,
+            _ => ()
+        }
+        err
+    }
+    /// Creates the error for a response with the given error status and body. If the body is not
+    /// an error message from backblaze, an [`UnexpectedResponse`] is returned, so the status is
+    /// not lost. At most 64 KiB of the body is read.
+    ///
+    /// ```rust
+    ///extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///use hyper::status::StatusCode;
+    ///use backblaze_b2::B2Error;
+    ///
+    ///# fn main() {
+    ///let err = B2Error::from_error_body(StatusCode::BadGateway, &b"<html>Bad Gateway</html>"[..]);
+    ///assert_eq!(err.http_status(), Some(502));
+    ///assert!(err.is_service_unavilable());
+    ///# }
+    /// ```
+    ///
+    ///  [`UnexpectedResponse`]: #variant.UnexpectedResponse
+    pub fn from_error_body<R: std::io::Read>(status: hyper::status::StatusCode, body: R)
+        -> B2Error
+    {
+        // the error message is small, so a huge body is not an error message
+        let mut buf = Vec::new();
+        let mut body = std::io::Read::take(body, MAX_ERROR_BODY);
+        if let Err(err) = std::io::Read::read_to_end(&mut body, &mut buf) {
+            return B2Error::IOError(err);
+        }
+        match serde_json::from_slice::<B2ErrorMessage>(&buf) {
+            Ok(errm) => B2Error::B2Error(status, errm),
+            Err(_) => {
+                let len = std::cmp::min(buf.len(), MAX_ERROR_SNIPPET);
+                B2Error::UnexpectedResponse {
+                    status: status,
+                    body: String::from_utf8_lossy(&buf[..len]).into_owned(),
+                    retry_after: None
+                }
+            }
         }
     }
 }
@@ -388,7 +701,26 @@ impl fmt::Display for B2Error {
             B2Error::IOError(ref ioe) => ioe.fmt(f),
             B2Error::JsonError(ref jsonerr) => jsonerr.fmt(f),
             B2Error::B2Error(_, ref b2err) => write!(f, "{} ({}): {}", b2err.status, b2err.code, b2err.message),
-            B2Error::ApiInconsistency(ref msg) => write!(f, "{}", msg)
+            B2Error::ApiInconsistency(ref msg) => write!(f, "{}", msg),
+            B2Error::InvalidRequest { field, ref reason } => write!(f, "invalid {}: {}", field, reason),
+            B2Error::BucketMismatch { ref expected, ref actual } =>
+                write!(f, "file is in bucket {}, but bucket {} was expected", actual, expected),
+            B2Error::UnexpectedResponse { status, ref body, .. } =>
+                write!(f, "unexpected response with status {}: {}", status, body),
+            B2Error::FileNotFound { ref file_name, ref reason } =>
+                write!(f, "file not found: {}: {}", file_name, reason),
+            B2Error::Conflict { ref file_name, ref reason } =>
+                write!(f, "conflict on {}: {}", file_name, reason),
+            B2Error::PaginationNotAdvancing { ref cursor, pages } =>
+                write!(f, "listing did not advance past {} after {} pages", cursor, pages),
+            B2Error::UploadNotReplayable(ref err) =>
+                write!(f, "the upload cannot be retried, since its body cannot be sent again: {}",
+                       err),
+            B2Error::UploadTooLarge { size, limit } =>
+                write!(f, "{} bytes exceeds the upload limit of {} bytes", size, limit),
+            B2Error::UnsupportedFileSelector { required } =>
+                write!(f, "this api call requires {} to select the file", required),
+            B2Error::__Nonexhaustive => f.write_str("unknown error")
         }
     }
 }
@@ -0,0 +1,260 @@
+//! This module defines a helper for publishing a set of files, such as a static website, such that
+//! readers never see a half-published state.
+//!
+//! Readers are expected to find the files through a manifest, such as an `index.html` or a json
+//! file listing the other files. The content files are uploaded first, and the manifest is only
+//! uploaded once every content file has been uploaded and verified. If something fails before the
+//! manifest is uploaded, the new file versions are deleted again.
+//!
+//! When the content files are uploaded under their final names, a reader using the old manifest
+//! might see new content files while the publish is in progress. With
+//! [`Naming::ContentAddressed`], every content file is stored under a name containing its sha1,
+//! so the files referenced by the old manifest never change, and concurrent publishes cannot
+//! interfere with each other.
+//!
+//!  [`Naming::ContentAddressed`]: enum.Naming.html#variant.ContentAddressed
+
+use hyper::Client;
+use hyper::mime::Mime;
+use hyper::net::{NetworkConnector, NetworkStream};
+
+use serde_json::Value as JsonValue;
+
+use sha1::Sha1;
+
+use B2Error;
+use raw::authorize::B2Authorization;
+use raw::files::{MoreFileInfo, effective_sha1};
+
+/// A file to be published.
+#[derive(Debug,Clone)]
+pub struct PublishFile {
+    pub name: String,
+    pub content_type: Option<Mime>,
+    pub data: Vec<u8>
+}
+
+/// Specifies the names content files are stored under.
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Naming {
+    /// Every file is stored under its own name.
+    Final,
+    /// Every file is stored under the name `{sha1}/{name}`.
+    ContentAddressed
+}
+impl Naming {
+    /// Returns the name a file with the given name and sha1 is stored under.
+    ///
+    /// ```rust
+    ///use backblaze_b2::publish::Naming;
+    ///
+    ///assert_eq!(Naming::Final.file_name("index.css", "a9993e36"), "index.css");
+    ///assert_eq!(Naming::ContentAddressed.file_name("index.css", "a9993e36"),
+    ///           "a9993e36/index.css");
+    /// ```
+    pub fn file_name(&self, name: &str, sha1: &str) -> String {
+        match *self {
+            Naming::Final => name.to_owned(),
+            Naming::ContentAddressed => format!("{}/{}", sha1, name)
+        }
+    }
+}
+
+/// A file that was uploaded by [`atomic_publish`].
+///
+///  [`atomic_publish`]: fn.atomic_publish.html
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct PublishedFile {
+    /// The name given in the [`PublishFile`].
+    ///
+    ///  [`PublishFile`]: struct.PublishFile.html
+    pub name: String,
+    /// The name the file is stored under.
+    pub file_name: String,
+    pub file_id: String,
+    pub content_sha1: String
+}
+
+/// The result of [`atomic_publish`].
+///
+///  [`atomic_publish`]: fn.atomic_publish.html
+#[derive(Debug)]
+pub struct PublishReport {
+    /// The content files that were uploaded. If the publish was rolled back, these are the files
+    /// that were uploaded before the failure.
+    pub files: Vec<PublishedFile>,
+    /// The manifest, if it was uploaded.
+    pub manifest: Option<PublishedFile>,
+    /// The error that caused the publish to fail.
+    pub error: Option<B2Error>,
+    /// True if the publish failed and the uploaded files were deleted again.
+    pub rolled_back: bool,
+    /// The files that could not be deleted during the rollback.
+    pub rollback_failures: Vec<(PublishedFile, B2Error)>
+}
+impl PublishReport {
+    /// Returns true if the manifest was uploaded, in which case the new files are visible to
+    /// readers.
+    pub fn is_published(&self) -> bool {
+        self.manifest.is_some()
+    }
+}
+
+/// Uploads the `files` to the bucket `bucket_id`, followed by the manifest, as described in the
+/// [module documentation][1]. The manifest is created by calling `manifest` with the uploaded
+/// files, so it can refer to the names the files are stored under.
+///
+/// The sha1 of every file is computed before the upload and passed to backblaze, and the sha1
+/// returned by backblaze is checked against it. If an upload fails, including the upload of the
+/// manifest, every file uploaded by this call is deleted again on a best effort basis, and the
+/// report describes what happened.
+///
+/// ```rust,no_run
+///# extern crate hyper;
+///# extern crate backblaze_b2;
+///# use hyper::Client;
+///# use hyper::net::{NetworkConnector, NetworkStream};
+///# use backblaze_b2::raw::authorize::B2Authorization;
+///# fn main() {}
+///# fn example<C, S>(auth: &B2Authorization, client: &Client, connector: &C)
+///#     where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+///# {
+///use backblaze_b2::publish::{atomic_publish, Naming, PublishFile};
+///
+///let files = vec![PublishFile {
+///    name: "style.css".to_owned(),
+///    content_type: Some("text/css".parse().unwrap()),
+///    data: b"body { color: black; }".to_vec()
+///}];
+///let report = atomic_publish(auth, "bucket id", files, "index.html", |files| {
+///    format!("<link rel=\"stylesheet\" href=\"{}\">", files[0].file_name).into_bytes()
+///}, Naming::ContentAddressed, client, connector);
+///if !report.is_published() {
+///    println!("publish failed: {:?}", report.error);
+///}
+///# }
+/// ```
+///
+///  [1]: index.html
+pub fn atomic_publish<F, C, S>(auth: &B2Authorization, bucket_id: &str, files: Vec<PublishFile>,
+                               manifest_name: &str, manifest: F, naming: Naming,
+                               client: &Client, connector: &C)
+    -> PublishReport
+    where F: FnOnce(&[PublishedFile]) -> Vec<u8>,
+          C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+{
+    let mut upload_auth = None;
+    publish_with(files, manifest_name, manifest, naming, |file_name, file, sha1| {
+        if upload_auth.is_none() {
+            upload_auth = Some(auth.get_upload_url(bucket_id, client)?);
+        }
+        let upload_auth = upload_auth.as_ref().unwrap();
+        let info: MoreFileInfo<JsonValue> = upload_auth.upload_file(
+            &mut &file.data[..], file_name.to_owned(), file.content_type.clone(),
+            file.data.len() as u64, sha1.to_owned(), connector)?;
+        Ok((info.file_id, info.content_sha1))
+    }, |file| auth.delete_file_version(&file.file_name, &file.file_id, client))
+}
+
+/// Performs the steps of [`atomic_publish`] using the given closures to upload and delete files.
+///
+/// The `upload` closure is called with the name to store the file under, the file and its sha1,
+/// and should return the file id and the sha1 reported by backblaze. The `delete` closure is
+/// called for every uploaded file if the publish fails.
+///
+///  [`atomic_publish`]: fn.atomic_publish.html
+pub fn publish_with<F, U, D>(files: Vec<PublishFile>, manifest_name: &str, manifest: F,
+                             naming: Naming, mut upload: U, mut delete: D)
+    -> PublishReport
+    where F: FnOnce(&[PublishedFile]) -> Vec<u8>,
+          U: FnMut(&str, &PublishFile, &str) -> Result<(String, String), B2Error>,
+          D: FnMut(&PublishedFile) -> Result<(), B2Error>
+{
+    let mut report = PublishReport {
+        files: Vec::new(),
+        manifest: None,
+        error: None,
+        rolled_back: false,
+        rollback_failures: Vec::new()
+    };
+    for file in &files {
+        let sha1 = sha1_hex(&file.data);
+        let file_name = naming.file_name(&file.name, &sha1);
+        let result = upload_file(&mut upload, &file_name, file, &sha1).and_then(|published| {
+            report.files.push(published.clone());
+            check_sha1(&published, &sha1)
+        });
+        if let Err(err) = result {
+            report.error = Some(err);
+            rollback(&mut report, &mut delete);
+            return report;
+        }
+    }
+    let manifest_file = PublishFile {
+        name: manifest_name.to_owned(),
+        content_type: None,
+        data: manifest(&report.files)
+    };
+    let sha1 = sha1_hex(&manifest_file.data);
+    let result = upload_file(&mut upload, manifest_name, &manifest_file, &sha1)
+        .and_then(|published| match check_sha1(&published, &sha1) {
+            Ok(()) => Ok(published),
+            Err(err) => {
+                // the manifest must be deleted before the files it refers to
+                if let Err(delete_err) = delete(&published) {
+                    report.rollback_failures.push((published, delete_err));
+                }
+                Err(err)
+            }
+        });
+    match result {
+        Ok(published) => report.manifest = Some(published),
+        Err(err) => {
+            report.error = Some(err);
+            rollback(&mut report, &mut delete);
+        }
+    }
+    report
+}
+
+fn sha1_hex(data: &[u8]) -> String {
+    let mut sha1 = Sha1::new();
+    sha1.update(data);
+    sha1.digest().to_string()
+}
+fn upload_file<U>(upload: &mut U, file_name: &str, file: &PublishFile, sha1: &str)
+    -> Result<PublishedFile, B2Error>
+    where U: FnMut(&str, &PublishFile, &str) -> Result<(String, String), B2Error>
+{
+    let (file_id, content_sha1) = upload(file_name, file, sha1)?;
+    Ok(PublishedFile {
+        name: file.name.clone(),
+        file_name: file_name.to_owned(),
+        file_id: file_id,
+        content_sha1: content_sha1
+    })
+}
+fn check_sha1(file: &PublishedFile, sha1: &str) -> Result<(), B2Error> {
+    let matches = match effective_sha1(&file.content_sha1, None).sha1() {
+        Some(actual) => actual.eq_ignore_ascii_case(sha1),
+        None => false
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(B2Error::ApiInconsistency(format!(
+                    "{} was uploaded with sha1 {}, but backblaze reported {}",
+                    file.file_name, sha1, file.content_sha1)))
+    }
+}
+
+fn rollback<D>(report: &mut PublishReport, delete: &mut D)
+    where D: FnMut(&PublishedFile) -> Result<(), B2Error>
+{
+    report.rolled_back = true;
+    for file in &report.files {
+        if let Err(err) = delete(file) {
+            report.rollback_failures.push((file.clone(), err));
+        }
+    }
+}
@@ -20,6 +20,8 @@ use serde_json;
 use B2Error;
 use B2AuthHeader;
 
+static API_HOST: &str = "https://api.backblazeb2.com";
+
 /// Contains the backblaze id and key needed to authorize access to the backblaze b2 api.
 /// This struct derives [Deserialize][1], so a simple way to read this from a file would be:
 ///
@@ -43,6 +45,39 @@ pub struct B2Credentials {
     pub key: String
 }
 impl B2Credentials {
+    /// Creates a new `B2Credentials`, checking that the id and key are not empty, that they do
+    /// not contain control characters, and that the id does not contain a colon.
+    ///
+    /// ```rust
+    ///use backblaze_b2::raw::authorize::B2Credentials;
+    ///
+    ///let cred = B2Credentials::new("id".to_owned(), "key".to_owned()).unwrap();
+    ///assert_eq!(cred.auth_string(), "Basic aWQ6a2V5");
+    ///
+    ///let err = B2Credentials::new("i:d".to_owned(), "key".to_owned()).unwrap_err();
+    ///assert_eq!(err.field(), Some("id"));
+    ///let err = B2Credentials::new("id".to_owned(), "k\0ey".to_owned()).unwrap_err();
+    ///assert_eq!(err.field(), Some("key"));
+    ///let err = B2Credentials::new("".to_owned(), "key".to_owned()).unwrap_err();
+    ///assert_eq!(err.field(), Some("id"));
+    /// ```
+    pub fn new(id: String, key: String) -> Result<B2Credentials, B2Error> {
+        fn check(field: &'static str, value: &str) -> Result<(), B2Error> {
+            let reason = if value.is_empty() {
+                "must not be empty"
+            } else if value.chars().any(|c| c.is_control()) {
+                "must not contain control characters"
+            } else if field == "id" && value.contains(':') {
+                "must not contain ':'"
+            } else {
+                return Ok(());
+            };
+            Err(B2Error::InvalidRequest { field: field, reason: reason.to_owned() })
+        }
+        check("id", &id)?;
+        check("key", &key)?;
+        Ok(B2Credentials { id: id, key: key })
+    }
     fn id_key(&self) -> String {
         format!("{}:{}", self.id, self.key)
     }
@@ -62,16 +97,67 @@ impl B2Credentials {
     ///  [`is_credentials_issue`]: ../../enum.B2Error.html#method.is_credentials_issue
     ///  [`B2Error`]: ../../enum.B2Error.html
     pub fn authorize(&self, client: &Client) -> Result<B2Authorization,B2Error> {
-        let resp = try!(client.get("https://api.backblazeb2.com/b2api/v1/b2_authorize_account")
+        self.authorize_at(API_HOST, client)
+    }
+    /// This function performs a [b2_authorize_account][1] api call like [`authorize`], but if
+    /// the connection to `api.backblazeb2.com` fails, the hosts in `fallback_hosts` are tried in
+    /// order. The hosts should be given including the scheme, e.g. `https://api.example.com`.
+    ///
+    /// Only failures to reach the host cause the next host to be tried: the host name could not
+    /// be resolved ([`is_dns_error`]), the connection was refused, or the request timed out
+    /// before a response was received ([`is_timeout`]). Hyper does not tell a connect timeout
+    /// apart from a timeout while sending the request or waiting for the response headers, so
+    /// both fall back. Every other error is returned immediately, including an error response,
+    /// since the host was reachable. On success, the host that performed the authorization is
+    /// returned together with the authorization. The urls used by other api calls are taken from
+    /// the authorization, so the fallback hosts are only used for this call.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. If every host fails to
+    /// connect, the error from the last host is returned. Besides the standard non-authorization
+    /// errors, this function can fail with [`is_credentials_issue`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_authorize_account.html
+    ///  [`authorize`]: #method.authorize
+    ///  [`is_dns_error`]: ../../enum.B2Error.html#method.is_dns_error
+    ///  [`is_timeout`]: ../../enum.B2Error.html#method.is_timeout
+    ///  [`is_credentials_issue`]: ../../enum.B2Error.html#method.is_credentials_issue
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    pub fn authorize_with_fallbacks<'a>(&self, fallback_hosts: &[&'a str], client: &Client)
+        -> Result<(B2Authorization, &'a str),B2Error>
+    {
+        let mut hosts = Some(API_HOST).into_iter().chain(fallback_hosts.iter().cloned())
+            .peekable();
+        loop {
+            let host = hosts.next().unwrap();
+            match self.authorize_at(host, client) {
+                Ok(auth) => return Ok((auth, host)),
+                Err(ref err) if is_connect_failure(err) && hosts.peek().is_some() => {},
+                Err(err) => return Err(err)
+            }
+        }
+    }
+    fn authorize_at(&self, host: &str, client: &Client) -> Result<B2Authorization,B2Error> {
+        let url_string: String = format!("{}/b2api/v1/b2_authorize_account", host);
+        let url: &str = &url_string;
+        let resp = try!(client.get(url)
             .header(self.clone())
             .send());
         if resp.status != hyper::status::StatusCode::Ok {
             Err(B2Error::from_response(resp))
         } else {
-            Ok(B2Authorization::from(self.id.clone(), try!(serde_json::from_reader(resp))))
+            let auth = B2Authorization::from(self.id.clone(), try!(serde_json::from_reader(resp)));
+            check_response("b2_authorize_account", auth.validate())?;
+            Ok(auth)
         }
     }
 }
+/// Returns true if the error means that the host could not be reached. The response body is
+/// read by serde, so io errors in the body are json errors and are not considered here.
+fn is_connect_failure(err: &B2Error) -> bool {
+    err.is_dns_error() || err.is_timeout() ||
+        err.get_io_kind() == Some(::std::io::ErrorKind::ConnectionRefused)
+}
 impl HeaderFormat for B2Credentials {
     fn fmt_header(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(self.auth_string().as_str())
@@ -100,7 +186,13 @@ struct B2AuthResponse {
 ///
 ///  [`authorize`]: struct.B2Credentials.html#method.authorize
 ///  [`B2Credentials`]: struct.B2Credentials.html
-#[derive(Debug)]
+///
+/// The authorization can be serialized in order to store it until it expires. Use [`validate`]
+/// after loading a stored authorization.
+///
+///  [`validate`]: #method.validate
+#[derive(Serialize,Deserialize,Clone,Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct B2Authorization {
     pub account_id: String,
     pub authorization_token: String,
@@ -124,5 +216,67 @@ impl B2Authorization {
     pub fn auth_header(&self) -> B2AuthHeader {
         B2AuthHeader(self.authorization_token.clone())
     }
+    /// Checks that the authorization token can be used as a header value, and that the urls
+    /// look like urls. This is useful when an authorization is loaded from storage, since a
+    /// corrupted authorization would otherwise first fail when it is used.
+    ///
+    /// ```rust
+    ///use backblaze_b2::raw::authorize::B2Authorization;
+    ///
+    ///let mut auth = B2Authorization {
+    ///    account_id: "account".to_owned(),
+    ///    authorization_token: "token".to_owned(),
+    ///    api_url: "https://api001.backblazeb2.com".to_owned(),
+    ///    download_url: "https://f001.backblazeb2.com".to_owned(),
+    ///    recommended_part_size: 100_000_000,
+    ///    absolute_minimum_part_size: 5_000_000
+    ///};
+    ///assert!(auth.validate().is_ok());
+    ///
+    ///auth.authorization_token.push('\n');
+    ///assert_eq!(auth.validate().unwrap_err().field(), Some("authorization_token"));
+    /// ```
+    ///
+    /// # Errors
+    /// This function returns an [`InvalidRequest`] error naming the first invalid field.
+    ///
+    ///  [`InvalidRequest`]: ../../enum.B2Error.html#variant.InvalidRequest
+    pub fn validate(&self) -> Result<(), B2Error> {
+        check_header_value("authorization_token", &self.authorization_token)?;
+        check_url("api_url", &self.api_url)?;
+        check_url("download_url", &self.download_url)?;
+        Ok(())
+    }
+}
+/// Turns a failed validation of a response from backblaze into an [`ApiInconsistency`], since
+/// the request was fine and the server sent the invalid value.
+///
+///  [`ApiInconsistency`]: ../../enum.B2Error.html#variant.ApiInconsistency
+pub(crate) fn check_response(api: &str, validation: Result<(), B2Error>) -> Result<(), B2Error> {
+    match validation {
+        Err(B2Error::InvalidRequest { field, reason }) =>
+            Err(B2Error::ApiInconsistency(format!("{} from {} response {}", field, api, reason))),
+        result => result
+    }
+}
+/// Checks that the value is not empty and can be used as a header value.
+pub(crate) fn check_header_value(field: &'static str, value: &str) -> Result<(), B2Error> {
+    let reason = if value.is_empty() {
+        "must not be empty"
+    } else if value.chars().any(|c| c.is_control() || !c.is_ascii()) {
+        "is not a valid header value"
+    } else {
+        return Ok(());
+    };
+    Err(B2Error::InvalidRequest { field: field, reason: reason.to_owned() })
+}
+pub(crate) fn check_url(field: &'static str, value: &str) -> Result<(), B2Error> {
+    let valid = (value.starts_with("https://") || value.starts_with("http://")) &&
+        !value.chars().any(|c| c.is_control() || c.is_whitespace());
+    if valid {
+        Ok(())
+    } else {
+        Err(B2Error::InvalidRequest { field: field, reason: "is not a valid url".to_owned() })
+    }
 }
 
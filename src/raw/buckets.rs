@@ -5,6 +5,7 @@
 //!  [`B2Authorization`]: ../authorize/struct.B2Authorization.html
 
 use std::fmt;
+use std::collections::BTreeMap;
 
 use hyper::{self, Client};
 use hyper::client::Body;
@@ -16,8 +17,18 @@ use serde_json::{self, Value as JsonValue};
 
 use B2Error;
 use raw::authorize::B2Authorization;
+use raw::files::{FileVersion, RetentionMode};
+use raw::encryption::{ServerSideEncryption, DefaultServerSideEncryption};
 
 /// Specifies the type of a bucket on backblaze.
+///
+/// Snapshot buckets are created by backblaze, and cannot be created using [`create_bucket`],
+/// which takes a [`CreatableBucketType`] instead. Deleting files from a snapshot bucket requires
+/// the method [`delete_file_version_allow_snapshot`].
+///
+///  [`create_bucket`]: ../authorize/struct.B2Authorization.html#method.create_bucket
+///  [`CreatableBucketType`]: enum.CreatableBucketType.html
+///  [`delete_file_version_allow_snapshot`]: ../authorize/struct.B2Authorization.html#method.delete_file_version_allow_snapshot
 #[derive(Debug,Clone,Copy,Eq,PartialEq)]
 pub enum BucketType {
     Public, Private, Snapshot
@@ -90,17 +101,277 @@ impl Serialize for BucketType {
     }
 }
 
+/// The bucket types that can be given to a bucket using [`create_bucket`]. This is a
+/// [`BucketType`] without `Snapshot`, since snapshot buckets can only be created by backblaze.
+///
+/// ```rust
+///use backblaze_b2::raw::buckets::{BucketType, CreatableBucketType};
+///
+///assert_eq!(BucketType::from(CreatableBucketType::Private), BucketType::Private);
+///assert_eq!(CreatableBucketType::from_bucket_type(BucketType::Public),
+///           Some(CreatableBucketType::Public));
+///assert_eq!(CreatableBucketType::from_bucket_type(BucketType::Snapshot), None);
+/// ```
+///
+///  [`create_bucket`]: ../authorize/struct.B2Authorization.html#method.create_bucket
+///  [`BucketType`]: enum.BucketType.html
+#[derive(Debug,Clone,Copy,Eq,PartialEq)]
+pub enum CreatableBucketType {
+    Public, Private
+}
+impl CreatableBucketType {
+    /// Returns the creatable type corresponding to the bucket type, or `None` for snapshot
+    /// buckets.
+    pub fn from_bucket_type(bucket_type: BucketType) -> Option<CreatableBucketType> {
+        match bucket_type {
+            BucketType::Public => Some(CreatableBucketType::Public),
+            BucketType::Private => Some(CreatableBucketType::Private),
+            BucketType::Snapshot => None
+        }
+    }
+    /// This function returns the string needed to specify the bucket type to the backblaze api.
+    pub fn as_str(&self) -> &'static str {
+        BucketType::from(*self).as_str()
+    }
+}
+impl From<CreatableBucketType> for BucketType {
+    fn from(bucket_type: CreatableBucketType) -> BucketType {
+        match bucket_type {
+            CreatableBucketType::Public => BucketType::Public,
+            CreatableBucketType::Private => BucketType::Private
+        }
+    }
+}
+impl Serialize for CreatableBucketType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where S: Serializer
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
 /// This struct contains a lifecycle rule as specified in the [backblaze b2
 /// documentation](https://www.backblaze.com/b2/docs/lifecycle_rules.html).
-#[derive(Serialize,Deserialize,Debug,Clone)]
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct LifecycleRule {
-    days_from_uploading_to_hiding: Option<u32>,
-    days_from_hiding_to_deleting: Option<u32>,
-    file_name_prefix: String
+    pub days_from_uploading_to_hiding: Option<u32>,
+    pub days_from_hiding_to_deleting: Option<u32>,
+    pub file_name_prefix: String
+}
+
+const MILLIS_PER_DAY: u64 = 24 * 60 * 60 * 1000;
+
+/// Specifies what a lifecycle rule does to a file version.
+#[derive(Debug,Clone,Copy,Eq,PartialEq)]
+pub enum LifecycleActionKind {
+    Hide, Delete
+}
+/// A single action that a lifecycle rule will perform on a file version.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct LifecycleAction {
+    pub file_name: String,
+    /// The id of the affected version. This is the id of the hide marker if a hide marker is
+    /// deleted.
+    pub file_id: String,
+    pub kind: LifecycleActionKind,
+    /// The time in milliseconds since the epoch at which backblaze performs the action at the
+    /// earliest. Backblaze applies lifecycle rules once a day, so the action may happen up to a
+    /// day later.
+    pub at: u64
+}
+/// The actions a single lifecycle rule will perform, as computed by [`lifecycle_preview`].
+///
+///  [`lifecycle_preview`]: fn.lifecycle_preview.html
+#[derive(Debug,Clone)]
+pub struct LifecycleRulePreview<'a> {
+    pub rule: &'a LifecycleRule,
+    /// The actions of this rule, sorted by time.
+    pub actions: Vec<LifecycleAction>
+}
+
+/// Computes the actions the lifecycle `rules` would perform on the file `versions` before the
+/// time `until`, given in milliseconds since the epoch. The versions must be in the order used by
+/// [`iter_file_versions`], that is sorted by file name with the newest version of each name
+/// first, and the listing must not use a delimiter. No api calls besides those made by the
+/// iterator are performed, so this can be used to preview new rules before applying them with
+/// [`create_bucket`].
+///
+/// The versions are consumed one at a time, so memory use only grows with the number of actions
+/// and not with the size of the bucket. Folders and unfinished large files are ignored.
+///
+/// Each file name is governed by the rule with the longest prefix matching the name, so a rule
+/// with an empty prefix applies to every file not matched by another rule. The newest version of a
+/// file is hidden `days_from_uploading_to_hiding` days after it was uploaded, and older versions
+/// and hide markers are deleted `days_from_hiding_to_deleting` days after they were replaced by a
+/// newer version. Deletions that follow from a predicted hide are included.
+///
+/// ```rust
+///#[macro_use]
+///extern crate serde_json;
+///# extern crate backblaze_b2;
+///use backblaze_b2::raw::buckets::*;
+///use backblaze_b2::raw::files::FileVersion;
+///
+///# fn main() {
+///const DAY: u64 = 24 * 60 * 60 * 1000;
+///let file = |name: &str, id: &str, day: u64| -> FileVersion {
+///    serde_json::from_value(json!({
+///        "action": "upload", "fileId": id, "fileName": name, "contentLength": 1,
+///        "contentType": "text/plain", "contentSha1": "da39a3ee5e6b4b0d3255bfef95601890afd80709",
+///        "fileInfo": {}, "uploadTimestamp": day * DAY
+///    })).unwrap()
+///};
+///let versions = vec![
+///    file("logs/a", "2", 5), file("logs/a", "1", 0),
+///    file("logs/keep/b", "3", 0),
+///    file("other", "4", 0),
+///];
+///let rules = vec![
+///    LifecycleRule {
+///        file_name_prefix: "".to_owned(),
+///        days_from_uploading_to_hiding: Some(30),
+///        days_from_hiding_to_deleting: None,
+///    },
+///    LifecycleRule {
+///        file_name_prefix: "logs/".to_owned(),
+///        days_from_uploading_to_hiding: None,
+///        days_from_hiding_to_deleting: Some(1),
+///    },
+///    LifecycleRule {
+///        file_name_prefix: "logs/keep/".to_owned(),
+///        days_from_uploading_to_hiding: None,
+///        days_from_hiding_to_deleting: None,
+///    },
+///];
+///
+///let preview = lifecycle_preview(versions.iter().cloned().map(Ok), &rules, 10 * DAY).unwrap();
+///// The catch-all rule hides "other" on day 30, which is after day 10.
+///assert_eq!(preview[0].actions.len(), 0);
+///// The old version of "logs/a" is deleted a day after it was replaced.
+///assert_eq!(preview[1].actions.len(), 1);
+///assert_eq!(preview[1].actions[0].file_id, "1");
+///assert_eq!(preview[1].actions[0].kind, LifecycleActionKind::Delete);
+///assert_eq!(preview[1].actions[0].at, 6 * DAY);
+///// The most specific rule keeps "logs/keep/b".
+///assert_eq!(preview[2].actions.len(), 0);
+///
+///let preview = lifecycle_preview(versions.into_iter().map(Ok), &rules, 40 * DAY).unwrap();
+///assert_eq!(preview[0].actions.len(), 1);
+///assert_eq!(preview[0].actions[0].file_name, "other");
+///assert_eq!(preview[0].actions[0].kind, LifecycleActionKind::Hide);
+///# }
+/// ```
+///
+/// The versions of a bucket can be previewed directly from the api:
+///
+/// ```rust,no_run
+///# extern crate hyper;
+///# extern crate serde_json;
+///# extern crate backblaze_b2;
+///# use hyper::Client;
+///# use backblaze_b2::B2Error;
+///# use backblaze_b2::raw::authorize::B2Authorization;
+///# fn main() {}
+///# fn example(auth: &B2Authorization, client: &Client, now: u64) -> Result<(), B2Error> {
+///use backblaze_b2::raw::buckets::{lifecycle_preview, LifecycleRule};
+///
+///let rules: Vec<LifecycleRule> = Vec::new();
+///let versions = auth.iter_file_versions::<serde_json::Value>("bucket id", 1000, None, None, client);
+///let preview = lifecycle_preview(versions, &rules, now + 30 * 24 * 60 * 60 * 1000)?;
+///for rule in preview {
+///    for action in rule.actions {
+///        println!("{:?} {} at {}", action.kind, action.file_name, action.at);
+///    }
+///}
+///# Ok(())
+///# }
+/// ```
+///
+/// # Errors
+/// The first error returned by `versions` is returned.
+///
+///  [`iter_file_versions`]: ../authorize/struct.B2Authorization.html#method.iter_file_versions
+///  [`create_bucket`]: ../authorize/struct.B2Authorization.html#method.create_bucket
+pub fn lifecycle_preview<'a, I, InfoType>(versions: I, rules: &'a [LifecycleRule], until: u64)
+    -> Result<Vec<LifecycleRulePreview<'a>>, B2Error>
+    where I: IntoIterator<Item=Result<FileVersion<InfoType>, B2Error>>
+{
+    let mut previews: Vec<LifecycleRulePreview> = rules.iter().map(|rule| LifecycleRulePreview {
+        rule: rule,
+        actions: Vec::new()
+    }).collect();
+    // the name of the current group, its rule, and the upload time of the previous version in
+    // the group
+    let mut current: Option<(String, Option<usize>, u64)> = None;
+    for version in versions {
+        let (file_name, file_id, time, is_hide) = match version? {
+            FileVersion::File(file) =>
+                (file.file_name, file.file_id, file.upload_timestamp, false),
+            FileVersion::HideMarker(hide) =>
+                (hide.file_name, hide.file_id, hide.upload_timestamp, true),
+            FileVersion::UnfinishedLargeFile(_) | FileVersion::Folder(_) => continue
+        };
+        let replaced_at = match current {
+            Some((ref name, _, ref mut previous)) if *name == file_name => {
+                let replaced_at = *previous;
+                *previous = time;
+                Some(replaced_at)
+            },
+            _ => None
+        };
+        if replaced_at.is_none() {
+            let rule_index = rules.iter().enumerate()
+                .filter(|&(_, rule)| file_name.starts_with(rule.file_name_prefix.as_str()))
+                .max_by_key(|&(_, rule)| rule.file_name_prefix.len())
+                .map(|(i, _)| i);
+            current = Some((file_name.clone(), rule_index, time));
+        }
+        let rule_index = match current {
+            Some((_, Some(rule_index), _)) => rule_index,
+            _ => continue
+        };
+        let rule = &rules[rule_index];
+        let delete_after = rule.days_from_hiding_to_deleting.map(|d| d as u64 * MILLIS_PER_DAY);
+
+        let mut planned: Vec<(LifecycleActionKind, u64)> = Vec::new();
+        match replaced_at {
+            Some(replaced_at) => if let Some(delete_after) = delete_after {
+                planned.push((LifecycleActionKind::Delete, replaced_at + delete_after));
+            },
+            None if is_hide => if let Some(delete_after) = delete_after {
+                planned.push((LifecycleActionKind::Delete, time + delete_after));
+            },
+            None => if let Some(days) = rule.days_from_uploading_to_hiding {
+                let hidden_at = time + days as u64 * MILLIS_PER_DAY;
+                planned.push((LifecycleActionKind::Hide, hidden_at));
+                if let Some(delete_after) = delete_after {
+                    planned.push((LifecycleActionKind::Delete, hidden_at + delete_after));
+                }
+            }
+        }
+        let actions = &mut previews[rule_index].actions;
+        for (kind, at) in planned {
+            if at < until {
+                actions.push(LifecycleAction {
+                    file_name: file_name.clone(),
+                    file_id: file_id.clone(),
+                    kind: kind,
+                    at: at
+                });
+            }
+        }
+    }
+    for preview in &mut previews {
+        preview.actions.sort_by_key(|action| action.at);
+    }
+    Ok(previews)
 }
 
 /// This function contains various information about a backblaze bucket.
+///
+/// The `Display` implementation prints a one-line summary without the bucket info and lifecycle
+/// rules, which is usually preferable to `Debug` when logging.
 #[derive(Serialize,Deserialize,Debug,Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Bucket<InfoType=JsonValue> {
@@ -110,7 +381,615 @@ pub struct Bucket<InfoType=JsonValue> {
     pub bucket_type: BucketType,
     pub bucket_info: InfoType,
     pub lifecycle_rules: Vec<LifecycleRule>,
-    pub revision: u32
+    pub revision: u32,
+    /// The default encryption of new files in the bucket. This is missing if the server did not
+    /// send it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_server_side_encryption: Option<DefaultServerSideEncryption>,
+    /// The file lock configuration of the bucket. This is missing if the server did not send it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_lock_configuration: Option<FileLockConfiguration>,
+    /// The replication configuration of the bucket. This is missing if the server did not send
+    /// it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replication_configuration: Option<ReplicationConfigurationSetting>,
+    /// Options enabled on the bucket by backblaze, such as `s3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Vec<String>>
+}
+/// The period of a default retention, such as 30 days. The unit is `days` or `years`.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPeriod {
+    pub duration: u32,
+    pub unit: String
+}
+/// The retention applied to new files in a bucket with file lock enabled.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultRetention {
+    pub mode: Option<RetentionMode>,
+    pub period: Option<RetentionPeriod>
+}
+/// The file lock settings of a bucket.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileLockSettings {
+    pub is_file_lock_enabled: bool,
+    pub default_retention: Option<DefaultRetention>
+}
+/// The file lock configuration of a bucket as returned by backblaze. If the authorization cannot
+/// read the configuration, `value` is missing.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileLockConfiguration {
+    pub is_client_authorized_to_read: bool,
+    pub value: Option<FileLockSettings>
+}
+/// A rule replicating files from a source bucket to a destination bucket.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationRule {
+    pub replication_rule_name: String,
+    pub destination_bucket_id: String,
+    pub file_name_prefix: String,
+    pub include_existing_files: bool,
+    pub is_enabled: bool,
+    pub priority: u32
+}
+/// The replication rules of a bucket that is the source of a replication.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationSource {
+    pub replication_rules: Vec<ReplicationRule>,
+    /// The id of the application key used to read the files in the source bucket.
+    pub source_application_key_id: String
+}
+/// The settings of a bucket that is the destination of a replication.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationDestination {
+    /// Maps the application key ids of the source accounts to application key ids used to write
+    /// to the destination bucket.
+    pub source_to_destination_key_mapping: BTreeMap<String, String>
+}
+/// The replication configuration of a bucket. A bucket can be both a source and a destination.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq,Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationConfiguration {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_replication_source: Option<ReplicationSource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_replication_destination: Option<ReplicationDestination>
+}
+/// The replication configuration of a bucket as returned by backblaze. If the authorization cannot
+/// read the configuration, `value` is missing.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplicationConfigurationSetting {
+    pub is_client_authorized_to_read: bool,
+    pub value: Option<ReplicationConfiguration>
+}
+/// Optional settings of a [`CreateBucket`] call. The default value sets none of them.
+///
+///  [`CreateBucket`]: struct.CreateBucket.html
+#[derive(Debug,Clone,Default)]
+pub struct CreateBucketOptions {
+    pub default_server_side_encryption: Option<ServerSideEncryption>,
+    pub file_lock_enabled: bool,
+    pub replication_configuration: Option<ReplicationConfiguration>
+}
+/// A builder for a [b2_create_bucket][1] api call, which avoids mixing up the many optional
+/// arguments. By default the bucket has an empty bucket info and no lifecycle rules, and none of
+/// the settings of [`CreateBucketOptions`] are set. The method [`create_bucket`] is a shorthand
+/// for this builder.
+///
+/// ```rust,no_run
+///#[macro_use]
+///extern crate serde_json;
+///# extern crate hyper;
+///# extern crate backblaze_b2;
+///# use hyper::Client;
+///# use backblaze_b2::B2Error;
+///# use backblaze_b2::raw::authorize::B2Authorization;
+///# fn main() {}
+///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+///use backblaze_b2::raw::buckets::{Bucket, CreatableBucketType, CreateBucket, LifecycleRule};
+///
+///// Delete old versions of files in the logs folder a week after they are replaced.
+///let bucket: Bucket = CreateBucket::new("my-logs", CreatableBucketType::Private)
+///    .bucket_info(json!({"owner": "ops"}))
+///    .lifecycle_rules(vec![LifecycleRule {
+///        file_name_prefix: "logs/".to_owned(),
+///        days_from_uploading_to_hiding: None,
+///        days_from_hiding_to_deleting: Some(7)
+///    }])
+///    .file_lock_enabled(true)
+///    .send(auth, client)?;
+///println!("created {}", bucket);
+///# Ok(())
+///# }
+/// ```
+///
+///  [1]: https://www.backblaze.com/b2/docs/b2_create_bucket.html
+///  [`create_bucket`]: ../authorize/struct.B2Authorization.html#method.create_bucket
+///  [`CreateBucketOptions`]: struct.CreateBucketOptions.html
+#[derive(Debug,Clone)]
+pub struct CreateBucket<'a, InfoType=JsonValue> {
+    bucket_name: &'a str,
+    bucket_type: CreatableBucketType,
+    bucket_info: InfoType,
+    cache_control: Option<&'a str>,
+    lifecycle_rules: Vec<LifecycleRule>,
+    options: CreateBucketOptions
+}
+impl<'a> CreateBucket<'a, JsonValue> {
+    /// Creates a bucket named `bucket_name` of the type `bucket_type`.
+    pub fn new(bucket_name: &'a str, bucket_type: CreatableBucketType)
+        -> CreateBucket<'a, JsonValue>
+    {
+        CreateBucket {
+            bucket_name: bucket_name,
+            bucket_type: bucket_type,
+            bucket_info: JsonValue::Object(serde_json::map::Map::new()),
+            cache_control: None,
+            lifecycle_rules: Vec::new(),
+            options: CreateBucketOptions::default()
+        }
+    }
+}
+impl<'a, IT> CreateBucket<'a, IT> {
+    /// Sets the bucket info.
+    pub fn bucket_info<T>(self, bucket_info: T) -> CreateBucket<'a, T> {
+        CreateBucket {
+            bucket_name: self.bucket_name,
+            bucket_type: self.bucket_type,
+            bucket_info: bucket_info,
+            cache_control: self.cache_control,
+            lifecycle_rules: self.lifecycle_rules,
+            options: self.options
+        }
+    }
+    /// Stores `value` under the `Cache-Control` key of the bucket info when the bucket is
+    /// created, see [`set_cache_control`]. The value is checked by [`send`].
+    ///
+    ///  [`set_cache_control`]: fn.set_cache_control.html
+    ///  [`send`]: #method.send
+    pub fn cache_control(mut self, value: &'a str) -> CreateBucket<'a, IT> {
+        self.cache_control = Some(value);
+        self
+    }
+    /// Sets the lifecycle rules of the bucket.
+    pub fn lifecycle_rules(mut self, lifecycle_rules: Vec<LifecycleRule>) -> CreateBucket<'a, IT> {
+        self.lifecycle_rules = lifecycle_rules;
+        self
+    }
+    /// Sets the default encryption of files uploaded to the bucket.
+    pub fn default_server_side_encryption(mut self, encryption: ServerSideEncryption)
+        -> CreateBucket<'a, IT>
+    {
+        self.options.default_server_side_encryption = Some(encryption);
+        self
+    }
+    /// Enables file lock on the bucket. File lock cannot be disabled once it is enabled, and it
+    /// allows files to be protected from deletion using [`update_file_retention`] and
+    /// [`update_file_legal_hold`].
+    ///
+    ///  [`update_file_retention`]: ../authorize/struct.B2Authorization.html#method.update_file_retention
+    ///  [`update_file_legal_hold`]: ../authorize/struct.B2Authorization.html#method.update_file_legal_hold
+    pub fn file_lock_enabled(mut self, file_lock_enabled: bool) -> CreateBucket<'a, IT> {
+        self.options.file_lock_enabled = file_lock_enabled;
+        self
+    }
+    /// Sets the replication configuration of the bucket.
+    pub fn replication_configuration(mut self, configuration: ReplicationConfiguration)
+        -> CreateBucket<'a, IT>
+    {
+        self.options.replication_configuration = Some(configuration);
+        self
+    }
+    /// Performs the [b2_create_bucket][1] api call.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_maximum_bucket_limit`],
+    /// [`is_duplicate_bucket_name`] and [`is_invalid_bucket_name`]. If a [`cache_control`] value
+    /// is set, the errors of [`set_cache_control`] are returned before contacting backblaze.
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_create_bucket.html
+    ///  [`cache_control`]: #method.cache_control
+    ///  [`set_cache_control`]: fn.set_cache_control.html
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_maximum_bucket_limit`]: ../../enum.B2Error.html#method.is_maximum_bucket_limit
+    ///  [`is_duplicate_bucket_name`]: ../../enum.B2Error.html#method.is_duplicate_bucket_name
+    ///  [`is_invalid_bucket_name`]: ../../enum.B2Error.html#method.is_invalid_bucket_name
+    pub fn send(self, auth: &B2Authorization, client: &Client) -> Result<Bucket<IT>, B2Error>
+        where for <'de> IT: Serialize + Deserialize<'de>
+    {
+        let url_string: String = format!("{}/b2api/v1/b2_create_bucket", auth.api_url);
+        let url: &str = &url_string;
+
+        let mut bucket_info = try!(serde_json::to_value(self.bucket_info));
+        if let Some(value) = self.cache_control {
+            try!(set_cache_control(&mut bucket_info, value));
+        }
+        let body = try!(serde_json::to_string(&CreateBucketRequest {
+            account_id: &auth.account_id,
+            bucket_name: self.bucket_name,
+            bucket_type: self.bucket_type,
+            bucket_info: bucket_info,
+            lifecycle_rules: self.lifecycle_rules,
+            default_server_side_encryption: self.options.default_server_side_encryption.as_ref(),
+            file_lock_enabled: if self.options.file_lock_enabled { Some(true) } else { None },
+            replication_configuration: self.options.replication_configuration.as_ref()
+        }));
+
+        let resp = try!(client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(auth.auth_header())
+            .send());
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            let bucket: Bucket<IT> = try!(serde_json::from_reader(resp));
+            Ok(bucket)
+        }
+    }
+}
+/// A builder for a [b2_update_bucket][1] api call. Only the settings given to the builder are
+/// sent, and the other settings of the bucket are left unchanged. The method [`update_bucket`]
+/// is a shorthand for this builder.
+///
+/// ```rust,no_run
+///#[macro_use]
+///extern crate serde_json;
+///# extern crate hyper;
+///# extern crate backblaze_b2;
+///# use hyper::Client;
+///# use backblaze_b2::B2Error;
+///# use backblaze_b2::raw::authorize::B2Authorization;
+///# fn main() {}
+///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+///use backblaze_b2::raw::buckets::{Bucket, CreatableBucketType, UpdateBucket};
+///
+///let bucket: Bucket = auth.list_buckets(client)?.remove(0);
+///// Fails with a conflict if someone else updated the bucket since it was listed.
+///let bucket: Bucket = UpdateBucket::new(&bucket.bucket_id)
+///    .bucket_type(CreatableBucketType::Public)
+///    .bucket_info(json!({"owner": "ops"}))
+///    .if_revision_is(bucket.revision)
+///    .send(auth, client)?;
+///println!("updated {}", bucket);
+///# Ok(())
+///# }
+/// ```
+///
+///  [1]: https://www.backblaze.com/b2/docs/b2_update_bucket.html
+///  [`update_bucket`]: ../authorize/struct.B2Authorization.html#method.update_bucket
+#[derive(Debug,Clone)]
+pub struct UpdateBucket<'a, InfoType=JsonValue> {
+    bucket_id: &'a str,
+    bucket_type: Option<CreatableBucketType>,
+    bucket_info: Option<InfoType>,
+    cache_control: Option<&'a str>,
+    lifecycle_rules: Option<Vec<LifecycleRule>>,
+    default_server_side_encryption: Option<ServerSideEncryption>,
+    default_retention: Option<DefaultRetention>,
+    file_lock_enabled: bool,
+    replication_configuration: Option<ReplicationConfiguration>,
+    if_revision_is: Option<u32>
+}
+impl<'a> UpdateBucket<'a, JsonValue> {
+    /// Updates the bucket with the id `bucket_id`. The builder initially changes nothing.
+    pub fn new(bucket_id: &'a str) -> UpdateBucket<'a, JsonValue> {
+        UpdateBucket {
+            bucket_id: bucket_id,
+            bucket_type: None,
+            bucket_info: None,
+            cache_control: None,
+            lifecycle_rules: None,
+            default_server_side_encryption: None,
+            default_retention: None,
+            file_lock_enabled: false,
+            replication_configuration: None,
+            if_revision_is: None
+        }
+    }
+}
+impl<'a, IT> UpdateBucket<'a, IT> {
+    /// Changes the type of the bucket.
+    pub fn bucket_type(mut self, bucket_type: CreatableBucketType) -> UpdateBucket<'a, IT> {
+        self.bucket_type = Some(bucket_type);
+        self
+    }
+    /// Replaces the bucket info.
+    pub fn bucket_info<T>(self, bucket_info: T) -> UpdateBucket<'a, T> {
+        UpdateBucket {
+            bucket_id: self.bucket_id,
+            bucket_type: self.bucket_type,
+            bucket_info: Some(bucket_info),
+            cache_control: self.cache_control,
+            lifecycle_rules: self.lifecycle_rules,
+            default_server_side_encryption: self.default_server_side_encryption,
+            default_retention: self.default_retention,
+            file_lock_enabled: self.file_lock_enabled,
+            replication_configuration: self.replication_configuration,
+            if_revision_is: self.if_revision_is
+        }
+    }
+    /// Stores `value` under the `Cache-Control` key of the new bucket info, see
+    /// [`set_cache_control`]. Backblaze replaces the whole bucket info, so if no bucket info is
+    /// given, the bucket info is replaced by one containing only this key. The value is checked
+    /// by [`send`].
+    ///
+    ///  [`set_cache_control`]: fn.set_cache_control.html
+    ///  [`send`]: #method.send
+    pub fn cache_control(mut self, value: &'a str) -> UpdateBucket<'a, IT> {
+        self.cache_control = Some(value);
+        self
+    }
+    /// Replaces the lifecycle rules of the bucket.
+    pub fn lifecycle_rules(mut self, lifecycle_rules: Vec<LifecycleRule>) -> UpdateBucket<'a, IT> {
+        self.lifecycle_rules = Some(lifecycle_rules);
+        self
+    }
+    /// Changes the default encryption of files uploaded to the bucket.
+    pub fn default_server_side_encryption(mut self, encryption: ServerSideEncryption)
+        -> UpdateBucket<'a, IT>
+    {
+        self.default_server_side_encryption = Some(encryption);
+        self
+    }
+    /// Changes the retention applied to new files. This requires file lock to be enabled on the
+    /// bucket.
+    pub fn default_retention(mut self, retention: DefaultRetention) -> UpdateBucket<'a, IT> {
+        self.default_retention = Some(retention);
+        self
+    }
+    /// Enables file lock on the bucket. File lock cannot be disabled once it is enabled, so
+    /// passing false leaves the bucket unchanged.
+    pub fn file_lock_enabled(mut self, file_lock_enabled: bool) -> UpdateBucket<'a, IT> {
+        self.file_lock_enabled = file_lock_enabled;
+        self
+    }
+    /// Replaces the replication configuration of the bucket.
+    pub fn replication_configuration(mut self, configuration: ReplicationConfiguration)
+        -> UpdateBucket<'a, IT>
+    {
+        self.replication_configuration = Some(configuration);
+        self
+    }
+    /// Only updates the bucket if its revision is `revision`, such that changes made by someone
+    /// else since the bucket was read are not overwritten.
+    pub fn if_revision_is(mut self, revision: u32) -> UpdateBucket<'a, IT> {
+        self.if_revision_is = Some(revision);
+        self
+    }
+    /// Performs the [b2_update_bucket][1] api call.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_bucket_not_found`], and with [`is_conflict`] if
+    /// the revision given to [`if_revision_is`] does not match. If a [`cache_control`] value is
+    /// set, the errors of [`set_cache_control`] are returned before contacting backblaze.
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_update_bucket.html
+    ///  [`if_revision_is`]: #method.if_revision_is
+    ///  [`cache_control`]: #method.cache_control
+    ///  [`set_cache_control`]: fn.set_cache_control.html
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_conflict`]: ../../enum.B2Error.html#method.is_conflict
+    pub fn send(self, auth: &B2Authorization, client: &Client) -> Result<Bucket<IT>, B2Error>
+        where for <'de> IT: Serialize + Deserialize<'de>
+    {
+        let url_string: String = format!("{}/b2api/v1/b2_update_bucket", auth.api_url);
+        let url: &str = &url_string;
+
+        let mut bucket_info = match self.bucket_info {
+            Some(bucket_info) => Some(try!(serde_json::to_value(bucket_info))),
+            None => None
+        };
+        if let Some(value) = self.cache_control {
+            let bucket_info = bucket_info.get_or_insert(JsonValue::Null);
+            try!(set_cache_control(bucket_info, value));
+        }
+        let body = try!(serde_json::to_string(&UpdateBucketRequest {
+            account_id: &auth.account_id,
+            bucket_id: self.bucket_id,
+            bucket_type: self.bucket_type,
+            bucket_info: bucket_info,
+            lifecycle_rules: self.lifecycle_rules,
+            default_server_side_encryption: self.default_server_side_encryption.as_ref(),
+            default_retention: self.default_retention.as_ref(),
+            file_lock_enabled: if self.file_lock_enabled { Some(true) } else { None },
+            replication_configuration: self.replication_configuration.as_ref(),
+            if_revision_is: self.if_revision_is
+        }));
+
+        let resp = try!(client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(auth.auth_header())
+            .send());
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            let bucket: Bucket<IT> = try!(serde_json::from_reader(resp));
+            Ok(bucket)
+        }
+    }
+}
+impl<InfoType> fmt::Display for Bucket<InfoType> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}, {}, revision {})", self.bucket_name, self.bucket_id,
+               self.bucket_type.as_str(), self.revision)
+    }
+}
+impl<InfoType> Bucket<InfoType> {
+    /// Returns true if file lock is enabled on the bucket. This returns false if the file lock
+    /// configuration is missing or cannot be read by the authorization.
+    pub fn is_file_lock_enabled(&self) -> bool {
+        match self.file_lock_configuration {
+            Some(FileLockConfiguration { value: Some(ref settings), .. }) =>
+                settings.is_file_lock_enabled,
+            _ => false
+        }
+    }
+    /// Returns true if this is a snapshot bucket. See [`BucketType`] for the operations that
+    /// behave differently on snapshot buckets.
+    ///
+    ///  [`BucketType`]: enum.BucketType.html
+    pub fn is_snapshot(&self) -> bool {
+        self.bucket_type == BucketType::Snapshot
+    }
+    /// Returns true if both values describe the same bucket, that is, if they have the same
+    /// account id and bucket id.
+    pub fn same_identity<IT>(&self, other: &Bucket<IT>) -> bool {
+        self.account_id == other.account_id && self.bucket_id == other.bucket_id
+    }
+    /// Returns true if the two buckets have the same name, type, info, lifecycle rules,
+    /// encryption, file lock, replication and options. The ids and the revision are not
+    /// compared, so this can compare a bucket against a template.
+    pub fn same_settings(&self, other: &Bucket<InfoType>) -> bool
+        where InfoType: PartialEq
+    {
+        self.bucket_name == other.bucket_name
+            && self.bucket_type == other.bucket_type
+            && self.bucket_info == other.bucket_info
+            && self.lifecycle_rules == other.lifecycle_rules
+            && self.default_server_side_encryption == other.default_server_side_encryption
+            && self.file_lock_configuration == other.file_lock_configuration
+            && self.replication_configuration == other.replication_configuration
+            && self.options == other.options
+    }
+    /// Returns true if this value describes a newer revision of the bucket than `other`, and
+    /// `None` if the two values describe different buckets. Backblaze increases the revision
+    /// every time the bucket is updated, so this detects changes the settings don't show.
+    ///
+    /// ```rust
+    ///extern crate serde_json;
+    ///# extern crate backblaze_b2;
+    ///use backblaze_b2::raw::buckets::Bucket;
+    ///
+    ///# fn main() {
+    ///let old: Bucket = serde_json::from_str(r#"{
+    ///    "accountId": "account", "bucketId": "bucket", "bucketName": "my-bucket",
+    ///    "bucketType": "allPrivate", "bucketInfo": {}, "lifecycleRules": [], "revision": 1
+    ///}"#).unwrap();
+    ///let mut new = old.clone();
+    ///new.revision = 2;
+    ///assert_eq!(new.is_newer_than(&old), Some(true));
+    ///assert_eq!(old.is_newer_than(&new), Some(false));
+    ///assert!(new.same_settings(&old));
+    ///
+    ///new.bucket_id = "other bucket".to_owned();
+    ///assert_eq!(new.is_newer_than(&old), None);
+    ///# }
+    /// ```
+    pub fn is_newer_than<IT>(&self, other: &Bucket<IT>) -> Option<bool> {
+        if self.same_identity(other) {
+            Some(self.revision > other.revision)
+        } else {
+            None
+        }
+    }
+}
+impl Bucket<JsonValue> {
+    /// Returns the `Cache-Control` value of the bucket info. Backblaze sends this value as the
+    /// `Cache-Control` header when files in a public bucket are downloaded.
+    ///
+    /// See [`set_cache_control`] for an example.
+    ///
+    ///  [`set_cache_control`]: fn.set_cache_control.html
+    pub fn cache_control(&self) -> Option<&str> {
+        self.bucket_info.get(CACHE_CONTROL).and_then(|value| value.as_str())
+    }
+}
+
+static CACHE_CONTROL: &str = "Cache-Control";
+/// Directives that take a number of seconds as argument.
+static CACHE_CONTROL_SECONDS: [&str; 4] =
+    ["max-age", "s-maxage", "stale-while-revalidate", "stale-if-error"];
+
+/// Stores `value` under the `Cache-Control` key of the bucket info, which can then be passed to
+/// [`create_bucket`]. If the bucket info is null, it is replaced by an object. The builders
+/// [`CreateBucket`] and [`UpdateBucket`] call this function for their `cache_control` setting.
+///
+/// The value is checked to be a comma separated list of directives, and directives such as
+/// `max-age` must have a number of seconds as argument. Directives unknown to this library are
+/// accepted.
+///
+/// ```rust
+///#[macro_use]
+///extern crate serde_json;
+///# extern crate backblaze_b2;
+///use backblaze_b2::raw::buckets::*;
+///
+///# fn main() {
+///let mut info = json!({"abc": "test"});
+///set_cache_control(&mut info, "public, max-age=86400").unwrap();
+///assert_eq!(info, json!({"abc": "test", "Cache-Control": "public, max-age=86400"}));
+///
+///assert!(set_cache_control(&mut info, "max-age=one day").is_err());
+///assert!(set_cache_control(&mut info, "public,,no-store").is_err());
+///
+///let bucket: Bucket = serde_json::from_value(json!({
+///    "accountId": "010203040506", "bucketId": "4a48fe8875c6214145260818",
+///    "bucketName": "Kitten-Videos", "bucketType": "allPublic", "bucketInfo": info,
+///    "lifecycleRules": [], "revision": 1
+///})).unwrap();
+///assert_eq!(bucket.cache_control(), Some("public, max-age=86400"));
+///# }
+/// ```
+///
+/// # Errors
+/// Fails with [`InvalidRequest`] if the value is not a valid `Cache-Control` header, or if the
+/// bucket info is neither an object nor null.
+///
+///  [`create_bucket`]: ../authorize/struct.B2Authorization.html#method.create_bucket
+///  [`CreateBucket`]: struct.CreateBucket.html
+///  [`UpdateBucket`]: struct.UpdateBucket.html
+///  [`InvalidRequest`]: ../../enum.B2Error.html#variant.InvalidRequest
+pub fn set_cache_control(bucket_info: &mut JsonValue, value: &str) -> Result<(), B2Error> {
+    for directive in value.split(',') {
+        let mut parts = directive.trim().splitn(2, '=');
+        let name = parts.next().unwrap_or("");
+        let is_token = |s: &str| !s.is_empty() && s.bytes().all(|b| {
+            b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+        });
+        if !is_token(name) {
+            return Err(B2Error::InvalidRequest {
+                field: "cache_control",
+                reason: format!("{:?} is not a valid directive", directive.trim())
+            });
+        }
+        let argument = parts.next();
+        if CACHE_CONTROL_SECONDS.contains(&name.to_ascii_lowercase().as_str()) {
+            let valid = match argument {
+                Some(seconds) => !seconds.is_empty() && seconds.bytes().all(|b| b.is_ascii_digit()),
+                None => false
+            };
+            if !valid {
+                return Err(B2Error::InvalidRequest {
+                    field: "cache_control",
+                    reason: format!("{} requires a number of seconds", name)
+                });
+            }
+        }
+    }
+    if bucket_info.is_null() {
+        *bucket_info = JsonValue::Object(serde_json::Map::new());
+    }
+    match bucket_info.as_object_mut() {
+        Some(map) => {
+            map.insert(CACHE_CONTROL.to_owned(), JsonValue::String(value.to_owned()));
+            Ok(())
+        },
+        None => Err(B2Error::InvalidRequest {
+            field: "bucket_info",
+            reason: "the bucket info is not a json object".to_owned()
+        })
+    }
 }
 
 #[derive(Deserialize)]
@@ -122,9 +1001,37 @@ struct ListBucketsResponse<InfoType> {
 struct CreateBucketRequest<'a, InfoType> {
     account_id: &'a str,
     bucket_name: &'a str,
-    bucket_type: BucketType,
+    bucket_type: CreatableBucketType,
     bucket_info: InfoType,
-    lifecycle_rules: Vec<LifecycleRule>
+    lifecycle_rules: Vec<LifecycleRule>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_server_side_encryption: Option<&'a ServerSideEncryption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_lock_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replication_configuration: Option<&'a ReplicationConfiguration>
+}
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateBucketRequest<'a, InfoType> {
+    account_id: &'a str,
+    bucket_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bucket_type: Option<CreatableBucketType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bucket_info: Option<InfoType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lifecycle_rules: Option<Vec<LifecycleRule>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_server_side_encryption: Option<&'a ServerSideEncryption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_retention: Option<&'a DefaultRetention>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_lock_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    replication_configuration: Option<&'a ReplicationConfiguration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    if_revision_is: Option<u32>
 }
 /// Methods related to the [buckets module][1].
 ///
@@ -132,6 +1039,24 @@ struct CreateBucketRequest<'a, InfoType> {
 impl B2Authorization {
     /// Performs a [b2_list_buckets][1] api call.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::buckets::Bucket;
+    ///
+    ///let buckets: Vec<Bucket> = auth.list_buckets(client)?;
+    ///for bucket in buckets {
+    ///    println!("{}", bucket);
+    ///}
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. This function is only
     /// going to fail with the standard errors.
@@ -155,7 +1080,38 @@ impl B2Authorization {
             Ok(buckets.buckets)
         }
     }
-    /// Performs a [b2_create_bucket][1] api call.
+    /// Performs a [b2_create_bucket][1] api call. The type of the bucket is a
+    /// [`CreatableBucketType`], since snapshot buckets can only be created by backblaze.
+    ///
+    /// This is a shorthand for the builder [`CreateBucket`], which can also set the default
+    /// encryption, file lock and replication of the bucket.
+    ///
+    /// ```rust,no_run
+    ///#[macro_use]
+    ///extern crate serde_json;
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::buckets::{Bucket, CreatableBucketType, CreateBucket, LifecycleRule};
+    ///
+    ///// Delete old versions of files in the logs folder a week after they are replaced.
+    ///let rules = vec![LifecycleRule {
+    ///    file_name_prefix: "logs/".to_owned(),
+    ///    days_from_uploading_to_hiding: None,
+    ///    days_from_hiding_to_deleting: Some(7)
+    ///}];
+    ///let bucket: Bucket = CreateBucket::new("my-logs", CreatableBucketType::Private)
+    ///    .bucket_info(json!({"owner": "ops"}))
+    ///    .lifecycle_rules(rules)
+    ///    .send(auth, client)?;
+    ///println!("created {}", bucket);
+    ///# Ok(())
+    ///# }
+    /// ```
     ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
@@ -163,40 +1119,118 @@ impl B2Authorization {
     /// [`is_duplicate_bucket_name`] and [`is_invalid_bucket_name`].
     ///
     ///  [1]: https://www.backblaze.com/b2/docs/b2_create_bucket.html
+    ///  [`CreateBucket`]: ../buckets/struct.CreateBucket.html
+    ///  [`CreatableBucketType`]: ../buckets/enum.CreatableBucketType.html
     ///  [`B2Error`]: ../../enum.B2Error.html
     ///  [`is_maximum_bucket_limit`]: ../../enum.B2Error.html#method.is_maximum_bucket_limit
     ///  [`is_duplicate_bucket_name`]: ../../enum.B2Error.html#method.is_duplicate_bucket_name
     ///  [`is_invalid_bucket_name`]: ../../enum.B2Error.html#method.is_invalid_bucket_name
     pub fn create_bucket<InfoType>(&self,
                                    bucket_name: &str,
-                                   bucket_type: BucketType,
+                                   bucket_type: CreatableBucketType,
                                    bucket_info: InfoType,
                                    lifecycle_rules: Vec<LifecycleRule>,
                                    client: &Client)
         -> Result<Bucket<InfoType>, B2Error>
         where for <'de> InfoType: Serialize + Deserialize<'de>
     {
-        let url_string: String = format!("{}/b2api/v1/b2_create_bucket", self.api_url);
-        let url: &str = &url_string;
-
-        let body = try!(serde_json::to_string(&CreateBucketRequest {
-            account_id: &self.account_id,
-            bucket_name: bucket_name,
-            bucket_type: bucket_type,
-            bucket_info: bucket_info,
-            lifecycle_rules: lifecycle_rules
-        }));
-
-        let resp = try!(client.post(url)
-            .body(Body::BufBody(body.as_bytes(), body.len()))
-            .header(self.auth_header())
-            .send());
-        if resp.status != hyper::status::StatusCode::Ok {
-            Err(B2Error::from_response(resp))
-        } else {
-            let bucket: Bucket<InfoType> = try!(serde_json::from_reader(resp));
-            Ok(bucket)
-        }
+        CreateBucket::new(bucket_name, bucket_type)
+            .bucket_info(bucket_info)
+            .lifecycle_rules(lifecycle_rules)
+            .send(self, client)
+    }
+    /// Performs a [b2_create_bucket][1] api call like [`create_bucket`], and sets the default
+    /// encryption of files uploaded to the bucket.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_maximum_bucket_limit`],
+    /// [`is_duplicate_bucket_name`] and [`is_invalid_bucket_name`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_create_bucket.html
+    ///  [`create_bucket`]: #method.create_bucket
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_maximum_bucket_limit`]: ../../enum.B2Error.html#method.is_maximum_bucket_limit
+    ///  [`is_duplicate_bucket_name`]: ../../enum.B2Error.html#method.is_duplicate_bucket_name
+    ///  [`is_invalid_bucket_name`]: ../../enum.B2Error.html#method.is_invalid_bucket_name
+    #[deprecated(note = "use the CreateBucket builder and its default_server_side_encryption")]
+    pub fn create_bucket_with_encryption<InfoType>(&self,
+                                                   bucket_name: &str,
+                                                   bucket_type: CreatableBucketType,
+                                                   bucket_info: InfoType,
+                                                   lifecycle_rules: Vec<LifecycleRule>,
+                                                   encryption: &ServerSideEncryption,
+                                                   client: &Client)
+        -> Result<Bucket<InfoType>, B2Error>
+        where for <'de> InfoType: Serialize + Deserialize<'de>
+    {
+        CreateBucket::new(bucket_name, bucket_type)
+            .bucket_info(bucket_info)
+            .lifecycle_rules(lifecycle_rules)
+            .default_server_side_encryption(encryption.clone())
+            .send(self, client)
+    }
+    /// Performs a [b2_create_bucket][1] api call like [`create_bucket`], and enables file lock on
+    /// the bucket.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_maximum_bucket_limit`],
+    /// [`is_duplicate_bucket_name`] and [`is_invalid_bucket_name`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_create_bucket.html
+    ///  [`create_bucket`]: #method.create_bucket
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_maximum_bucket_limit`]: ../../enum.B2Error.html#method.is_maximum_bucket_limit
+    ///  [`is_duplicate_bucket_name`]: ../../enum.B2Error.html#method.is_duplicate_bucket_name
+    ///  [`is_invalid_bucket_name`]: ../../enum.B2Error.html#method.is_invalid_bucket_name
+    #[deprecated(note = "use the CreateBucket builder and its file_lock_enabled")]
+    pub fn create_bucket_with_file_lock<InfoType>(&self,
+                                                  bucket_name: &str,
+                                                  bucket_type: CreatableBucketType,
+                                                  bucket_info: InfoType,
+                                                  lifecycle_rules: Vec<LifecycleRule>,
+                                                  client: &Client)
+        -> Result<Bucket<InfoType>, B2Error>
+        where for <'de> InfoType: Serialize + Deserialize<'de>
+    {
+        CreateBucket::new(bucket_name, bucket_type)
+            .bucket_info(bucket_info)
+            .lifecycle_rules(lifecycle_rules)
+            .file_lock_enabled(true)
+            .send(self, client)
+    }
+    /// Performs a [b2_create_bucket][1] api call like [`create_bucket`], and sends the settings
+    /// given in the [`CreateBucketOptions`].
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_maximum_bucket_limit`],
+    /// [`is_duplicate_bucket_name`] and [`is_invalid_bucket_name`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_create_bucket.html
+    ///  [`create_bucket`]: #method.create_bucket
+    ///  [`CreateBucketOptions`]: ../buckets/struct.CreateBucketOptions.html
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_maximum_bucket_limit`]: ../../enum.B2Error.html#method.is_maximum_bucket_limit
+    ///  [`is_duplicate_bucket_name`]: ../../enum.B2Error.html#method.is_duplicate_bucket_name
+    ///  [`is_invalid_bucket_name`]: ../../enum.B2Error.html#method.is_invalid_bucket_name
+    #[deprecated(note = "use the CreateBucket builder")]
+    pub fn create_bucket_with_options<InfoType>(&self,
+                                                bucket_name: &str,
+                                                bucket_type: CreatableBucketType,
+                                                bucket_info: InfoType,
+                                                lifecycle_rules: Vec<LifecycleRule>,
+                                                options: &CreateBucketOptions,
+                                                client: &Client)
+        -> Result<Bucket<InfoType>, B2Error>
+        where for <'de> InfoType: Serialize + Deserialize<'de>
+    {
+        let mut builder = CreateBucket::new(bucket_name, bucket_type)
+            .bucket_info(bucket_info)
+            .lifecycle_rules(lifecycle_rules);
+        builder.options = options.clone();
+        builder.send(self, client)
     }
     /// Performs a [b2_create_bucket][1] api call. This function initializes the bucket with no
     /// info.
@@ -213,13 +1247,54 @@ impl B2Authorization {
     ///  [`is_invalid_bucket_name`]: ../../enum.B2Error.html#method.is_invalid_bucket_name
     pub fn create_bucket_no_info(&self,
                                    bucket_name: &str,
-                                   bucket_type: BucketType,
+                                   bucket_type: CreatableBucketType,
                                    lifecycle_rules: Vec<LifecycleRule>,
                                    client: &Client)
         -> Result<Bucket<JsonValue>, B2Error>
     {
-        self.create_bucket(bucket_name, bucket_type, JsonValue::Object(serde_json::map::Map::new()),
-            lifecycle_rules, client)
+        CreateBucket::new(bucket_name, bucket_type)
+            .lifecycle_rules(lifecycle_rules)
+            .send(self, client)
+    }
+    /// Performs a [b2_update_bucket][1] api call. The settings that are `None` are left
+    /// unchanged, and if `if_revision_is` is given, the bucket is only updated if its revision
+    /// matches.
+    ///
+    /// This is a shorthand for the builder [`UpdateBucket`], which can also change the default
+    /// encryption, file lock and replication of the bucket.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_bucket_not_found`], and with [`is_conflict`] if
+    /// the revision does not match.
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_update_bucket.html
+    ///  [`UpdateBucket`]: ../buckets/struct.UpdateBucket.html
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_conflict`]: ../../enum.B2Error.html#method.is_conflict
+    pub fn update_bucket<InfoType>(&self,
+                                   bucket_id: &str,
+                                   bucket_type: Option<CreatableBucketType>,
+                                   bucket_info: Option<InfoType>,
+                                   lifecycle_rules: Option<Vec<LifecycleRule>>,
+                                   if_revision_is: Option<u32>,
+                                   client: &Client)
+        -> Result<Bucket<InfoType>, B2Error>
+        where for <'de> InfoType: Serialize + Deserialize<'de>
+    {
+        UpdateBucket {
+            bucket_id: bucket_id,
+            bucket_type: bucket_type,
+            bucket_info: bucket_info,
+            cache_control: None,
+            lifecycle_rules: lifecycle_rules,
+            default_server_side_encryption: None,
+            default_retention: None,
+            file_lock_enabled: false,
+            replication_configuration: None,
+            if_revision_is: if_revision_is
+        }.send(self, client)
     }
     /// Performs a [b2_delete_bucket][1] api call.
     ///
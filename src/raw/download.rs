@@ -13,20 +13,33 @@
 //!  [3]: ../authorize/struct.B2Authorization.html#method.to_download_authorization
 //!  [4]: ../authorize/struct.B2Authorization.html#method.get_download_authorization
 
+use std::cmp;
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::thread;
+use std::time::{Duration, Instant};
+
 use hyper::{self, Client};
 use hyper::client::Body;
 use hyper::client::response::Response;
-use hyper::header::{ContentLength,ContentType,CacheControl};
+use hyper::header::{Headers,ContentLength,ContentRange,ContentRangeSpec,ContentType};
 
 use serde::Deserialize;
 use serde_json;
 use serde_json::value::{Value as JsonValue};
 use serde_json::map::Map;
 
+use sha1::Sha1;
+
 use B2Error;
+use B2ErrorMessage;
 use B2AuthHeader;
-use raw::authorize::B2Authorization;
-use raw::files::FileInfo;
+use RetryAfter;
+use encoding::{percent_encode, percent_decode};
+use raw::authorize::{B2Authorization, check_header_value, check_response, check_url};
+use raw::files::{FileInfo, MoreFileInfo, Sha1Availability, effective_sha1};
+use raw::encryption::ServerSideEncryption;
+use retry::RetryPolicy;
 
 header! { (XBzFileId, "X-Bz-File-Id") => [String] }
 header! { (XBzUploadTimestamp, "X-Bz-Upload-Timestamp") => [String] }
@@ -61,73 +74,412 @@ impl DownloadAuthorization {
             None => true
         }
     }
+    /// Checks that the authorization token can be used as a header value, and that the download
+    /// url looks like a url. This is useful when the authorization is loaded from storage, see
+    /// [`B2Authorization::validate`].
+    ///
+    /// # Errors
+    /// This function returns an [`InvalidRequest`] error naming the first invalid field.
+    ///
+    ///  [`B2Authorization::validate`]: ../authorize/struct.B2Authorization.html#method.validate
+    ///  [`InvalidRequest`]: ../../enum.B2Error.html#variant.InvalidRequest
+    pub fn validate(&self) -> Result<(), B2Error> {
+        check_header_value("authorization_token", &self.authorization_token)?;
+        check_url("download_url", &self.download_url)?;
+        Ok(())
+    }
 }
 
-fn handle_download_response<InfoType>(resp: Response)
-    -> Result<(Response, Option<FileInfo<InfoType>>), B2Error>
-    where for<'de> InfoType: Deserialize<'de>
-{
-    loop { // never actually loops, but allows break
-           // I break so I can return response even though the match borrows it
-        let file_id = match resp.headers.get::<XBzFileId>() {
-            Some(header) => format!("{}", header),
-            None => break
-        };
-        let file_name = match resp.headers.get::<XBzFileName>() {
-            Some(header) => format!("{}", header),
-            None => break
-        };
-        let content_length = match resp.headers.get::<ContentLength>() {
-            Some(header) => header.0,
-            None => break
-        };
-        let content_type = match resp.headers.get::<ContentType>() {
-            Some(header) => format!("{}", header),
-            None => break
-        };
-        let content_sha1 = match resp.headers.get::<XBzContentSha1>() {
-            Some(header) => format!("{}", header),
-            None => break
-        };
-        let upload_timestamp = match resp.headers.get::<XBzUploadTimestamp>() {
-            Some(header) => format!("{}", header),
-            None => break
-        };
-        let mut info = Map::new();
-        // maybe add ContentRange check here?
-        let check_headers = if resp.headers.has::<CacheControl>() {
-            resp.headers.len() > 7
+/// The longest validity backblaze allows for a download authorization, which is one week.
+pub const MAX_DOWNLOAD_AUTHORIZATION_SECONDS: u32 = 604800;
+
+/// A cache of download authorizations created by [`get_download_authorization`].
+///
+/// A download authorization allows downloading every file whose name starts with its prefix, so
+/// a cached authorization for `photos/` is also used for a request for `photos/2024/`. New
+/// authorizations are created with the validity given to [`new`], or longer if a request needs
+/// it.
+///
+/// The methods take `&mut self`. When the cache is shared between threads in a `Mutex`, threads
+/// missing the cache at the same time wait for the single api call made while the lock is held.
+///
+/// ```rust,no_run
+///# extern crate hyper;
+///# extern crate backblaze_b2;
+///# use hyper::Client;
+///# use backblaze_b2::B2Error;
+///# use backblaze_b2::raw::authorize::B2Authorization;
+///# fn main() {}
+///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+///use std::time::Duration;
+///use backblaze_b2::raw::download::DownloadAuthCache;
+///
+///let mut cache = DownloadAuthCache::new(Duration::from_secs(24 * 60 * 60));
+///for name in &["photos/cat.png", "photos/dog.png"] {
+///    // only the first iteration calls b2_get_download_authorization
+///    let download_auth = cache.get_or_create(auth, "bucket id", "photos/",
+///                                            Duration::from_secs(60 * 60), client)?;
+///    println!("{}/file/my-bucket/{}?Authorization={}", download_auth.download_url, name,
+///             download_auth.authorization_token);
+///}
+///# Ok(())
+///# }
+/// ```
+///
+///  [`get_download_authorization`]: ../authorize/struct.B2Authorization.html#method.get_download_authorization
+///  [`new`]: #method.new
+#[derive(Debug,Clone)]
+pub struct DownloadAuthCache {
+    valid_duration: Duration,
+    entries: Vec<CachedDownloadAuthorization>
+}
+#[derive(Debug,Clone)]
+struct CachedDownloadAuthorization {
+    bucket_id: String,
+    prefix: String,
+    expires_at: Instant,
+    auth: DownloadAuthorization
+}
+impl DownloadAuthCache {
+    /// Creates an empty cache, that creates authorizations valid for `valid_duration`.
+    pub fn new(valid_duration: Duration) -> DownloadAuthCache {
+        DownloadAuthCache {
+            valid_duration: valid_duration,
+            entries: Vec::new()
+        }
+    }
+    /// Returns the amount of cached authorizations, including expired ones.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Returns true if no authorizations are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Removes every cached authorization.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+    /// Returns a cached authorization for the bucket that covers `prefix` and is valid for at
+    /// least `min_remaining_validity`, or creates one using [`get_download_authorization`].
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_bucket_not_found`]. If `min_remaining_validity`
+    /// is longer than a week, [`InvalidRequest`] is returned.
+    ///
+    ///  [`get_download_authorization`]: ../authorize/struct.B2Authorization.html#method.get_download_authorization
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`InvalidRequest`]: ../../enum.B2Error.html#variant.InvalidRequest
+    pub fn get_or_create(&mut self, auth: &B2Authorization, bucket_id: &str, prefix: &str,
+                         min_remaining_validity: Duration, client: &Client)
+        -> Result<DownloadAuthorization, B2Error>
+    {
+        self.get_or_create_with(bucket_id, prefix, min_remaining_validity, Instant::now(),
+                                |bucket_id, prefix, seconds| {
+            auth.get_download_authorization(bucket_id, Some(prefix), seconds, client)
+        })
+    }
+    /// Performs the lookup of [`get_or_create`] at the time `now`, using the closure to create
+    /// authorizations. The closure is called with the bucket id, the prefix and the validity in
+    /// seconds.
+    ///
+    /// ```rust
+    ///use std::time::{Duration, Instant};
+    ///use backblaze_b2::B2Error;
+    ///use backblaze_b2::raw::download::{DownloadAuthCache, DownloadAuthorization};
+    ///
+    ///let mut calls = 0;
+    ///let mut create = |bucket_id: &str, prefix: &str, _seconds: u32|
+    ///    -> Result<DownloadAuthorization, B2Error>
+    ///{
+    ///    calls += 1;
+    ///    Ok(DownloadAuthorization {
+    ///        authorization_token: format!("token {}", calls),
+    ///        bucket_id: Some(bucket_id.to_owned()),
+    ///        file_name_prefix: prefix.to_owned(),
+    ///        download_url: "https://f001.backblazeb2.com".to_owned()
+    ///    })
+    ///};
+    ///let mut cache = DownloadAuthCache::new(Duration::from_secs(3600));
+    ///let now = Instant::now();
+    ///let hour = Duration::from_secs(3600);
+    ///let a = cache.get_or_create_with("bucket", "photos/", hour, now, &mut create).unwrap();
+    ///let b = cache.get_or_create_with("bucket", "photos/cats/", hour, now, &mut create).unwrap();
+    ///assert_eq!(a.authorization_token, b.authorization_token);
+    /// ```
+    ///
+    /// # Errors
+    /// Errors returned by the closure are returned. If `min_remaining_validity` is longer than a
+    /// week, [`InvalidRequest`] is returned.
+    ///
+    ///  [`get_or_create`]: #method.get_or_create
+    ///  [`InvalidRequest`]: ../../enum.B2Error.html#variant.InvalidRequest
+    pub fn get_or_create_with<F>(&mut self, bucket_id: &str, prefix: &str,
+                                 min_remaining_validity: Duration, now: Instant, create: F)
+        -> Result<DownloadAuthorization, B2Error>
+        where F: FnOnce(&str, &str, u32) -> Result<DownloadAuthorization, B2Error>
+    {
+        self.entries.retain(|entry| entry.expires_at > now);
+        let needed = now + min_remaining_validity;
+        let cached = self.entries.iter()
+            .filter(|entry| entry.bucket_id == bucket_id && prefix.starts_with(&entry.prefix))
+            .filter(|entry| entry.expires_at >= needed)
+            .max_by_key(|entry| entry.expires_at);
+        if let Some(entry) = cached {
+            return Ok(entry.auth.clone());
+        }
+
+        let validity = cmp::max(self.valid_duration, min_remaining_validity);
+        // round up, so the authorization is valid for at least the requested duration
+        let seconds = validity.as_secs() + if validity.subsec_nanos() > 0 { 1 } else { 0 };
+        if seconds > u64::from(MAX_DOWNLOAD_AUTHORIZATION_SECONDS) {
+            return Err(B2Error::InvalidRequest {
+                field: if min_remaining_validity > self.valid_duration {
+                    "min_remaining_validity"
+                } else {
+                    "valid_duration"
+                },
+                reason: "a download authorization is valid for at most a week".to_owned()
+            });
+        }
+        let auth = create(bucket_id, prefix, seconds as u32)?;
+        // the validity starts when backblaze creates the authorization, which is after now
+        self.entries.push(CachedDownloadAuthorization {
+            bucket_id: bucket_id.to_owned(),
+            prefix: prefix.to_owned(),
+            expires_at: now + Duration::from_secs(seconds),
+            auth: auth.clone()
+        });
+        Ok(auth)
+    }
+}
+
+/// A reader that computes the sha1 of the data read through it. When the end of the data is
+/// reached, the sha1 is compared with the expected sha1, and if they differ, the read fails with
+/// an io error of the kind `InvalidData` instead of returning 0.
+///
+/// The sha1 of backblaze is for the entire file, so this reader should not be used with range
+/// requests.
+///
+/// ```rust
+///use std::io::{Read, ErrorKind};
+///use backblaze_b2::raw::download::VerifyingReader;
+///
+///let data = b"hello world";
+///let mut buf = Vec::new();
+///let mut reader = VerifyingReader::new(&data[..], "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed");
+///reader.read_to_end(&mut buf).unwrap();
+///
+///let mut reader = VerifyingReader::new(&data[..], "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+///let err = reader.read_to_end(&mut buf).unwrap_err();
+///assert_eq!(err.kind(), ErrorKind::InvalidData);
+///
+///// large files are not verified
+///let mut reader = VerifyingReader::new(&data[..], "none");
+///reader.read_to_end(&mut buf).unwrap();
+/// ```
+pub struct VerifyingReader<R> {
+    inner: R,
+    sha1: Sha1,
+    expected: Option<String>
+}
+impl<R: Read> VerifyingReader<R> {
+    /// Creates a reader that verifies that the data in `inner` has the given sha1. If the sha1
+    /// is `none`, which backblaze uses for large files, the data is not verified. The prefix
+    /// `unverified:` is ignored.
+    pub fn new(inner: R, expected_sha1: &str) -> VerifyingReader<R> {
+        let expected = if expected_sha1 == "none" {
+            None
+        } else if expected_sha1.starts_with("unverified:") {
+            Some(expected_sha1[11..].to_owned())
         } else {
-            resp.headers.len() > 6
+            Some(expected_sha1.to_owned())
         };
-        if check_headers {
-            for header in resp.headers.iter() {
-                if header.name().starts_with("X-Bz-Info-") {
-                    info.insert(header.name()[10..].to_owned(),
-                    JsonValue::String(header.value_string()));
+        VerifyingReader {
+            inner: inner,
+            sha1: Sha1::new(),
+            expected: expected
+        }
+    }
+    /// Returns true if the data is verified.
+    pub fn is_verifying(&self) -> bool {
+        self.expected.is_some()
+    }
+    /// Returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+impl<R: Read> Read for VerifyingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        if let Some(ref expected) = self.expected {
+            if n > 0 {
+                self.sha1.update(&buf[..n]);
+            } else if !buf.is_empty() {
+                let actual = self.sha1.digest().to_string();
+                if !actual.eq_ignore_ascii_case(expected) {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+                                "sha1 mismatch: expected {}, but the data has sha1 {}",
+                                expected, actual)));
                 }
             }
         }
-        return Ok((resp, Some(FileInfo {
-            file_id: file_id,
-            file_name: file_name,
-            content_length: content_length,
-            content_type: content_type,
-            content_sha1: content_sha1,
-            file_info: serde_json::from_value(JsonValue::Object(info))?,
-            upload_timestamp: match upload_timestamp.parse() {
-                Ok(v) => v,
-                Err(_) => return Err(B2Error::ApiInconsistency("upload timestamp not integer".to_owned()))
+        Ok(n)
+    }
+}
+
+/// The file information found in the headers of a download response. Unlike the [`FileInfo`]
+/// returned by the download methods, every field is optional, so this can also be used on
+/// responses where backblaze omits some of the headers.
+///
+/// The file name and the values of the file info are percent-decoded.
+///
+///  [`FileInfo`]: ../files/struct.FileInfo.html
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct DownloadedFileInfo {
+    pub file_id: Option<String>,
+    pub file_name: Option<String>,
+    pub content_sha1: Option<String>,
+    pub content_type: Option<String>,
+    /// The length of the response body. For range requests this is the length of the range.
+    pub content_length: Option<u64>,
+    pub upload_timestamp: Option<u64>,
+    /// The `X-Bz-Info-*` headers, with the prefix removed from the names.
+    pub file_info: HashMap<String, String>
+}
+impl DownloadedFileInfo {
+    /// Parses the file information in the headers of a download response.
+    ///
+    /// ```rust
+    ///extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///use hyper::header::Headers;
+    ///use backblaze_b2::raw::download::DownloadedFileInfo;
+    ///
+    ///# fn main() {
+    ///let mut headers = Headers::new();
+    ///headers.set_raw("X-Bz-File-Name", vec![b"photos/%C3%B8.png".to_vec()]);
+    ///headers.set_raw("X-Bz-Info-author", vec![b"J%C3%B8rgen".to_vec()]);
+    ///headers.set_raw("Content-Length", vec![b"100".to_vec()]);
+    ///let info = DownloadedFileInfo::from_headers(&headers).unwrap();
+    ///assert_eq!(info.file_name, Some("photos/ø.png".to_owned()));
+    ///assert_eq!(info.file_info["author"], "Jørgen");
+    ///assert_eq!(info.content_length, Some(100));
+    ///assert_eq!(info.file_id, None);
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// Fails with [`ApiInconsistency`] if a header is present, but cannot be parsed.
+    ///
+    ///  [`ApiInconsistency`]: ../../enum.B2Error.html#variant.ApiInconsistency
+    pub fn from_headers(headers: &Headers) -> Result<DownloadedFileInfo, B2Error> {
+        fn decode(name: &str, value: &str) -> Result<String, B2Error> {
+            percent_decode(value).ok_or_else(|| B2Error::ApiInconsistency(
+                    format!("{} header not percent-encoded", name)))
+        }
+        let file_name = match headers.get::<XBzFileName>() {
+            Some(header) => Some(decode("file name", &header.0)?),
+            None => None
+        };
+        let upload_timestamp = match headers.get::<XBzUploadTimestamp>() {
+            Some(header) => match header.0.parse() {
+                Ok(v) => Some(v),
+                Err(_) => return Err(B2Error::ApiInconsistency(
+                        "upload timestamp not integer".to_owned()))
             },
-        })));
+            None => None
+        };
+        let mut file_info = HashMap::new();
+        for header in headers.iter() {
+            let name = header.name();
+            if name.len() > 10 && name[..10].eq_ignore_ascii_case("X-Bz-Info-") {
+                let value = decode(name, &header.value_string())?;
+                file_info.insert(name[10..].to_owned(), value);
+            }
+        }
+        Ok(DownloadedFileInfo {
+            file_id: headers.get::<XBzFileId>().map(|header| header.0.clone()),
+            file_name: file_name,
+            content_sha1: headers.get::<XBzContentSha1>().map(|header| header.0.clone()),
+            content_type: headers.get::<ContentType>().map(|header| format!("{}", header)),
+            content_length: headers.get::<ContentLength>().map(|header| header.0),
+            upload_timestamp: upload_timestamp,
+            file_info: file_info
+        })
+    }
+    /// Returns the sha1 of the file, using the `large_file_sha1` file info for large files. See
+    /// [`effective_sha1`].
+    ///
+    ///  [`effective_sha1`]: ../files/fn.effective_sha1.html
+    pub fn effective_sha1(&self) -> Sha1Availability {
+        match self.content_sha1 {
+            Some(ref content_sha1) => effective_sha1(
+                content_sha1, self.file_info.get("large_file_sha1").map(|sha1| sha1.as_str())),
+            None => Sha1Availability::Unavailable
+        }
     }
-    Ok((resp, None))
+}
+
+fn handle_download_response<InfoType>(resp: Response)
+    -> Result<(Response, Option<FileInfo<InfoType>>), B2Error>
+    where for<'de> InfoType: Deserialize<'de>
+{
+    let info = match DownloadedFileInfo::from_headers(&resp.headers)? {
+        DownloadedFileInfo {
+            file_id: Some(file_id),
+            file_name: Some(file_name),
+            content_sha1: Some(content_sha1),
+            content_type: Some(content_type),
+            content_length: Some(content_length),
+            upload_timestamp: Some(upload_timestamp),
+            file_info
+        } => {
+            let file_info: Map<String, JsonValue> = file_info.into_iter()
+                .map(|(name, value)| (name, JsonValue::String(value)))
+                .collect();
+            Some(FileInfo {
+                file_id: file_id,
+                file_name: file_name,
+                content_length: content_length,
+                content_type: content_type,
+                content_sha1: content_sha1,
+                file_info: serde_json::from_value(JsonValue::Object(file_info))?,
+                upload_timestamp: upload_timestamp
+            })
+        },
+        _ => None
+    };
+    Ok((resp, info))
 }
 
 impl DownloadAuthorization {
 
     /// Performs a [b2_download_file_by_id][1] api call.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use std::io::Read;
+    ///use backblaze_b2::raw::files::FileInfo;
+    ///
+    ///let download_auth = auth.to_download_authorization();
+    ///let (mut resp, info): (_, Option<FileInfo>) =
+    ///    download_auth.download_file_by_id("file id", client)?;
+    ///let mut data = Vec::new();
+    ///resp.read_to_end(&mut data)?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
     /// errors, this function can fail with [`is_file_not_found`].
@@ -154,9 +506,60 @@ impl DownloadAuthorization {
             handle_download_response(resp)
         }
     }
+    /// Performs a [b2_download_file_by_id][1] api call, and wraps the response in a
+    /// [`VerifyingReader`] that checks the data against the `X-Bz-Content-Sha1` header.
+    ///
+    /// Large files have no sha1 stored on backblaze, so they are verified against the
+    /// `large_file_sha1` file info if the uploader stored one, and are otherwise not verified.
+    /// See [`effective_sha1`].
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`]. See [`VerifyingReader`] for the
+    /// error returned when the data does not match.
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_download_file_by_id.html
+    ///  [`VerifyingReader`]: struct.VerifyingReader.html
+    ///  [`effective_sha1`]: ../files/fn.effective_sha1.html
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    pub fn download_file_by_id_verified<InfoType>(&self, file_id: &str, client: &Client)
+        -> Result<(VerifyingReader<Response>, Option<FileInfo<InfoType>>), B2Error>
+        where for<'de> InfoType: Deserialize<'de>
+    {
+        let (resp, info) = self.download_file_by_id(file_id, client)?;
+        let sha1 = DownloadedFileInfo::from_headers(&resp.headers)?.effective_sha1();
+        let reader = match sha1.sha1() {
+            Some(sha1) => VerifyingReader::new(resp, sha1),
+            None => VerifyingReader::new(resp, "none")
+        };
+        Ok((reader, info))
+    }
     /// Performs a [b2_download_file_by_id][1] api call. This function specifies the range of the
     /// file to download, and the range_max parameter is inclusive.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use std::io::Read;
+    ///use backblaze_b2::raw::files::FileInfo;
+    ///
+    ///// Download bytes 100 to 199. The range is inclusive, so range_max is 199.
+    ///let download_auth = auth.to_download_authorization();
+    ///let (mut resp, _): (_, Option<FileInfo>) =
+    ///    download_auth.download_range_by_id("file id", 100, 199, client)?;
+    ///let mut data = Vec::new();
+    ///resp.read_to_end(&mut data)?;
+    ///assert_eq!(data.len(), 100);
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
     /// errors, this function can fail with [`is_file_not_found`] and [`is_range_out_of_bounds`].
@@ -169,24 +572,31 @@ impl DownloadAuthorization {
         -> Result<(Response, Option<FileInfo<InfoType>>), B2Error>
         where for<'de> InfoType: Deserialize<'de>
     {
-        let url_string: String = format!("{}/b2api/v1/b2_download_file_by_id", self.download_url);
-        let url: &str = &url_string;
-
-        let body: String = format!("{{\"fileId\":\"{}\"}}", file_id);
-
-        let resp = try!(client.post(url)
-            .body(Body::BufBody(body.as_bytes(), body.len()))
-            .header(self.auth_header())
-            .header(B2Range(format!("bytes={}-{}", range_min, range_max)))
-            .send());
-        if resp.status != hyper::status::StatusCode::PartialContent {
-            Err(B2Error::from_response(resp))
-        } else {
-            handle_download_response(resp)
-        }
+        self.download_byte_range(file_id, ByteRange::Inclusive(range_min, range_max), client)
     }
     /// Performs a [b2_download_file_by_name][1] api call.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use std::io::Read;
+    ///use backblaze_b2::raw::files::FileInfo;
+    ///
+    ///// The file name is percent-encoded by this function.
+    ///let download_auth = auth.to_download_authorization();
+    ///let (mut resp, _): (_, Option<FileInfo>) =
+    ///    download_auth.download_file_by_name("my-bucket", "photos/ø.png", client)?;
+    ///let mut data = Vec::new();
+    ///resp.read_to_end(&mut data)?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
     /// errors, this function can fail with [`is_file_not_found`] and [`is_bucket_not_found`].
@@ -199,7 +609,8 @@ impl DownloadAuthorization {
         -> Result<(Response, Option<FileInfo<InfoType>>), B2Error>
         where for<'de> InfoType: Deserialize<'de>
     {
-        let url_string: String = format!("{}/file/{}/{}", self.download_url, bucket_name, file_name);
+        let url_string: String = format!("{}/file/{}/{}", self.download_url, bucket_name,
+                                         percent_encode(file_name));
         let url: &str = &url_string;
 
         let resp = try!(client.get(url)
@@ -211,6 +622,66 @@ impl DownloadAuthorization {
             handle_download_response(resp)
         }
     }
+    /// Retrieves the information about a file without downloading it, by sending a `HEAD`
+    /// request to the [b2_download_file_by_name][1] url. The information is parsed from the
+    /// headers like for downloads.
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///let download_auth = auth.to_download_authorization();
+    ///match download_auth.head_file_by_name("my-bucket", "photos/cat.png", client) {
+    ///    Ok(info) => println!("{:?} bytes", info.content_length),
+    ///    Err(ref err) if err.is_file_not_found() => println!("no such file"),
+    ///    Err(err) => return Err(err)
+    ///}
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Since the response to a
+    /// `HEAD` request has no body, the error is created from the status code, and a missing file
+    /// gives an error where [`is_file_not_found`] returns true.
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_download_file_by_name.html
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    pub fn head_file_by_name(&self, bucket_name: &str, file_name: &str, client: &Client)
+        -> Result<DownloadedFileInfo, B2Error>
+    {
+        let url_string: String = format!("{}/file/{}/{}", self.download_url, bucket_name,
+                                         percent_encode(file_name));
+        let url: &str = &url_string;
+
+        let resp = client.head(url)
+            .header(self.auth_header())
+            .send()?;
+        match resp.status {
+            hyper::status::StatusCode::Ok => DownloadedFileInfo::from_headers(&resp.headers),
+            hyper::status::StatusCode::NotFound => Err(B2Error::B2Error(resp.status,
+                B2ErrorMessage {
+                    code: "no_such_file".to_owned(),
+                    message: format!("File not present: {}", file_name),
+                    status: 404,
+                    retry_after: None
+                })),
+            status => {
+                let reason = status.canonical_reason().unwrap_or("unknown error");
+                Err(B2Error::B2Error(status, B2ErrorMessage {
+                    code: reason.to_lowercase().replace(' ', "_"),
+                    message: reason.to_owned(),
+                    status: u32::from(status.to_u16()),
+                    retry_after: resp.headers.get::<RetryAfter>().map(|header| header.0)
+                }))
+            }
+        }
+    }
     /// Performs a [b2_download_file_by_name][1] api call. This function specifies the range of the
     /// file to download, and the range_max parameter is inclusive.
     ///
@@ -229,22 +700,442 @@ impl DownloadAuthorization {
         -> Result<(Response, Option<FileInfo<InfoType>>), B2Error>
         where for<'de> InfoType: Deserialize<'de>
     {
-        let url_string: String = format!("{}/file/{}/{}", self.download_url, bucket_name, file_name);
-        let url: &str = &url_string;
-
-        let resp = try!(client.get(url)
-            .header(self.auth_header())
-            .header(B2Range(format!("bytes={}-{}", range_min, range_max)))
-            .send());
+        self.download_byte_range((bucket_name, file_name),
+                                 ByteRange::Inclusive(range_min, range_max), client)
+    }
+    /// Downloads the given range of the file specified by the [`FileSelector`]. Unlike
+    /// [`download_range_by_id`] and [`download_range_by_name`], this function also supports
+    /// open-ended and suffix ranges.
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use std::io::Read;
+    ///use backblaze_b2::raw::download::ByteRange;
+    ///use backblaze_b2::raw::files::FileInfo;
+    ///
+    ///// Download the last 100 bytes of the file.
+    ///let download_auth = auth.to_download_authorization();
+    ///let (mut resp, _): (_, Option<FileInfo>) = download_auth.download_byte_range(
+    ///    ("my-bucket", "logs/today.log"), ByteRange::Suffix(100), client)?;
+    ///let mut data = Vec::new();
+    ///resp.read_to_end(&mut data)?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`], [`is_range_out_of_bounds`] and
+    /// [`is_bucket_not_found`].
+    ///
+    ///  [`FileSelector`]: enum.FileSelector.html
+    ///  [`download_range_by_id`]: #method.download_range_by_id
+    ///  [`download_range_by_name`]: #method.download_range_by_name
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_range_out_of_bounds`]: ../../enum.B2Error.html#method.is_range_out_of_bounds
+    pub fn download_byte_range<'a, S, InfoType>(&self, file: S, range: ByteRange, client: &Client)
+        -> Result<(Response, Option<FileInfo<InfoType>>), B2Error>
+        where S: Into<FileSelector<'a>>, for<'de> InfoType: Deserialize<'de>
+    {
+        let resp = try!(match file.into() {
+            FileSelector::ById(file_id) => {
+                let url = format!("{}/b2api/v1/b2_download_file_by_id", self.download_url);
+                let body: String = format!("{{\"fileId\":\"{}\"}}", file_id);
+                client.post(url.as_str())
+                    .body(Body::BufBody(body.as_bytes(), body.len()))
+                    .header(self.auth_header())
+                    .header(B2Range(range.header_value()))
+                    .send()
+            },
+            FileSelector::ByName { bucket_name, file_name } => {
+                let url = format!("{}/file/{}/{}", self.download_url, bucket_name,
+                                  percent_encode(file_name));
+                client.get(url.as_str())
+                    .header(self.auth_header())
+                    .header(B2Range(range.header_value()))
+                    .send()
+            }
+        });
         if resp.status != hyper::status::StatusCode::PartialContent {
             Err(B2Error::from_response(resp))
         } else {
             handle_download_response(resp)
         }
     }
+    /// Downloads the file specified by the [`FileSelector`] using either
+    /// [`download_file_by_id`] or [`download_file_by_name`].
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`] and [`is_bucket_not_found`].
+    ///
+    ///  [`FileSelector`]: enum.FileSelector.html
+    ///  [`download_file_by_id`]: #method.download_file_by_id
+    ///  [`download_file_by_name`]: #method.download_file_by_name
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    pub fn download_file<'a, S, InfoType>(&self, file: S, client: &Client)
+        -> Result<(Response, Option<FileInfo<InfoType>>), B2Error>
+        where S: Into<FileSelector<'a>>, for<'de> InfoType: Deserialize<'de>
+    {
+        match file.into() {
+            FileSelector::ById(file_id) => self.download_file_by_id(file_id, client),
+            FileSelector::ByName { bucket_name, file_name } =>
+                self.download_file_by_name(bucket_name, file_name, client)
+        }
+    }
+    /// Downloads the file specified by the [`FileSelector`] like [`download_file`]. The
+    /// encryption is needed to download files encrypted with SSE-C, for other files it is
+    /// ignored.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`] and [`is_bucket_not_found`]. If
+    /// the file is encrypted with SSE-C and the key is missing or wrong, the error returned by
+    /// backblaze is returned.
+    ///
+    ///  [`FileSelector`]: enum.FileSelector.html
+    ///  [`download_file`]: #method.download_file
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    pub fn download_file_with_encryption<'a, S, InfoType>(&self, file: S,
+                                                          encryption: &ServerSideEncryption,
+                                                          client: &Client)
+        -> Result<(Response, Option<FileInfo<InfoType>>), B2Error>
+        where S: Into<FileSelector<'a>>, for<'de> InfoType: Deserialize<'de>
+    {
+        let mut headers = Headers::new();
+        headers.set(self.auth_header());
+        encryption.set_download_headers(&mut headers);
+        let resp = try!(match file.into() {
+            FileSelector::ById(file_id) => {
+                let url = format!("{}/b2api/v1/b2_download_file_by_id", self.download_url);
+                let body: String = format!("{{\"fileId\":\"{}\"}}", file_id);
+                client.post(url.as_str())
+                    .body(Body::BufBody(body.as_bytes(), body.len()))
+                    .headers(headers)
+                    .send()
+            },
+            FileSelector::ByName { bucket_name, file_name } => {
+                let url = format!("{}/file/{}/{}", self.download_url, bucket_name,
+                                  percent_encode(file_name));
+                client.get(url.as_str())
+                    .headers(headers)
+                    .send()
+            }
+        });
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            handle_download_response(resp)
+        }
+    }
+    /// Downloads the file specified by the [`FileSelector`], and returns a reader that resumes
+    /// the download if the connection is lost. When reading the body fails, or the body ends
+    /// early, a range request is made for the remaining bytes. At most `max_resumes` such
+    /// requests are made.
+    ///
+    /// If `range` is given, only that range of bytes is downloaded, like
+    /// [`download_byte_range`].
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use std::io::Read;
+    ///use backblaze_b2::raw::files::FileInfo;
+    ///
+    ///let download_auth = auth.to_download_authorization();
+    ///let (mut reader, _): (_, Option<FileInfo>) =
+    ///    download_auth.download_resumable(("my-bucket", "backup.tar"), None, 5, client)?;
+    ///let mut data = Vec::new();
+    ///reader.read_to_end(&mut data)?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`], [`is_bucket_not_found`] and
+    /// [`is_range_out_of_bounds`]. If the response does not contain the id of the file, the
+    /// download could not tell whether a resumed request is for the same file, so
+    /// [`ApiInconsistency`] is returned. See [`ResumableDownload`] for the errors when reading.
+    ///
+    ///  [`FileSelector`]: enum.FileSelector.html
+    ///  [`ResumableDownload`]: struct.ResumableDownload.html
+    ///  [`download_byte_range`]: #method.download_byte_range
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_range_out_of_bounds`]: ../../enum.B2Error.html#method.is_range_out_of_bounds
+    ///  [`ApiInconsistency`]: ../../enum.B2Error.html#variant.ApiInconsistency
+    pub fn download_resumable<'a, S, InfoType>(&'a self, file: S, range: Option<ByteRange>,
+                                               max_resumes: u32, client: &'a Client)
+        -> Result<(ResumableDownload<'a>, Option<FileInfo<InfoType>>), B2Error>
+        where S: Into<FileSelector<'a>>, for<'de> InfoType: Deserialize<'de>
+    {
+        let file = file.into();
+        let (resp, info) = match range {
+            Some(range) => self.download_byte_range(file, range, client)?,
+            None => self.download_file(file, client)?
+        };
+        // the start of open ended and suffix ranges is only known from the response
+        let start = match resp.headers.get::<ContentRange>() {
+            Some(&ContentRange(ContentRangeSpec::Bytes { range: Some((min, _)), .. })) => min,
+            _ => 0
+        };
+        // without a content length we don't know which range to request when resuming
+        let end = resp.headers.get::<ContentLength>().map(|len| start + len.0);
+        let file_id = match resp.headers.get::<XBzFileId>() {
+            Some(header) => header.0.clone(),
+            None => return Err(B2Error::ApiInconsistency(
+                    "download response did not contain the file id".to_owned()))
+        };
+        Ok((ResumableDownload {
+            auth: self,
+            client: client,
+            file: file,
+            file_id: file_id,
+            response: resp,
+            position: start,
+            end: end,
+            resumes_left: max_resumes,
+            policy: RetryPolicy::default(),
+            failures: 0
+        }, info))
+    }
 }
 header! { (B2Range, "Range") => [String] }
 
+/// A range of bytes in a file. The bounds of an inclusive range are both included, like in the
+/// http `Range` header.
+///
+/// ```rust
+///use backblaze_b2::raw::download::ByteRange;
+///
+///assert_eq!(ByteRange::Inclusive(0, 99).header_value(), "bytes=0-99");
+///assert_eq!(ByteRange::From(100).header_value(), "bytes=100-");
+///assert_eq!(ByteRange::Suffix(100).header_value(), "bytes=-100");
+/// ```
+#[derive(Debug,Clone,Copy,Eq,PartialEq)]
+pub enum ByteRange {
+    /// The bytes from the first offset to the second offset, both included.
+    Inclusive(u64, u64),
+    /// Every byte from the offset to the end of the file.
+    From(u64),
+    /// The given amount of bytes at the end of the file.
+    Suffix(u64)
+}
+impl ByteRange {
+    /// Returns the value of the `Range` header requesting this range.
+    pub fn header_value(&self) -> String {
+        match *self {
+            ByteRange::Inclusive(min, max) => format!("bytes={}-{}", min, max),
+            ByteRange::From(min) => format!("bytes={}-", min),
+            ByteRange::Suffix(len) => format!("bytes=-{}", len)
+        }
+    }
+}
+
+/// Specifies a file either by its id, or by the name of its bucket and its file name.
+///
+/// Some api calls only support one of the variants. Functions taking a `FileSelector` document
+/// which variants they support, and fail with an [`UnsupportedFileSelector`] error on the
+/// others.
+///
+/// ```rust
+///use backblaze_b2::raw::download::FileSelector;
+///
+///assert_eq!(FileSelector::from("4_z4a48fe8875c6214145260818"),
+///           FileSelector::ById("4_z4a48fe8875c6214145260818"));
+///assert_eq!(FileSelector::from(("bucket", "photos/cat.png")),
+///           FileSelector::ByName { bucket_name: "bucket", file_name: "photos/cat.png" });
+/// ```
+///
+///  [`UnsupportedFileSelector`]: ../../enum.B2Error.html#variant.UnsupportedFileSelector
+#[derive(Debug,Clone,Copy,Eq,PartialEq)]
+pub enum FileSelector<'a> {
+    ById(&'a str),
+    ByName { bucket_name: &'a str, file_name: &'a str }
+}
+impl<'a> FileSelector<'a> {
+    /// Returns the file id, or an [`UnsupportedFileSelector`] error if the file is selected by
+    /// name.
+    ///
+    ///  [`UnsupportedFileSelector`]: ../../enum.B2Error.html#variant.UnsupportedFileSelector
+    pub fn file_id(&self) -> Result<&'a str, B2Error> {
+        match *self {
+            FileSelector::ById(file_id) => Ok(file_id),
+            FileSelector::ByName { .. } =>
+                Err(B2Error::UnsupportedFileSelector { required: "a file id" })
+        }
+    }
+}
+impl<'a> From<&'a str> for FileSelector<'a> {
+    fn from(file_id: &'a str) -> FileSelector<'a> {
+        FileSelector::ById(file_id)
+    }
+}
+impl<'a> From<(&'a str, &'a str)> for FileSelector<'a> {
+    fn from((bucket_name, file_name): (&'a str, &'a str)) -> FileSelector<'a> {
+        FileSelector::ByName { bucket_name: bucket_name, file_name: file_name }
+    }
+}
+impl<'a, IT> From<&'a FileInfo<IT>> for FileSelector<'a> {
+    fn from(file: &'a FileInfo<IT>) -> FileSelector<'a> {
+        FileSelector::ById(&file.file_id)
+    }
+}
+impl<'a, IT> From<&'a MoreFileInfo<IT>> for FileSelector<'a> {
+    fn from(file: &'a MoreFileInfo<IT>) -> FileSelector<'a> {
+        FileSelector::ById(&file.file_id)
+    }
+}
+
+/// A reader returned by [`download_resumable`], which resumes the download with a range request
+/// if the connection is lost.
+///
+/// Only errors that may go away when trying again are resumed: io errors where
+/// [`is_transient_io_error`] returns true, including a connection that is closed before the end
+/// of the body. If the resumed request fails with an error where [`should_back_off`] or
+/// [`is_transient_io_error`] returns true, it is made again. Before every resumed request, the
+/// thread sleeps for the delay given by the [`RetryPolicy`], see [`retry_policy`]. The delay
+/// grows with every failed attempt in a row, and starts over once data is read again.
+///
+/// If the download can't be resumed, the error that caused the download to stop is returned.
+/// This happens when the error is not transient, when no resumes are left, when the server
+/// doesn't report the content length, or when the resumed request fails with
+/// [`is_range_out_of_bounds`]. If the resumed request fails with some other error, that error is
+/// returned instead. The download fails with an error of the kind `InvalidData` if the file is
+/// replaced while resuming, or if the response to a resumed request has no file id.
+///
+///  [`download_resumable`]: struct.DownloadAuthorization.html#method.download_resumable
+///  [`retry_policy`]: #method.retry_policy
+///  [`RetryPolicy`]: ../../retry/struct.RetryPolicy.html
+///  [`is_transient_io_error`]: ../../enum.B2Error.html#method.is_transient_io_error
+///  [`should_back_off`]: ../../enum.B2Error.html#method.should_back_off
+///  [`is_range_out_of_bounds`]: ../../enum.B2Error.html#method.is_range_out_of_bounds
+pub struct ResumableDownload<'a> {
+    auth: &'a DownloadAuthorization,
+    client: &'a Client,
+    file: FileSelector<'a>,
+    file_id: String,
+    response: Response,
+    position: u64,
+    end: Option<u64>,
+    resumes_left: u32,
+    policy: RetryPolicy,
+    failures: u32
+}
+impl<'a> ResumableDownload<'a> {
+    /// Returns the offset in the file of the next byte to be read.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+    /// Returns the amount of resumes left.
+    pub fn resumes_left(&self) -> u32 {
+        self.resumes_left
+    }
+    /// Sets the policy deciding how long to wait before resuming, which defaults to
+    /// `RetryPolicy::default()`. The amount of resumes is limited by the `max_resumes` passed to
+    /// [`download_resumable`], so the `max_attempts` of the policy is not used.
+    ///
+    ///  [`download_resumable`]: struct.DownloadAuthorization.html#method.download_resumable
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+    fn resume(&mut self, err: io::Error) -> io::Result<()> {
+        let end = match self.end {
+            Some(end) if self.resumes_left > 0 => end,
+            _ => return Err(err)
+        };
+        let err = B2Error::IOError(err);
+        if !err.is_transient_io_error() {
+            return Err(into_io_error(err));
+        }
+        let mut last_err = err;
+        loop {
+            self.resumes_left -= 1;
+            self.failures += 1;
+            let delay = last_err.retry_after()
+                .unwrap_or_else(|| self.policy.delay(self.failures));
+            thread::sleep(delay);
+            let result: Result<(Response, Option<FileInfo<JsonValue>>), B2Error> =
+                self.auth.download_byte_range(self.file,
+                                              ByteRange::Inclusive(self.position, end - 1),
+                                              self.client);
+            match result {
+                Ok((resp, _)) => {
+                    match resp.headers.get::<XBzFileId>() {
+                        Some(header) if header.0 == self.file_id => {},
+                        Some(_) => return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                                  "the file was replaced during the download")),
+                        None => return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                               "the resumed download did not contain the file id"))
+                    }
+                    self.response = resp;
+                    return Ok(());
+                },
+                Err(ref b2err) if b2err.is_range_out_of_bounds() =>
+                    return Err(into_io_error(last_err)),
+                Err(b2err) => {
+                    if self.resumes_left == 0 || !self.policy.should_retry(&b2err) {
+                        return Err(into_io_error(b2err));
+                    }
+                    last_err = b2err;
+                }
+            }
+        }
+    }
+}
+fn into_io_error(err: B2Error) -> io::Error {
+    match err {
+        B2Error::IOError(ioerr) => ioerr,
+        B2Error::HyperError(hyper::Error::Io(ioerr)) => ioerr,
+        b2err => io::Error::new(io::ErrorKind::Other, format!("{}", b2err))
+    }
+}
+impl<'a> Read for ResumableDownload<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let err = match self.response.read(buf) {
+                Ok(0) if !buf.is_empty() => match self.end {
+                    Some(end) if self.position < end =>
+                        io::Error::new(io::ErrorKind::UnexpectedEof, "the download ended early"),
+                    _ => return Ok(0)
+                },
+                Ok(n) => {
+                    self.position += n as u64;
+                    if n > 0 {
+                        self.failures = 0;
+                    }
+                    return Ok(n);
+                },
+                Err(err) => err
+            };
+            if err.kind() == io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+            self.resume(err)?;
+        }
+    }
+}
+
 /// Methods related to the [download module][1].
 ///
 ///  [1]: ../download/index.html
@@ -312,12 +1203,14 @@ impl B2Authorization {
             let Response {
                 authorization_token, bucket_id, file_name_prefix
             } = serde_json::from_reader(resp)?;
-            Ok(DownloadAuthorization {
+            let auth = DownloadAuthorization {
                 authorization_token: authorization_token,
                 bucket_id: Some(bucket_id),
                 file_name_prefix: file_name_prefix,
                 download_url: self.download_url.clone()
-            })
+            };
+            check_response("b2_get_download_authorization", auth.validate())?;
+            Ok(auth)
         }
     }
 }
@@ -339,7 +1232,8 @@ pub fn download_file_by_name<InfoType>(download_url: &str, bucket_name: &str, fi
     -> Result<(Response, Option<FileInfo<InfoType>>), B2Error>
     where for<'de> InfoType: Deserialize<'de>
 {
-    let url_string: String = format!("{}/file/{}/{}", download_url, bucket_name, file_name);
+    let url_string: String = format!("{}/file/{}/{}", download_url, bucket_name,
+                                     percent_encode(file_name));
     let url: &str = &url_string;
 
     let resp = try!(client.post(url)
@@ -371,7 +1265,8 @@ pub fn download_range_by_name<InfoType>(download_url: &str, bucket_name: &str, f
     -> Result<(Response, Option<FileInfo<InfoType>>), B2Error>
     where for<'de> InfoType: Deserialize<'de>
 {
-    let url_string: String = format!("{}/file/{}/{}", download_url, bucket_name, file_name);
+    let url_string: String = format!("{}/file/{}/{}", download_url, bucket_name,
+                                     percent_encode(file_name));
     let url: &str = &url_string;
 
     let resp = try!(client.get(url)
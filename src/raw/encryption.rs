@@ -0,0 +1,148 @@
+//! This module defines the settings for [server-side encryption][1].
+//!
+//! Files can be encrypted with keys managed by backblaze (SSE-B2), or with a key supplied by the
+//! customer (SSE-C). Files encrypted with SSE-C can only be downloaded if the same key is supplied
+//! when downloading. If the key is missing, backblaze returns an error that is stored in a
+//! [`B2Error`] like any other error returned by backblaze.
+//!
+//!  [1]: https://www.backblaze.com/b2/docs/server_side_encryption.html
+//!  [`B2Error`]: ../../enum.B2Error.html
+
+use hyper::header::Headers;
+
+use serde::ser::{Serialize, Serializer, SerializeStruct};
+
+header! { (XBzServerSideEncryption, "X-Bz-Server-Side-Encryption") => [String] }
+header! { (XBzSseCustomerAlgorithm,
+           "X-Bz-Server-Side-Encryption-Customer-Algorithm") => [String] }
+header! { (XBzSseCustomerKey, "X-Bz-Server-Side-Encryption-Customer-Key") => [String] }
+header! { (XBzSseCustomerKeyMd5, "X-Bz-Server-Side-Encryption-Customer-Key-Md5") => [String] }
+
+/// The only algorithm supported by backblaze.
+pub static AES256: &str = "AES256";
+
+/// The server-side encryption of a file or the default encryption of a bucket.
+///
+/// When serialized, this type uses the json format expected by backblaze.
+///
+/// ```rust
+///extern crate backblaze_b2;
+///extern crate hyper;
+///extern crate serde_json;
+///use backblaze_b2::raw::encryption::ServerSideEncryption;
+///
+///# fn main() {
+///assert_eq!(serde_json::to_string(&ServerSideEncryption::SseB2).unwrap(),
+///           r#"{"mode":"SSE-B2","algorithm":"AES256"}"#);
+///
+///let sse_c = ServerSideEncryption::SseC {
+///    algorithm: "AES256".to_owned(),
+///    key: "base64 encoded key".to_owned(),
+///    key_md5: "base64 encoded md5 of key".to_owned()
+///};
+///let mut headers = hyper::header::Headers::new();
+///sse_c.set_download_headers(&mut headers);
+///assert_eq!(headers.len(), 3);
+///# }
+/// ```
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum ServerSideEncryption {
+    None,
+    /// Encryption with keys managed by backblaze.
+    SseB2,
+    /// Encryption with a key supplied by the customer. The key and its md5 are base64 encoded.
+    SseC { algorithm: String, key: String, key_md5: String }
+}
+impl Default for ServerSideEncryption {
+    fn default() -> ServerSideEncryption {
+        ServerSideEncryption::None
+    }
+}
+impl ServerSideEncryption {
+    /// Adds the headers needed to upload a file with this encryption.
+    pub fn set_upload_headers(&self, headers: &mut Headers) {
+        match *self {
+            ServerSideEncryption::None => {},
+            ServerSideEncryption::SseB2 =>
+                headers.set(XBzServerSideEncryption(AES256.to_owned())),
+            ServerSideEncryption::SseC { .. } => self.set_customer_key_headers(headers)
+        }
+    }
+    /// Adds the headers needed to upload a part of a large file with this encryption. The mode
+    /// of a large file is chosen when it is started, so only SSE-C requires headers, and the key
+    /// must be the one the large file was started with.
+    pub fn set_upload_part_headers(&self, headers: &mut Headers) {
+        self.set_customer_key_headers(headers);
+    }
+    /// Adds the headers needed to download a file with this encryption. Only SSE-C requires
+    /// headers when downloading.
+    pub fn set_download_headers(&self, headers: &mut Headers) {
+        self.set_customer_key_headers(headers);
+    }
+    fn set_customer_key_headers(&self, headers: &mut Headers) {
+        if let ServerSideEncryption::SseC { ref algorithm, ref key, ref key_md5 } = *self {
+            headers.set(XBzSseCustomerAlgorithm(algorithm.clone()));
+            headers.set(XBzSseCustomerKey(key.clone()));
+            headers.set(XBzSseCustomerKeyMd5(key_md5.clone()));
+        }
+    }
+    /// Returns the value of an encryption field in a json request, which is left out of the
+    /// request if there is no encryption.
+    pub(crate) fn request_field(&self) -> Option<&ServerSideEncryption> {
+        match *self {
+            ServerSideEncryption::None => None,
+            ref encryption => Some(encryption)
+        }
+    }
+    /// Returns the value of an encryption field in a json request that only needs the key of
+    /// SSE-C, such as the `sourceServerSideEncryption` field of a copy request.
+    pub(crate) fn customer_key_field(&self) -> Option<&ServerSideEncryption> {
+        match *self {
+            ServerSideEncryption::SseC { .. } => Some(self),
+            _ => None
+        }
+    }
+}
+impl Serialize for ServerSideEncryption {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            ServerSideEncryption::None => {
+                let mut state = serializer.serialize_struct("ServerSideEncryption", 1)?;
+                state.serialize_field("mode", &None::<&str>)?;
+                state.end()
+            },
+            ServerSideEncryption::SseB2 => {
+                let mut state = serializer.serialize_struct("ServerSideEncryption", 2)?;
+                state.serialize_field("mode", "SSE-B2")?;
+                state.serialize_field("algorithm", AES256)?;
+                state.end()
+            },
+            ServerSideEncryption::SseC { ref algorithm, ref key, ref key_md5 } => {
+                let mut state = serializer.serialize_struct("ServerSideEncryption", 4)?;
+                state.serialize_field("mode", "SSE-C")?;
+                state.serialize_field("algorithm", algorithm)?;
+                state.serialize_field("customerKey", key)?;
+                state.serialize_field("customerKeyMd5", key_md5)?;
+                state.end()
+            }
+        }
+    }
+}
+
+/// The encryption settings returned by backblaze. The mode is `SSE-B2`, `SSE-C` or missing if the
+/// file or bucket is not encrypted.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptionSettings {
+    pub mode: Option<String>,
+    pub algorithm: Option<String>
+}
+/// The default encryption of a bucket, as returned by backblaze.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultServerSideEncryption {
+    /// If false, the authorization used to list the bucket cannot read the encryption settings,
+    /// and `value` is missing.
+    pub is_client_authorized_to_read: bool,
+    pub value: Option<EncryptionSettings>
+}
@@ -6,9 +6,12 @@
 //!  [1]: ../authorize/struct.B2Authorization.html
 
 use std::fmt;
+use std::vec;
+use std::cmp::Ordering;
 
 use hyper::{self, Client};
 use hyper::client::Body;
+use hyper::mime::Mime;
 
 use serde::{Serialize, Deserialize};
 use serde::ser::Serializer;
@@ -17,10 +20,24 @@ use serde_json::{self, Value as JsonValue};
 
 use B2Error;
 use raw::authorize::B2Authorization;
+use raw::download::ByteRange;
+use raw::encryption::ServerSideEncryption;
+
+header! { (XBzAllowSnapshot, "X-Bz-Allow-Snapshot") => [String] }
+
+/// The default amount of pages a listing iterator fetches before failing with
+/// [`PaginationNotAdvancing`]. This limit can be changed with the `max_pages` method of the
+/// iterators.
+///
+///  [`PaginationNotAdvancing`]: ../../enum.B2Error.html#variant.PaginationNotAdvancing
+pub const DEFAULT_MAX_PAGES: u64 = 100_000;
 
 /// Contains information for a b2 file.
 /// This struct is returned by the function get_file_info and the functions for uploading files.
 /// This struct contains more information about the file compared to the FileInfo struct.
+///
+/// The `Display` implementation prints a one-line summary without the file info, which is
+/// usually preferable to `Debug` when logging.
 #[derive(Serialize,Deserialize,Debug,Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MoreFileInfo<InfoType=JsonValue> {
@@ -34,6 +51,12 @@ pub struct MoreFileInfo<InfoType=JsonValue> {
     pub file_info: InfoType,
     pub action: FileType,
     pub upload_timestamp: u64,
+    /// The retention of the file. This is missing if the server did not send it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_retention: Option<FileRetentionSetting>,
+    /// The legal hold of the file. This is missing if the server did not send it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legal_hold: Option<LegalHoldSetting>,
 }
 impl<IT> Into<FileInfo<IT>> for MoreFileInfo<IT> {
     fn into(self) -> FileInfo<IT> {
@@ -48,10 +71,37 @@ impl<IT> Into<FileInfo<IT>> for MoreFileInfo<IT> {
         }
     }
 }
+impl<IT> MoreFileInfo<IT> {
+    /// Returns an error if this file is not in the bucket with the id `bucket_id`.
+    ///
+    /// # Errors
+    /// Fails with [`BucketMismatch`] if the bucket ids differ.
+    ///
+    ///  [`BucketMismatch`]: ../../enum.B2Error.html#variant.BucketMismatch
+    pub fn check_bucket(&self, bucket_id: &str) -> Result<(), B2Error> {
+        if self.bucket_id == bucket_id {
+            Ok(())
+        } else {
+            Err(B2Error::BucketMismatch {
+                expected: bucket_id.to_owned(),
+                actual: self.bucket_id.clone()
+            })
+        }
+    }
+}
+impl<IT> fmt::Display for MoreFileInfo<IT> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}, {} bytes, {})", self.file_name, self.file_id, self.content_length,
+               self.action.as_str())
+    }
+}
 /// Contains information for a b2 file.
 /// This struct is returned by the file listing functions and the functions for downloading files.
 /// Some other functions return additional information about the file than this struct, and they
 /// use the struct MoreFileInfo.
+///
+/// The `Display` implementation prints a one-line summary without the file info, which is
+/// usually preferable to `Debug` when logging.
 #[derive(Serialize,Deserialize,Debug,Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct FileInfo<InfoType=JsonValue> {
@@ -63,6 +113,117 @@ pub struct FileInfo<InfoType=JsonValue> {
     pub file_info: InfoType,
     pub upload_timestamp: u64,
 }
+impl FileInfo<JsonValue> {
+    /// Returns the sha1 of this file, using the `large_file_sha1` file info for large files. See
+    /// [`effective_sha1`].
+    ///
+    ///  [`effective_sha1`]: fn.effective_sha1.html
+    pub fn effective_sha1(&self) -> Sha1Availability {
+        effective_sha1(&self.content_sha1,
+                       self.file_info.get("large_file_sha1").and_then(|sha1| sha1.as_str()))
+    }
+}
+impl<IT> fmt::Display for FileInfo<IT> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}, {} bytes)", self.file_name, self.file_id, self.content_length)
+    }
+}
+/// The retention mode of a file in a bucket with file lock enabled. Files in governance mode can
+/// be deleted by keys with the `bypassGovernance` capability, files in compliance mode cannot be
+/// deleted until the retention expires.
+#[derive(Serialize,Deserialize,Debug,Clone,Copy,PartialEq,Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionMode {
+    Governance, Compliance
+}
+/// The retention of a file. A file without retention has no mode and no timestamp.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRetention {
+    pub mode: Option<RetentionMode>,
+    /// The time in milliseconds since the epoch until which the file is retained.
+    pub retain_until_timestamp: Option<u64>
+}
+/// The retention of a file as returned by backblaze. If the authorization cannot read the
+/// retention, `value` is missing.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRetentionSetting {
+    pub is_client_authorized_to_read: bool,
+    pub value: Option<FileRetention>
+}
+/// Specifies whether a file is under legal hold. Files under legal hold cannot be deleted.
+#[derive(Serialize,Deserialize,Debug,Clone,Copy,PartialEq,Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LegalHold {
+    On, Off
+}
+/// The legal hold of a file as returned by backblaze. If the authorization cannot read the legal
+/// hold, `value` is missing.
+#[derive(Serialize,Deserialize,Debug,Clone,PartialEq,Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LegalHoldSetting {
+    pub is_client_authorized_to_read: bool,
+    pub value: Option<LegalHold>
+}
+/// The sha1 of a file, as returned by [`effective_sha1`].
+///
+///  [`effective_sha1`]: fn.effective_sha1.html
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub enum Sha1Availability {
+    /// The sha1 computed by backblaze when the file was uploaded.
+    Exact(String),
+    /// The sha1 stored in the `large_file_sha1` file info by the uploader of a large file.
+    /// Backblaze does not check this sha1.
+    LargeFileDeclared(String),
+    /// The sha1 of the file is not known.
+    Unavailable
+}
+impl Sha1Availability {
+    /// Returns the sha1, if it is known.
+    pub fn sha1(&self) -> Option<&str> {
+        match *self {
+            Sha1Availability::Exact(ref sha1) => Some(sha1),
+            Sha1Availability::LargeFileDeclared(ref sha1) => Some(sha1),
+            Sha1Availability::Unavailable => None
+        }
+    }
+}
+/// Decides which sha1 to use for a file with the given `content_sha1`, and the given
+/// `large_file_sha1` file info if it has one.
+///
+/// Backblaze reports the sha1 `none` for large files, in which case the sha1 declared by the
+/// uploader in the `large_file_sha1` file info is used. The prefix `unverified:` used for files
+/// uploaded with the sha1 at the end is ignored. Values that are not 40 hexadecimal digits are
+/// never returned as a sha1.
+///
+/// ```rust
+///use backblaze_b2::raw::files::{effective_sha1, Sha1Availability};
+///
+///let sha1 = "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed";
+///assert_eq!(effective_sha1(sha1, None), Sha1Availability::Exact(sha1.to_owned()));
+///assert_eq!(effective_sha1("none", Some(sha1)),
+///           Sha1Availability::LargeFileDeclared(sha1.to_owned()));
+///assert_eq!(effective_sha1("none", None), Sha1Availability::Unavailable);
+/// ```
+pub fn effective_sha1(content_sha1: &str, large_file_sha1: Option<&str>) -> Sha1Availability {
+    let content_sha1 = if content_sha1.starts_with("unverified:") {
+        &content_sha1[11..]
+    } else {
+        content_sha1
+    };
+    if is_sha1(content_sha1) {
+        return Sha1Availability::Exact(content_sha1.to_ascii_lowercase());
+    }
+    match large_file_sha1 {
+        Some(sha1) if content_sha1 == "none" && is_sha1(sha1) =>
+            Sha1Availability::LargeFileDeclared(sha1.to_ascii_lowercase()),
+        _ => Sha1Availability::Unavailable
+    }
+}
+fn is_sha1(s: &str) -> bool {
+    s.len() == 40 && s.bytes().all(|b| (b as char).is_digit(16))
+}
 /// Folders are not real objects stored on backblaze b2, but derived from the names of the stored
 /// files. This struct is returned by the file listing functions.
 #[derive(Serialize,Deserialize,Debug,Clone)]
@@ -85,6 +246,8 @@ pub struct HideMarkerInfo {
 pub struct UnfinishedLargeFileInfo<InfoType=JsonValue> {
     pub file_id: String,
     pub file_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket_id: Option<String>,
     pub content_type: String,
     pub file_info: InfoType,
     pub upload_timestamp: u64,
@@ -96,6 +259,24 @@ pub struct FileNameListing<InfoType=JsonValue> {
     pub files: Vec<FileInfo<InfoType>>,
     pub folders: Vec<FolderInfo>,
 }
+/// Iterating over a listing yields only the files, since folders are not stored on backblaze.
+///
+/// ```rust
+///use backblaze_b2::raw::files::{FileNameListing, FolderInfo};
+///
+///let listing: FileNameListing = FileNameListing {
+///    files: Vec::new(),
+///    folders: vec![FolderInfo { file_name: "photos/".to_owned() }]
+///};
+///assert_eq!(listing.into_iter().count(), 0);
+/// ```
+impl<InfoType> IntoIterator for FileNameListing<InfoType> {
+    type Item = FileInfo<InfoType>;
+    type IntoIter = vec::IntoIter<FileInfo<InfoType>>;
+    fn into_iter(self) -> vec::IntoIter<FileInfo<InfoType>> {
+        self.files.into_iter()
+    }
+}
 /// Contains the files, folders, hide markers and unfinished large files returned by the file
 /// version listing api.
 #[derive(Serialize,Deserialize,Debug,Clone)]
@@ -106,6 +287,87 @@ pub struct FileVersionListing<InfoType=JsonValue> {
     pub hide_markers: Vec<HideMarkerInfo>,
     pub unfinished_large_files: Vec<UnfinishedLargeFileInfo<InfoType>>,
 }
+impl<InfoType> FileVersionListing<InfoType> {
+    /// Groups the files and hide markers in this listing by file name using [`group_versions`].
+    /// The groups are sorted by file name in the order used by backblaze, as defined by
+    /// [`file_name_cmp`], and the versions in each group are sorted with the newest first.
+    ///
+    /// This requires the entire listing in memory. To group the versions of a large bucket, use
+    /// [`grouped`] on the iterator returned by [`iter_file_versions`] instead.
+    ///
+    ///  [`group_versions`]: fn.group_versions.html
+    ///  [`file_name_cmp`]: fn.file_name_cmp.html
+    ///  [`grouped`]: struct.FileVersionIter.html#method.grouped
+    ///  [`iter_file_versions`]: ../authorize/struct.B2Authorization.html#method.iter_file_versions
+    pub fn versions_grouped(self) -> Vec<VersionGroup<InfoType>> {
+        let versions = self.sorted_versions().into_iter().map(Ok);
+        // the listing cannot fail, so neither can the grouping
+        group_versions(versions).filter_map(Result::ok).collect()
+    }
+    /// Returns the newest version of every file name that is not hidden by a hide marker. This is
+    /// the set of files that would be returned by [`list_file_names`], and the files are returned
+    /// in the same order. This uses [`latest_visible_versions`] on the sorted listing.
+    ///
+    /// To compute this for a large bucket without keeping every version in memory, use
+    /// [`latest_visible`] on the iterator returned by [`iter_file_versions`].
+    ///
+    /// ```rust
+    ///#[macro_use]
+    ///extern crate serde_json;
+    ///# extern crate backblaze_b2;
+    ///use backblaze_b2::raw::files::FileVersionListing;
+    ///
+    ///# fn main() {
+    ///let file = |name: &str, id: &str, time: u64| json!({
+    ///    "fileId": id, "fileName": name, "contentLength": 1, "contentType": "text/plain",
+    ///    "contentSha1": "da39a3ee5e6b4b0d3255bfef95601890afd80709", "fileInfo": {},
+    ///    "uploadTimestamp": time
+    ///});
+    ///let hide = |name: &str, id: &str, time: u64| json!({
+    ///    "fileId": id, "fileName": name, "uploadTimestamp": time
+    ///});
+    ///let listing: FileVersionListing = serde_json::from_value(json!({
+    ///    "files": [
+    ///        file("hidden", "1", 10),
+    ///        file("reuploaded", "2", 10), file("reuploaded", "3", 30),
+    ///        file("twice", "4", 10),
+    ///    ],
+    ///    "hideMarkers": [
+    ///        hide("hidden", "5", 20),
+    ///        hide("reuploaded", "6", 20),
+    ///        hide("twice", "7", 20), hide("twice", "8", 30),
+    ///    ],
+    ///    "folders": [],
+    ///    "unfinishedLargeFiles": [],
+    ///})).unwrap();
+    ///
+    ///let visible = listing.latest_visible_versions();
+    ///assert_eq!(visible.len(), 1);
+    ///assert_eq!(visible[0].file_id, "3");
+    ///# }
+    /// ```
+    ///
+    ///  [`list_file_names`]: ../authorize/struct.B2Authorization.html#method.list_file_names
+    ///  [`latest_visible_versions`]: fn.latest_visible_versions.html
+    ///  [`latest_visible`]: struct.FileVersionIter.html#method.latest_visible
+    ///  [`iter_file_versions`]: ../authorize/struct.B2Authorization.html#method.iter_file_versions
+    pub fn latest_visible_versions(self) -> Vec<FileInfo<InfoType>> {
+        let versions = self.sorted_versions().into_iter().map(Ok);
+        latest_visible_versions(versions).filter_map(Result::ok).collect()
+    }
+    /// Returns the files and hide markers in the order of a listing from backblaze.
+    fn sorted_versions(self) -> Vec<FileVersion<InfoType>> {
+        let mut versions: Vec<(u64, FileVersion<InfoType>)> = Vec::new();
+        versions.extend(self.files.into_iter()
+                        .map(|file| (file.upload_timestamp, FileVersion::File(file))));
+        versions.extend(self.hide_markers.into_iter()
+                        .map(|hide| (hide.upload_timestamp, FileVersion::HideMarker(hide))));
+        versions.sort_by(|&(a_time, ref a), &(b_time, ref b)| {
+            file_name_cmp(a.file_name(), b.file_name()).then(b_time.cmp(&a_time))
+        });
+        versions.into_iter().map(|(_, version)| version).collect()
+    }
+}
 
 /// Methods related to the [files module][1].
 ///
@@ -113,6 +375,22 @@ pub struct FileVersionListing<InfoType=JsonValue> {
 impl B2Authorization {
     /// Performs a [b2_get_file_info][1] api call.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::files::MoreFileInfo;
+    ///
+    ///let file: MoreFileInfo = auth.get_file_info("file id", client)?;
+    ///println!("{} was uploaded at {}", file.file_name, file.upload_timestamp);
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
     /// errors, this function can fail with [`is_file_not_found`].
@@ -139,9 +417,165 @@ impl B2Authorization {
             Ok(serde_json::from_reader(resp)?)
         }
     }
+    /// Performs a [b2_get_file_info][1] api call, and checks that the file is in the bucket with
+    /// the id `bucket_id`. This guards against using a file id belonging to a different bucket,
+    /// for example one copied from another environment.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`] and [`is_bucket_mismatch`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_get_file_info.html
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_bucket_mismatch`]: ../../enum.B2Error.html#method.is_bucket_mismatch
+    pub fn get_file_info_in_bucket<IT>(&self, file_id: &str, bucket_id: &str, client: &Client)
+        -> Result<MoreFileInfo<IT>,B2Error>
+        where for<'de> IT: Deserialize<'de>
+    {
+        let file: MoreFileInfo<IT> = self.get_file_info(file_id, client)?;
+        file.check_bucket(bucket_id)?;
+        Ok(file)
+    }
+    /// Performs a [b2_copy_file][1] api call, which copies a file on the server without
+    /// downloading it. The new file is placed in the bucket `destination_bucket_id`, or in the
+    /// bucket of the source file if this is `None`.
+    ///
+    /// If a [`ByteRange`] is given, only those bytes of the source file are copied. The
+    /// [`MetadataDirective`] decides if the
+    /// content type and file info are copied from the source or replaced.
+    ///
+    /// The builder [`CopyFile`] performs the same call without mixing up the optional
+    /// arguments, and is the preferred way to make this call.
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::download::ByteRange;
+    ///use backblaze_b2::raw::files::{CopyFile, MoreFileInfo};
+    ///
+    ///// Copy the first kilobyte of the file. The range is inclusive, so it ends at 1023.
+    ///let head: MoreFileInfo = CopyFile::new("source file id", "head.bin")
+    ///    .range(ByteRange::Inclusive(0, 1023))
+    ///    .send(auth, client)?;
+    ///assert_eq!(head.content_length, 1024);
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`], [`is_bucket_not_found`],
+    /// [`is_invalid_file_name`], [`is_range_out_of_bounds`] and [`is_cap_exceeded`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_copy_file.html
+    ///  [`CopyFile`]: ../files/struct.CopyFile.html
+    ///  [`ByteRange`]: ../download/enum.ByteRange.html
+    ///  [`MetadataDirective`]: ../files/enum.MetadataDirective.html
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
+    ///  [`is_range_out_of_bounds`]: ../../enum.B2Error.html#method.is_range_out_of_bounds
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    pub fn copy_file<IT>(&self, source_file_id: &str, file_name: &str,
+                         destination_bucket_id: Option<&str>, range: Option<ByteRange>,
+                         metadata: MetadataDirective<IT>, client: &Client)
+        -> Result<MoreFileInfo<IT>, B2Error>
+        where IT: Serialize, for<'de> IT: Deserialize<'de>
+    {
+        self.copy_file_with_encryption(source_file_id, file_name, destination_bucket_id, range,
+                                       metadata, &ServerSideEncryption::None,
+                                       &ServerSideEncryption::None, client)
+    }
+    /// Performs a [b2_copy_file][1] api call like [`copy_file`], but the new file is stored
+    /// using `destination_encryption`. If the source file is encrypted with SSE-C, its key must
+    /// be given in `source_encryption`. Other source encryptions are not sent, since backblaze
+    /// can read those files without a key.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`], [`is_bucket_not_found`],
+    /// [`is_invalid_file_name`], [`is_range_out_of_bounds`] and [`is_cap_exceeded`]. If the key
+    /// of the source file is missing or wrong, the error returned by backblaze is returned.
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_copy_file.html
+    ///  [`copy_file`]: #method.copy_file
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
+    ///  [`is_range_out_of_bounds`]: ../../enum.B2Error.html#method.is_range_out_of_bounds
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    pub fn copy_file_with_encryption<IT>(&self, source_file_id: &str, file_name: &str,
+                                         destination_bucket_id: Option<&str>,
+                                         range: Option<ByteRange>,
+                                         metadata: MetadataDirective<IT>,
+                                         source_encryption: &ServerSideEncryption,
+                                         destination_encryption: &ServerSideEncryption,
+                                         client: &Client)
+        -> Result<MoreFileInfo<IT>, B2Error>
+        where IT: Serialize, for<'de> IT: Deserialize<'de>
+    {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a, InfoType> {
+            source_file_id: &'a str,
+            file_name: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            destination_bucket_id: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            range: Option<String>,
+            metadata_directive: &'static str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            content_type: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            file_info: Option<InfoType>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            source_server_side_encryption: Option<&'a ServerSideEncryption>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            destination_server_side_encryption: Option<&'a ServerSideEncryption>
+        }
+        let (directive, content_type, file_info) = match metadata {
+            MetadataDirective::Copy => ("COPY", None, None),
+            MetadataDirective::Replace { content_type, file_info } =>
+                ("REPLACE", Some(content_type.to_string()), Some(file_info))
+        };
+        let request = Request {
+            source_file_id: source_file_id,
+            file_name: file_name,
+            destination_bucket_id: destination_bucket_id,
+            range: range.map(|range| range.header_value()),
+            metadata_directive: directive,
+            content_type: content_type,
+            file_info: file_info,
+            source_server_side_encryption: source_encryption.customer_key_field(),
+            destination_server_side_encryption: destination_encryption.request_field()
+        };
+        let body: String = serde_json::to_string(&request)?;
+        let url_string: String = format!("{}/b2api/v1/b2_copy_file", self.api_url);
+        let url: &str = &url_string;
+        let resp = client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(self.auth_header())
+            .send()?;
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            Ok(serde_json::from_reader(resp)?)
+        }
+    }
     /// Performs a [b2_list_file_names][1] api call. This function returns at most max_file_count
     /// files.
     ///
+    /// The builder [`ListFileNames`] performs the same call without mixing up the optional
+    /// arguments, and is the preferred way to make this call.
+    ///
     /// In order to list all the files on b2, pass None as start_file_name on the first call to
     /// this function and to subsequent calls pass the Option returned by this function to the next
     /// call of this function, until that Option is None. This is also done by the convenience
@@ -149,12 +583,49 @@ impl B2Authorization {
     ///
     /// Filenames hidden by a hide marker are not returned.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::files::{FileNameListing, ListFileNames};
+    ///
+    ///// Pass the file name returned by each call to the next call, until it is None.
+    ///let mut start_file_name: Option<String> = None;
+    ///loop {
+    ///    let mut request = ListFileNames::new("bucket id")
+    ///        .max_file_count(1000)
+    ///        .prefix("photos/")
+    ///        .delimiter('/');
+    ///    if let Some(ref start) = start_file_name {
+    ///        request = request.start_file_name(start);
+    ///    }
+    ///    let (listing, next): (FileNameListing, Option<String>) = request.send(auth, client)?;
+    ///    for file in listing.files {
+    ///        println!("{}", file);
+    ///    }
+    ///    for folder in listing.folders {
+    ///        println!("{} is a folder", folder.file_name);
+    ///    }
+    ///    start_file_name = next;
+    ///    if start_file_name.is_none() {
+    ///        break;
+    ///    }
+    ///}
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
     /// errors, this function can fail with [`is_bucket_not_found`], [`is_invalid_file_name`],
     /// [`is_prefix_issue`], [`is_invalid_delimiter`].
     ///
     ///  [1]: https://www.backblaze.com/b2/docs/b2_list_file_names.html
+    ///  [`ListFileNames`]: ../files/struct.ListFileNames.html
     ///  [`B2Error`]: ../authorize/enum.B2Error.html
     ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
     ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
@@ -250,6 +721,22 @@ impl B2Authorization {
     ///
     /// Filenames hidden by a hide marker are not returned.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::files::FileNameListing;
+    ///
+    ///let listing: FileNameListing = auth.list_all_file_names("bucket id", 1000, None, None, client)?;
+    ///println!("the bucket contains {} files", listing.files.len());
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
     /// errors, this function can fail with [`is_bucket_not_found`], [`is_prefix_issue`],
@@ -278,13 +765,101 @@ impl B2Authorization {
         }
         Ok(fnl)
     }
+    /// Returns an iterator over every file name in the bucket, that performs the
+    /// [`list_file_names`] calls lazily as the iterator is advanced. Unlike
+    /// [`list_all_file_names`], this does not require the entire listing to fit in memory.
+    ///
+    /// The files are returned in the order used by backblaze. If an api call fails, the error is
+    /// returned by the iterator, after which the iterator returns `None`. Files returned before
+    /// the error are not affected.
+    ///
+    /// Filenames hidden by a hide marker are not returned.
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::files::FileInfo;
+    ///
+    ///for file in auth.iter_file_names("bucket id", 1000, Some("logs/"), client) {
+    ///    let file: FileInfo = file?;
+    ///    println!("{}", file);
+    ///}
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// The iterator returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, the iterator can fail with [`is_bucket_not_found`] and [`is_prefix_issue`]. If
+    /// backblaze returns a next file name that is not after the previous one, or the listing
+    /// needs more pages than allowed by [`max_pages`], the files of the last page are returned
+    /// followed by an error for which [`is_pagination_not_advancing`] returns true.
+    ///
+    ///  [`list_file_names`]: #method.list_file_names
+    ///  [`list_all_file_names`]: #method.list_all_file_names
+    ///  [`max_pages`]: ../files/struct.FileNameIter.html#method.max_pages
+    ///  [`is_pagination_not_advancing`]: ../../enum.B2Error.html#method.is_pagination_not_advancing
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_prefix_issue`]: ../../enum.B2Error.html#method.is_prefix_issue
+    pub fn iter_file_names<'a, IT>(&'a self, bucket_id: &'a str, files_per_request: u32,
+                                   prefix: Option<&'a str>, client: &'a Client)
+        -> FileNameIter<'a, IT>
+        where for<'de> IT: Deserialize<'de>
+    {
+        FileNameIter {
+            auth: self,
+            bucket_id: bucket_id,
+            files_per_request: files_per_request,
+            prefix: prefix,
+            client: client,
+            files: Vec::new().into_iter(),
+            next_file_name: None,
+            pages: 0,
+            max_pages: DEFAULT_MAX_PAGES,
+            error: None,
+            done: false
+        }
+    }
     /// Performs a [b2_list_file_versions][1] api call. This function returns at most max_file_count
     /// files.
     ///
-    /// In order to list all the files on b2, pass None as start_file_name on the first call to
-    /// this function and to subsequent calls pass the Option returned by this function to the next
-    /// call of this function, until that Option is None. This is also done by the convenience
-    /// function list_all_file_names.
+    /// The builder [`ListFileVersions`] performs the same call without mixing up the optional
+    /// arguments, and is the preferred way to make this call.
+    ///
+    /// In order to list all the files on b2, pass None as start_file_name and start_file_id on the
+    /// first call to this function and to subsequent calls pass the values returned by this
+    /// function, until both are None. Backblaze may return only one of the two values. This is
+    /// also done by [`iter_file_versions`].
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::files::{FileVersionListing, ListFileVersions};
+    ///
+    ///let (listing, next_name, next_id): (FileVersionListing, _, _) =
+    ///    ListFileVersions::new("bucket id")
+    ///    .max_file_count(1000)
+    ///    .prefix("photos/")
+    ///    .send(auth, client)?;
+    ///println!("{} versions and {} hide markers", listing.files.len(),
+    ///         listing.hide_markers.len());
+    ///if next_name.is_some() || next_id.is_some() {
+    ///    println!("there are more versions");
+    ///}
+    ///# Ok(())
+    ///# }
+    /// ```
     ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
@@ -292,6 +867,8 @@ impl B2Authorization {
     /// [`is_prefix_issue`] and [`is_invalid_delimiter`].
     ///
     ///  [1]: https://www.backblaze.com/b2/docs/b2_list_file_versions.html
+    ///  [`ListFileVersions`]: ../files/struct.ListFileVersions.html
+    ///  [`iter_file_versions`]: #method.iter_file_versions
     ///  [`B2Error`]: ../authorize/enum.B2Error.html
     ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
     ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
@@ -302,6 +879,36 @@ impl B2Authorization {
                                   delimiter: Option<char>, client: &Client)
         -> Result<(FileVersionListing<IT>, Option<String>, Option<String>), B2Error>
         where for<'de> IT: Deserialize<'de>
+    {
+        let (versions, next_file_name, next_file_id) = self.list_file_versions_in_order(
+            bucket_id, start_file_name, start_file_id, max_file_count, prefix, delimiter, client)?;
+        let mut files = Vec::new();
+        let mut folders = Vec::new();
+        let mut hides = Vec::new();
+        let mut larges = Vec::new();
+        for version in versions {
+            match version {
+                FileVersion::File(file) => files.push(file),
+                FileVersion::HideMarker(hide) => hides.push(hide),
+                FileVersion::UnfinishedLargeFile(large) => larges.push(large),
+                FileVersion::Folder(folder) => folders.push(folder),
+            }
+        }
+        Ok((FileVersionListing {
+            files: files,
+            hide_markers: hides,
+            unfinished_large_files: larges,
+            folders: folders
+        }, next_file_name, next_file_id))
+    }
+    /// Performs a b2_list_file_versions api call, keeping the versions in the order returned by
+    /// backblaze.
+    fn list_file_versions_in_order<IT>(&self, bucket_id: &str, start_file_name: Option<&str>,
+                                       start_file_id: Option<&str>, max_file_count: u32,
+                                       prefix: Option<&str>, delimiter: Option<char>,
+                                       client: &Client)
+        -> Result<(Vec<FileVersion<IT>>, Option<String>, Option<String>), B2Error>
+        where for<'de> IT: Deserialize<'de>
     {
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
@@ -331,106 +938,39 @@ impl B2Authorization {
         if resp.status != hyper::status::StatusCode::Ok {
             Err(B2Error::from_response(resp))
         } else {
-            #[derive(Deserialize)]
-            #[serde(tag = "action")]
-            #[allow(non_camel_case_types)]
-            enum LFV<InfoType> {
-                #[serde(rename_all = "camelCase")]
-                upload {
-                    file_id: String,
-                    file_name: String,
-                    content_length: u64,
-                    content_type: String,
-                    content_sha1: String,
-                    file_info: InfoType,
-                    upload_timestamp: u64,
-                },
-                #[serde(rename_all = "camelCase")]
-                start {
-                    file_id: String,
-                    file_name: String,
-                    content_type: String,
-                    file_info: InfoType,
-                    upload_timestamp: u64,
-                },
-                #[serde(rename_all = "camelCase")]
-                hide {
-                    file_id: String,
-                    file_name: String,
-                    upload_timestamp: u64,
-                },
-                #[serde(rename_all = "camelCase")]
-                folder {
-                    file_name: String
-                }
-            }
             #[derive(Deserialize)]
             #[serde(rename_all = "camelCase")]
             struct Response<InfoType> {
-                files: Vec<LFV<InfoType>>,
+                files: Vec<FileVersion<InfoType>>,
                 next_file_name: Option<String>,
                 next_file_id: Option<String>,
             }
-            let lfns: Response<IT> = serde_json::from_reader(resp)?;
-            let mut files = Vec::new();
-            let mut folders = Vec::new();
-            let mut hides = Vec::new();
-            let mut larges = Vec::new();
-            for lfn in lfns.files {
-                match lfn {
-                    LFV::folder { file_name } => folders.push(FolderInfo { file_name: file_name }),
-                    LFV::upload {
-                        file_id,
-                        file_name,
-                        content_length,
-                        content_type,
-                        content_sha1,
-                        file_info,
-                        upload_timestamp
-                    } => files.push(FileInfo {
-                        file_id: file_id,
-                        file_name: file_name,
-                        content_length: content_length,
-                        content_type: content_type,
-                        content_sha1: content_sha1,
-                        file_info: file_info,
-                        upload_timestamp: upload_timestamp
-                    }),
-                    LFV::start {
-                        file_id,
-                        file_name,
-                        content_type,
-                        file_info,
-                        upload_timestamp,
-                    } => larges.push(UnfinishedLargeFileInfo {
-                        file_id: file_id,
-                        file_name: file_name,
-                        content_type: content_type,
-                        file_info: file_info,
-                        upload_timestamp: upload_timestamp,
-                    }),
-                    LFV::hide {
-                        file_id,
-                        file_name,
-                        upload_timestamp,
-                    } => hides.push(HideMarkerInfo {
-                        file_id: file_id,
-                        file_name: file_name,
-                        upload_timestamp: upload_timestamp,
-                    }),
-                }
-            }
-            Ok((FileVersionListing {
-                files: files,
-                hide_markers: hides,
-                unfinished_large_files: larges,
-                folders: folders
-            }, lfns.next_file_name, lfns.next_file_id))
+            let response: Response<IT> = serde_json::from_reader(resp)?;
+            Ok((response.files, response.next_file_name, response.next_file_id))
         }
     }
     /// Uses the function [`list_file_versions`] several times in order to download a list of all file
     /// versions on b2.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::files::FileVersionListing;
+    ///
+    ///let listing: FileVersionListing =
+    ///    auth.list_all_file_versions("bucket id", 1000, None, None, client)?;
+    ///for file in listing.latest_visible_versions() {
+    ///    println!("{}", file);
+    ///}
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
     /// errors, this function can fail with [`is_bucket_not_found`], [`is_prefix_issue`] and
@@ -462,10 +1002,92 @@ impl B2Authorization {
         }
         Ok(fvl)
     }
+    /// Returns an iterator over every file version in the bucket, that performs the
+    /// [`list_file_versions`] calls lazily as the iterator is advanced. The versions are returned
+    /// in the order used by backblaze, that is, sorted by name and then with the newest version
+    /// first.
+    ///
+    /// If a delimiter is given, the iterator also returns folders. If an api call fails, the error
+    /// is returned by the iterator, after which the iterator returns `None`.
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::files::FileVersion;
+    ///
+    ///for version in auth.iter_file_versions("bucket id", 1000, None, Some('/'), client) {
+    ///    let version: FileVersion = version?;
+    ///    match version {
+    ///        FileVersion::File(file) => println!("{}", file),
+    ///        FileVersion::HideMarker(hide) => println!("{} is hidden", hide.file_name),
+    ///        FileVersion::UnfinishedLargeFile(large) => println!("{} is unfinished", large.file_name),
+    ///        FileVersion::Folder(folder) => println!("{} is a folder", folder.file_name),
+    ///    }
+    ///}
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// The iterator returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, the iterator can fail with [`is_bucket_not_found`], [`is_prefix_issue`] and
+    /// [`is_invalid_delimiter`]. If backblaze returns a cursor that does not advance the listing,
+    /// or the listing needs more pages than allowed by [`max_pages`], the versions of the last
+    /// page are returned followed by an error for which [`is_pagination_not_advancing`] returns
+    /// true.
+    ///
+    ///  [`list_file_versions`]: #method.list_file_versions
+    ///  [`max_pages`]: ../files/struct.FileVersionIter.html#method.max_pages
+    ///  [`is_pagination_not_advancing`]: ../../enum.B2Error.html#method.is_pagination_not_advancing
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_prefix_issue`]: ../../enum.B2Error.html#method.is_prefix_issue
+    ///  [`is_invalid_delimiter`]: ../../enum.B2Error.html#method.is_invalid_delimiter
+    pub fn iter_file_versions<'a, IT>(&'a self, bucket_id: &'a str, files_per_request: u32,
+                                      prefix: Option<&'a str>, delimiter: Option<char>,
+                                      client: &'a Client)
+        -> FileVersionIter<'a, IT>
+        where for<'de> IT: Deserialize<'de>
+    {
+        FileVersionIter {
+            auth: self,
+            bucket_id: bucket_id,
+            files_per_request: files_per_request,
+            prefix: prefix,
+            delimiter: delimiter,
+            client: client,
+            versions: Vec::new().into_iter(),
+            next_file_name: None,
+            next_file_id: None,
+            pages: 0,
+            max_pages: DEFAULT_MAX_PAGES,
+            error: None,
+            done: false
+        }
+    }
     /// Performs a [b2_delete_file_version][1] api call.
     ///
     /// This function also works on unfinished large files and hide markers.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///// Both the name and the id of the version are required.
+    ///auth.delete_file_version("photos/cat.png", "file id", client)?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
     /// errors, this function can fail with [`is_file_not_found`].
@@ -475,6 +1097,33 @@ impl B2Authorization {
     ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
     pub fn delete_file_version(&self, file_name: &str, file_id: &str, client: &Client)
         -> Result<(),B2Error>
+    {
+        self.delete_file_version_inner(file_name, file_id, false, client)
+    }
+    /// Performs a [b2_delete_file_version][1] api call with the `X-Bz-Allow-Snapshot` header set.
+    ///
+    /// Deleting a file from a snapshot bucket fails with [`is_snapshot_interaction_failure`]
+    /// unless this header is present. On other buckets this function behaves identically to
+    /// [`delete_file_version`].
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_delete_file_version.html
+    ///  [`delete_file_version`]: #method.delete_file_version
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_snapshot_interaction_failure`]: ../../enum.B2Error.html#method.is_snapshot_interaction_failure
+    pub fn delete_file_version_allow_snapshot(&self, file_name: &str, file_id: &str,
+                                              client: &Client)
+        -> Result<(),B2Error>
+    {
+        self.delete_file_version_inner(file_name, file_id, true, client)
+    }
+    fn delete_file_version_inner(&self, file_name: &str, file_id: &str, allow_snapshot: bool,
+                                 client: &Client)
+        -> Result<(),B2Error>
     {
         let url_string: String = format!("{}/b2api/v1/b2_delete_file_version", self.api_url);
         let url: &str = &url_string;
@@ -491,29 +1140,166 @@ impl B2Authorization {
         };
         let body: String = serde_json::to_string(&request)?;
 
-        let resp = client.post(url)
+        let mut req = client.post(url)
             .body(Body::BufBody(body.as_bytes(), body.len()))
-            .header(self.auth_header())
-            .send()?;
+            .header(self.auth_header());
+        if allow_snapshot {
+            req = req.header(XBzAllowSnapshot("true".to_owned()));
+        }
+        let resp = req.send()?;
         if resp.status != hyper::status::StatusCode::Ok {
             Err(B2Error::from_response(resp))
         } else {
             Ok(())
         }
     }
-    /// Performs a [b2_hide_file][1] api call.
+    /// Performs a [b2_update_file_retention][1] api call. Retention can only be set on files in
+    /// buckets with file lock enabled. Shortening or removing the retention of a file in
+    /// governance mode requires `bypass_governance`, and the retention of a file in compliance
+    /// mode cannot be shortened.
     ///
-    /// This function creates a hide marker with the given name.
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::files::{FileRetention, RetentionMode};
+    ///
+    ///let retention = FileRetention {
+    ///    mode: Some(RetentionMode::Governance),
+    ///    retain_until_timestamp: Some(1_700_000_000_000)
+    ///};
+    ///auth.update_file_retention("photos/cat.png", "file id", &retention, false, client)?;
+    ///# Ok(())
+    ///# }
+    /// ```
     ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
-    /// errors, this function can fail with [`is_file_not_found`], [`is_bucket_not_found`],
-    /// [`is_file_already_hidden`] and [`is_invalid_file_name`].
+    /// errors, this function can fail with [`is_file_not_found`] and
+    /// [`is_file_lock_violation`].
     ///
-    ///  [1]: https://www.backblaze.com/b2/docs/b2_hide_file.html
-    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_update_file_retention.html
+    ///  [`B2Error`]: ../../enum.B2Error.html
     ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
-    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_file_lock_violation`]: ../../enum.B2Error.html#method.is_file_lock_violation
+    pub fn update_file_retention(&self, file_name: &str, file_id: &str,
+                                 retention: &FileRetention, bypass_governance: bool,
+                                 client: &Client)
+        -> Result<FileRetention, B2Error>
+    {
+        // file lock is only available in version 2 of the api
+        let url_string: String = format!("{}/b2api/v2/b2_update_file_retention", self.api_url);
+        let url: &str = &url_string;
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a> {
+            file_name: &'a str,
+            file_id: &'a str,
+            file_retention: &'a FileRetention,
+            bypass_governance: bool
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            file_retention: FileRetention
+        }
+        let body: String = serde_json::to_string(&Request {
+            file_name: file_name,
+            file_id: file_id,
+            file_retention: retention,
+            bypass_governance: bypass_governance
+        })?;
+
+        let resp = client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(self.auth_header())
+            .send()?;
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            let response: Response = serde_json::from_reader(resp)?;
+            Ok(response.file_retention)
+        }
+    }
+    /// Performs a [b2_update_file_legal_hold][1] api call. Legal holds can only be set on files
+    /// in buckets with file lock enabled.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_update_file_legal_hold.html
+    ///  [`B2Error`]: ../../enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    pub fn update_file_legal_hold(&self, file_name: &str, file_id: &str, legal_hold: LegalHold,
+                                  client: &Client)
+        -> Result<LegalHold, B2Error>
+    {
+        // file lock is only available in version 2 of the api
+        let url_string: String = format!("{}/b2api/v2/b2_update_file_legal_hold", self.api_url);
+        let url: &str = &url_string;
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a> {
+            file_name: &'a str,
+            file_id: &'a str,
+            legal_hold: LegalHold
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Response {
+            legal_hold: LegalHold
+        }
+        let body: String = serde_json::to_string(&Request {
+            file_name: file_name,
+            file_id: file_id,
+            legal_hold: legal_hold
+        })?;
+
+        let resp = client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(self.auth_header())
+            .send()?;
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            let response: Response = serde_json::from_reader(resp)?;
+            Ok(response.legal_hold)
+        }
+    }
+    /// Performs a [b2_hide_file][1] api call.
+    ///
+    /// This function creates a hide marker with the given name.
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///let hide_marker = auth.hide_file("photos/cat.png", "bucket id", client)?;
+    ///println!("hidden at {}", hide_marker.upload_timestamp);
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`], [`is_bucket_not_found`],
+    /// [`is_file_already_hidden`] and [`is_invalid_file_name`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_hide_file.html
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
     ///  [`is_file_already_hidden`]: ../../enum.B2Error.html#method.is_file_already_hidden
     ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
     pub fn hide_file(&self, file_name: &str, bucket_id: &str, client: &Client)
@@ -544,6 +1330,464 @@ impl B2Authorization {
             Ok(serde_json::from_reader(resp)?)
         }
     }
+    /// Makes a file name hidden by [`hide_file`] visible again.
+    ///
+    /// Backblaze has no api call for unhiding a file. This function lists the versions of the
+    /// file using [`iter_file_versions`], and deletes every hide marker that is at least as new as
+    /// the newest uploaded version using [`delete_file_version`]. If the file is not hidden,
+    /// nothing is deleted. The returned [`UnhideStrategy`] tells what was done.
+    ///
+    /// If the first hide marker cannot be deleted because the application key lacks the
+    /// `deleteFiles` capability, or because the hide marker is protected by file lock, the newest
+    /// uploaded version is instead copied on the server using [`copy_file`], such that the copy is
+    /// newer than the hide markers. Other errors, such as an invalid authorization token, are
+    /// returned.
+    ///
+    /// If a hide marker disappears between the listing and the deletion, the versions are listed
+    /// again once. The hide markers deleted before that are still reported in
+    /// `deleted_hide_markers`.
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::files::{UnhiddenFile, UnhideStrategy};
+    ///
+    ///let unhidden: UnhiddenFile = auth.unhide_file("photos/cat.png", "bucket id", client)?;
+    ///if unhidden.strategy != UnhideStrategy::NotHidden {
+    ///    println!("{} is visible again", unhidden.file);
+    ///}
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_bucket_not_found`] and [`is_cap_exceeded`]. If the
+    /// file name has no versions, or only hide markers, a [`FileNotFound`] error is returned,
+    /// for which [`is_file_not_found`] returns true. If a hide marker disappears again after the
+    /// versions were listed the second time, a [`Conflict`] error is returned, for which
+    /// [`is_conflict`] returns true.
+    ///
+    ///  [`hide_file`]: #method.hide_file
+    ///  [`iter_file_versions`]: #method.iter_file_versions
+    ///  [`delete_file_version`]: #method.delete_file_version
+    ///  [`copy_file`]: #method.copy_file
+    ///  [`UnhideStrategy`]: ../files/enum.UnhideStrategy.html
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`FileNotFound`]: ../../enum.B2Error.html#variant.FileNotFound
+    ///  [`Conflict`]: ../../enum.B2Error.html#variant.Conflict
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_conflict`]: ../../enum.B2Error.html#method.is_conflict
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    pub fn unhide_file<IT>(&self, file_name: &str, bucket_id: &str, client: &Client)
+        -> Result<UnhiddenFile<IT>,B2Error>
+        where IT: Serialize, for<'de> IT: Deserialize<'de>
+    {
+        let mut deleted = Vec::new();
+        let mut retried = false;
+        'inspect: loop {
+            let mut files = Vec::new();
+            let mut hide_markers = Vec::new();
+            for version in self.iter_file_versions(bucket_id, 100, Some(file_name), None, client) {
+                match version? {
+                    // the versions are sorted by name, so the remaining names are different too
+                    ref version if version.file_name() != file_name => break,
+                    FileVersion::File(file) => files.push(file),
+                    FileVersion::HideMarker(hide) => hide_markers.push(hide),
+                    _ => {}
+                }
+            }
+            files.sort_by(|a, b| b.upload_timestamp.cmp(&a.upload_timestamp));
+            hide_markers.sort_by(|a, b| b.upload_timestamp.cmp(&a.upload_timestamp));
+            let file = match files.into_iter().next() {
+                Some(file) => file,
+                None => return Err(B2Error::FileNotFound {
+                    file_name: file_name.to_owned(),
+                    reason: if hide_markers.is_empty() {
+                        "the file name has no versions".to_owned()
+                    } else {
+                        "the file name only has hide markers".to_owned()
+                    }
+                })
+            };
+            let hiding: Vec<HideMarkerInfo> = hide_markers.into_iter()
+                .take_while(|hide| hide.upload_timestamp >= file.upload_timestamp)
+                .collect();
+            if hiding.is_empty() {
+                return Ok(UnhiddenFile {
+                    file: file,
+                    strategy: if deleted.is_empty() {
+                        UnhideStrategy::NotHidden
+                    } else {
+                        UnhideStrategy::DeletedHideMarkers
+                    },
+                    deleted_hide_markers: deleted
+                });
+            }
+            for hide in hiding {
+                match self.delete_file_version(&hide.file_name, &hide.file_id, client) {
+                    Ok(()) => deleted.push(hide),
+                    Err(ref err) if err.is_file_not_found() => {
+                        if retried {
+                            return Err(B2Error::Conflict {
+                                file_name: file_name.to_owned(),
+                                reason: format!("the hide marker {} was deleted by someone \
+                                                 else", hide.file_id)
+                            });
+                        }
+                        retried = true;
+                        continue 'inspect;
+                    },
+                    Err(ref err) if deleted.is_empty() && cannot_delete(err) => {
+                        let copy: MoreFileInfo<IT> = self.copy_file(
+                            &file.file_id, file_name, None, None, MetadataDirective::Copy,
+                            client)?;
+                        return Ok(UnhiddenFile {
+                            file: copy.into(),
+                            strategy: UnhideStrategy::CopiedNewestVersion,
+                            deleted_hide_markers: deleted
+                        });
+                    },
+                    Err(err) => return Err(err)
+                }
+            }
+            return Ok(UnhiddenFile {
+                file: file,
+                strategy: UnhideStrategy::DeletedHideMarkers,
+                deleted_hide_markers: deleted
+            });
+        }
+    }
+    /// Returns the version of the file that was visible at the time `at`, given in milliseconds
+    /// since the epoch like the upload timestamps. If the file did not exist or was hidden at
+    /// that time, `None` is returned. A time in the future returns the newest version.
+    ///
+    /// The versions of the file are listed newest first until a version uploaded at or before
+    /// `at` is found, as described on [`find_version_at`].
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_bucket_not_found`].
+    ///
+    ///  [`find_version_at`]: ../files/fn.find_version_at.html
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    pub fn version_at<IT>(&self, bucket_id: &str, file_name: &str, at: u64, client: &Client)
+        -> Result<Option<FileInfo<IT>>, B2Error>
+        where for<'de> IT: Deserialize<'de>
+    {
+        find_version_at(self.iter_file_versions(bucket_id, 100, Some(file_name), None, client),
+                        file_name, at)
+    }
+    /// Makes the version of the file that was visible at the time `at` the newest version, by
+    /// copying it on the server using [`copy_file`]. This works even if the file has been hidden
+    /// since then. The time is given in milliseconds since the epoch.
+    ///
+    /// If the file did not exist or was hidden at that time, nothing is copied and `None` is
+    /// returned. See [`version_at`].
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::files::MoreFileInfo;
+    ///
+    ///let restored: Option<MoreFileInfo> =
+    ///    auth.restore_version("bucket id", "config.toml", 1500000000000, client)?;
+    ///if restored.is_none() {
+    ///    println!("config.toml did not exist at that time");
+    ///}
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_bucket_not_found`] and [`is_cap_exceeded`].
+    ///
+    ///  [`copy_file`]: #method.copy_file
+    ///  [`version_at`]: #method.version_at
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    pub fn restore_version<IT>(&self, bucket_id: &str, file_name: &str, at: u64, client: &Client)
+        -> Result<Option<MoreFileInfo<IT>>, B2Error>
+        where IT: Serialize, for<'de> IT: Deserialize<'de>
+    {
+        let version: Option<FileInfo<IT>> = self.version_at(bucket_id, file_name, at, client)?;
+        match version {
+            Some(file) => Ok(Some(self.copy_file(&file.file_id, file_name, None, None,
+                                                 MetadataDirective::Copy, client)?)),
+            None => Ok(None)
+        }
+    }
+}
+
+/// The result of an [`unhide_file`] call.
+///
+///  [`unhide_file`]: ../authorize/struct.B2Authorization.html#method.unhide_file
+#[derive(Debug,Clone)]
+pub struct UnhiddenFile<InfoType=JsonValue> {
+    /// The version of the file that is now visible. If the newest version was copied, this is
+    /// the copy.
+    pub file: FileInfo<InfoType>,
+    /// How the file was made visible.
+    pub strategy: UnhideStrategy,
+    /// The hide markers that were deleted. This is empty if the file was not hidden, or if the
+    /// newest version was copied without deleting any hide markers.
+    pub deleted_hide_markers: Vec<HideMarkerInfo>,
+}
+/// Tells how [`unhide_file`] made a file visible.
+///
+///  [`unhide_file`]: ../authorize/struct.B2Authorization.html#method.unhide_file
+#[derive(Debug,Clone,Copy,Eq,PartialEq)]
+pub enum UnhideStrategy {
+    /// The file was not hidden, so nothing was changed.
+    NotHidden,
+    /// The hide markers newer than the newest uploaded version were deleted.
+    DeletedHideMarkers,
+    /// The hide markers could not be deleted, so the newest uploaded version was copied on the
+    /// server, and the copy is newer than the hide markers.
+    CopiedNewestVersion
+}
+/// Returns true if the error means that the hide marker may not be deleted, as opposed to a
+/// failure that another attempt could fix.
+fn cannot_delete(err: &B2Error) -> bool {
+    err.is_file_lock_violation() || err.is_missing_capability()
+}
+
+/// An iterator over the files in a bucket. This iterator is created by [`iter_file_names`].
+///
+///  [`iter_file_names`]: ../authorize/struct.B2Authorization.html#method.iter_file_names
+pub struct FileNameIter<'a, InfoType> {
+    auth: &'a B2Authorization,
+    bucket_id: &'a str,
+    files_per_request: u32,
+    prefix: Option<&'a str>,
+    client: &'a Client,
+    files: vec::IntoIter<FileInfo<InfoType>>,
+    next_file_name: Option<String>,
+    pages: u64,
+    max_pages: u64,
+    error: Option<B2Error>,
+    done: bool
+}
+impl<'a, IT> FileNameIter<'a, IT> {
+    /// Sets the amount of pages fetched before the iterator fails with
+    /// [`PaginationNotAdvancing`]. The default is [`DEFAULT_MAX_PAGES`].
+    ///
+    ///  [`PaginationNotAdvancing`]: ../../enum.B2Error.html#variant.PaginationNotAdvancing
+    ///  [`DEFAULT_MAX_PAGES`]: constant.DEFAULT_MAX_PAGES.html
+    pub fn max_pages(mut self, max_pages: u64) -> FileNameIter<'a, IT> {
+        self.max_pages = max_pages;
+        self
+    }
+}
+impl<'a, IT> Iterator for FileNameIter<'a, IT>
+    where for<'de> IT: Deserialize<'de>
+{
+    type Item = Result<FileInfo<IT>, B2Error>;
+    fn next(&mut self) -> Option<Result<FileInfo<IT>, B2Error>> {
+        loop {
+            if let Some(file) = self.files.next() {
+                return Some(Ok(file));
+            }
+            if let Some(err) = self.error.take() {
+                return Some(Err(err));
+            }
+            if self.done {
+                return None;
+            }
+            // a page may be empty even though more files follow, so keep going until backblaze
+            // stops returning a next file name
+            let res = self.auth.list_file_names(self.bucket_id,
+                                                self.next_file_name.as_ref().map(|s| s.as_str()),
+                                                self.files_per_request, self.prefix, None,
+                                                self.client);
+            self.pages += 1;
+            match res {
+                Ok((listing, next_file_name)) => {
+                    // a buggy server repeating the cursor would make this loop forever, so the
+                    // error is returned after the files of the page
+                    self.error = next_file_name.as_ref().and_then(|next| {
+                        let advanced = match self.next_file_name {
+                            Some(ref prev) => file_name_cmp(next, prev) == Ordering::Greater,
+                            None => true
+                        };
+                        pagination_error(advanced, self.pages, self.max_pages, next)
+                    });
+                    self.files = listing.files.into_iter();
+                    self.done = next_file_name.is_none() || self.error.is_some();
+                    self.next_file_name = next_file_name;
+                },
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+/// Specifies the content type and file info of a file created by [`copy_file`].
+///
+///  [`copy_file`]: ../authorize/struct.B2Authorization.html#method.copy_file
+#[derive(Debug,Clone)]
+pub enum MetadataDirective<InfoType=JsonValue> {
+    /// The content type and file info are copied from the source file.
+    Copy,
+    /// The content type and file info are replaced with the given values.
+    Replace { content_type: Mime, file_info: InfoType }
+}
+
+/// A single entry in a file version listing, as returned by [`iter_file_versions`].
+///
+/// When serialized, the variant is stored in the `action` field like in the responses from
+/// backblaze, so hide markers and unfinished large files can be deserialized even though they
+/// have no sha1 or length.
+///
+///  [`iter_file_versions`]: ../authorize/struct.B2Authorization.html#method.iter_file_versions
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[serde(tag = "action")]
+pub enum FileVersion<InfoType=JsonValue> {
+    #[serde(rename = "upload")]
+    File(FileInfo<InfoType>),
+    #[serde(rename = "hide")]
+    HideMarker(HideMarkerInfo),
+    #[serde(rename = "start")]
+    UnfinishedLargeFile(UnfinishedLargeFileInfo<InfoType>),
+    #[serde(rename = "folder")]
+    Folder(FolderInfo),
+}
+impl<IT> FileVersion<IT> {
+    /// Returns the name of the file or folder.
+    pub fn file_name(&self) -> &str {
+        match *self {
+            FileVersion::File(ref file) => &file.file_name,
+            FileVersion::HideMarker(ref hide) => &hide.file_name,
+            FileVersion::UnfinishedLargeFile(ref large) => &large.file_name,
+            FileVersion::Folder(ref folder) => &folder.file_name,
+        }
+    }
+}
+/// An iterator over the file versions in a bucket. This iterator is created by
+/// [`iter_file_versions`].
+///
+///  [`iter_file_versions`]: ../authorize/struct.B2Authorization.html#method.iter_file_versions
+pub struct FileVersionIter<'a, InfoType> {
+    auth: &'a B2Authorization,
+    bucket_id: &'a str,
+    files_per_request: u32,
+    prefix: Option<&'a str>,
+    delimiter: Option<char>,
+    client: &'a Client,
+    versions: vec::IntoIter<FileVersion<InfoType>>,
+    next_file_name: Option<String>,
+    next_file_id: Option<String>,
+    pages: u64,
+    max_pages: u64,
+    error: Option<B2Error>,
+    done: bool
+}
+impl<'a, IT> FileVersionIter<'a, IT> {
+    /// Sets the amount of pages fetched before the iterator fails with
+    /// [`PaginationNotAdvancing`]. The default is [`DEFAULT_MAX_PAGES`].
+    ///
+    ///  [`PaginationNotAdvancing`]: ../../enum.B2Error.html#variant.PaginationNotAdvancing
+    ///  [`DEFAULT_MAX_PAGES`]: constant.DEFAULT_MAX_PAGES.html
+    pub fn max_pages(mut self, max_pages: u64) -> FileVersionIter<'a, IT> {
+        self.max_pages = max_pages;
+        self
+    }
+    /// Returns the prefix this iterator was created with.
+    pub fn prefix(&self) -> Option<&'a str> {
+        self.prefix
+    }
+    /// Returns the delimiter this iterator was created with.
+    pub fn delimiter(&self) -> Option<char> {
+        self.delimiter
+    }
+    /// Groups the versions by file name, keeping only a single group in memory at a time. See
+    /// [`group_versions`].
+    ///
+    ///  [`group_versions`]: fn.group_versions.html
+    pub fn grouped(self) -> VersionGroups<Self, IT>
+        where for<'de> IT: Deserialize<'de>
+    {
+        group_versions(self)
+    }
+    /// Returns the newest visible version of every file name, keeping only a single group in
+    /// memory at a time. See [`latest_visible_versions`].
+    ///
+    ///  [`latest_visible_versions`]: fn.latest_visible_versions.html
+    pub fn latest_visible(self) -> LatestVisibleVersions<Self, IT>
+        where for<'de> IT: Deserialize<'de>
+    {
+        latest_visible_versions(self)
+    }
+}
+impl<'a, IT> Iterator for FileVersionIter<'a, IT>
+    where for<'de> IT: Deserialize<'de>
+{
+    type Item = Result<FileVersion<IT>, B2Error>;
+    fn next(&mut self) -> Option<Result<FileVersion<IT>, B2Error>> {
+        loop {
+            if let Some(version) = self.versions.next() {
+                return Some(Ok(version));
+            }
+            if let Some(err) = self.error.take() {
+                return Some(Err(err));
+            }
+            if self.done {
+                return None;
+            }
+            // backblaze may return only one of the two continuation values, so the listing is
+            // only complete once both are missing
+            let res = self.auth.list_file_versions_in_order(
+                self.bucket_id,
+                self.next_file_name.as_ref().map(|s| s.as_str()),
+                self.next_file_id.as_ref().map(|s| s.as_str()),
+                self.files_per_request, self.prefix, self.delimiter, self.client);
+            self.pages += 1;
+            match res {
+                Ok((versions, next_file_name, next_file_id)) => {
+                    // a buggy server repeating the cursor would make this loop forever, so the
+                    // error is returned after the versions of the page. Several versions share
+                    // a name, so the cursor also advances if only the file id changed.
+                    self.error = next_file_name.as_ref().and_then(|next| {
+                        let advanced = match self.next_file_name {
+                            Some(ref prev) => match file_name_cmp(next, prev) {
+                                Ordering::Greater => true,
+                                Ordering::Equal => next_file_id != self.next_file_id,
+                                Ordering::Less => false
+                            },
+                            None => true
+                        };
+                        pagination_error(advanced, self.pages, self.max_pages, next)
+                    });
+                    self.versions = versions.into_iter();
+                    self.done = (next_file_name.is_none() && next_file_id.is_none())
+                        || self.error.is_some();
+                    self.next_file_name = next_file_name;
+                    self.next_file_id = next_file_id;
+                },
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
 }
 
 /// Specifies if something is a file or a hide marker.
@@ -690,3 +1934,583 @@ impl Serialize for FileFolderType {
     }
 }
 
+
+/// Returns the error a listing iterator returns after fetching its `pages`th page, which returned
+/// the cursor `next`, or `None` if the listing may continue.
+pub(crate) fn pagination_error<C: fmt::Display>(advanced: bool, pages: u64, max_pages: u64,
+                                                next: C) -> Option<B2Error> {
+    if advanced && pages < max_pages {
+        None
+    } else {
+        Some(B2Error::PaginationNotAdvancing { cursor: next.to_string(), pages: pages })
+    }
+}
+
+/// Compares two file names in the order used by backblaze when listing files. Backblaze sorts
+/// file names by their UTF-8 encoding, byte for byte.
+///
+/// ```rust
+///use std::cmp::Ordering;
+///use backblaze_b2::raw::files::file_name_cmp;
+///
+///assert_eq!(file_name_cmp("a/b", "a-b"), Ordering::Greater);
+///assert_eq!(file_name_cmp("Z", "a"), Ordering::Less);
+///assert_eq!(file_name_cmp("\u{e9}", "\u{ff21}"), Ordering::Less);
+///assert_eq!(file_name_cmp("\u{ffff}", "\u{10000}"), Ordering::Less);
+/// ```
+pub fn file_name_cmp(a: &str, b: &str) -> Ordering {
+    a.as_bytes().cmp(b.as_bytes())
+}
+/// Returns the smallest valid file name that comes after `file_name` in the order used by
+/// backblaze. Passing this as the `start_file_name` of [`list_file_names`] lists the files
+/// strictly after `file_name`.
+///
+/// Backblaze does not allow characters below U+0020 in file names, so the usual trick of
+/// appending `\0` would produce a name that backblaze rejects. Instead this function appends a
+/// space, which is the lowest code point allowed in a file name. The result is still the minimal
+/// successor among valid names: a valid name greater than `file_name` either differs from it at
+/// some byte, in which case it is also greater than the result, or continues it with a character
+/// of at least U+0020, in which case it is at least the result.
+///
+/// ```rust
+///use std::cmp::Ordering;
+///use backblaze_b2::raw::files::{file_name_cmp, next_name_after};
+///
+///let next = next_name_after("photos/cat.png");
+///assert_eq!(next, "photos/cat.png ");
+///assert_eq!(file_name_cmp("photos/cat.png", &next), Ordering::Less);
+///assert_eq!(file_name_cmp(&next, "photos/cat.png!"), Ordering::Less);
+///assert_eq!(file_name_cmp(&next, "photos/cat.pnh"), Ordering::Less);
+/// ```
+///
+///  [`list_file_names`]: ../authorize/struct.B2Authorization.html#method.list_file_names
+pub fn next_name_after(file_name: &str) -> String {
+    format!("{} ", file_name)
+}
+
+/// A builder for a [`list_file_names`] call, which avoids mixing up the many optional arguments.
+///
+/// ```rust,no_run
+///# extern crate hyper;
+///# extern crate backblaze_b2;
+///# use hyper::Client;
+///# use backblaze_b2::B2Error;
+///# use backblaze_b2::raw::authorize::B2Authorization;
+///# fn main() {}
+///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+///use backblaze_b2::raw::files::{FileNameListing, ListFileNames};
+///
+///let (listing, next): (FileNameListing, Option<String>) = ListFileNames::new("bucket id")
+///    .max_file_count(1000)
+///    .prefix("photos/")
+///    .delimiter('/')
+///    .send(auth, client)?;
+///# Ok(())
+///# }
+/// ```
+///
+///  [`list_file_names`]: ../authorize/struct.B2Authorization.html#method.list_file_names
+#[derive(Debug,Clone,Copy)]
+pub struct ListFileNames<'a> {
+    bucket_id: &'a str,
+    start_file_name: Option<&'a str>,
+    max_file_count: u32,
+    prefix: Option<&'a str>,
+    delimiter: Option<char>
+}
+impl<'a> ListFileNames<'a> {
+    /// Creates a listing of the bucket with the id `bucket_id`. By default at most 100 files are
+    /// returned, starting at the first file in the bucket.
+    pub fn new(bucket_id: &'a str) -> ListFileNames<'a> {
+        ListFileNames {
+            bucket_id: bucket_id,
+            start_file_name: None,
+            max_file_count: 100,
+            prefix: None,
+            delimiter: None
+        }
+    }
+    /// Starts the listing at the given file name.
+    pub fn start_file_name(mut self, start_file_name: &'a str) -> ListFileNames<'a> {
+        self.start_file_name = Some(start_file_name);
+        self
+    }
+    /// Sets the maximum amount of files returned.
+    pub fn max_file_count(mut self, max_file_count: u32) -> ListFileNames<'a> {
+        self.max_file_count = max_file_count;
+        self
+    }
+    /// Only lists files whose name starts with the prefix.
+    pub fn prefix(mut self, prefix: &'a str) -> ListFileNames<'a> {
+        self.prefix = Some(prefix);
+        self
+    }
+    /// Returns the files in subfolders as folders, see [`list_file_names`].
+    ///
+    ///  [`list_file_names`]: ../authorize/struct.B2Authorization.html#method.list_file_names
+    pub fn delimiter(mut self, delimiter: char) -> ListFileNames<'a> {
+        self.delimiter = Some(delimiter);
+        self
+    }
+    /// Performs the [`list_file_names`] call.
+    ///
+    /// # Errors
+    /// See [`list_file_names`].
+    ///
+    ///  [`list_file_names`]: ../authorize/struct.B2Authorization.html#method.list_file_names
+    pub fn send<IT>(&self, auth: &B2Authorization, client: &Client)
+        -> Result<(FileNameListing<IT>, Option<String>), B2Error>
+        where for<'de> IT: Deserialize<'de>
+    {
+        auth.list_file_names(self.bucket_id, self.start_file_name, self.max_file_count,
+                             self.prefix, self.delimiter, client)
+    }
+}
+
+/// A builder for a [`list_file_versions`] call, which avoids mixing up the many optional
+/// arguments.
+///
+/// ```rust,no_run
+///# extern crate hyper;
+///# extern crate backblaze_b2;
+///# use hyper::Client;
+///# use backblaze_b2::B2Error;
+///# use backblaze_b2::raw::authorize::B2Authorization;
+///# fn main() {}
+///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+///use backblaze_b2::raw::files::{FileVersionListing, ListFileVersions};
+///
+///let (listing, next_name, next_id): (FileVersionListing, Option<String>, Option<String>) =
+///    ListFileVersions::new("bucket id").prefix("photos/").send(auth, client)?;
+///# Ok(())
+///# }
+/// ```
+///
+///  [`list_file_versions`]: ../authorize/struct.B2Authorization.html#method.list_file_versions
+#[derive(Debug,Clone,Copy)]
+pub struct ListFileVersions<'a> {
+    bucket_id: &'a str,
+    start_file_name: Option<&'a str>,
+    start_file_id: Option<&'a str>,
+    max_file_count: u32,
+    prefix: Option<&'a str>,
+    delimiter: Option<char>
+}
+impl<'a> ListFileVersions<'a> {
+    /// Creates a listing of the bucket with the id `bucket_id`. By default at most 100 files are
+    /// returned, starting at the first file in the bucket.
+    pub fn new(bucket_id: &'a str) -> ListFileVersions<'a> {
+        ListFileVersions {
+            bucket_id: bucket_id,
+            start_file_name: None,
+            start_file_id: None,
+            max_file_count: 100,
+            prefix: None,
+            delimiter: None
+        }
+    }
+    /// Starts the listing at the given file name and file id. The file id may only be given
+    /// together with a file name.
+    pub fn start(mut self, start_file_name: &'a str, start_file_id: Option<&'a str>)
+        -> ListFileVersions<'a>
+    {
+        self.start_file_name = Some(start_file_name);
+        self.start_file_id = start_file_id;
+        self
+    }
+    /// Sets the maximum amount of files returned.
+    pub fn max_file_count(mut self, max_file_count: u32) -> ListFileVersions<'a> {
+        self.max_file_count = max_file_count;
+        self
+    }
+    /// Only lists files whose name starts with the prefix.
+    pub fn prefix(mut self, prefix: &'a str) -> ListFileVersions<'a> {
+        self.prefix = Some(prefix);
+        self
+    }
+    /// Returns the files in subfolders as folders, see [`list_file_versions`].
+    ///
+    ///  [`list_file_versions`]: ../authorize/struct.B2Authorization.html#method.list_file_versions
+    pub fn delimiter(mut self, delimiter: char) -> ListFileVersions<'a> {
+        self.delimiter = Some(delimiter);
+        self
+    }
+    /// Performs the [`list_file_versions`] call.
+    ///
+    /// # Errors
+    /// See [`list_file_versions`].
+    ///
+    ///  [`list_file_versions`]: ../authorize/struct.B2Authorization.html#method.list_file_versions
+    pub fn send<IT>(&self, auth: &B2Authorization, client: &Client)
+        -> Result<(FileVersionListing<IT>, Option<String>, Option<String>), B2Error>
+        where for<'de> IT: Deserialize<'de>
+    {
+        auth.list_file_versions(self.bucket_id, self.start_file_name, self.start_file_id,
+                                self.max_file_count, self.prefix, self.delimiter, client)
+    }
+}
+/// A builder for a [`copy_file`] call, which avoids mixing up the many optional arguments. By
+/// default the whole file is copied into the same bucket, the content type and file info are
+/// copied from the source file, and no encryption is requested.
+///
+/// ```rust,no_run
+///#[macro_use]
+///extern crate serde_json;
+///# extern crate hyper;
+///# extern crate backblaze_b2;
+///# use hyper::Client;
+///# use backblaze_b2::B2Error;
+///# use backblaze_b2::raw::authorize::B2Authorization;
+///# fn main() {}
+///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+///use backblaze_b2::raw::download::ByteRange;
+///use backblaze_b2::raw::files::{CopyFile, MetadataDirective, MoreFileInfo};
+///
+///// Copy the first kilobyte of the file. The range is inclusive, so it ends at 1023.
+///let head: MoreFileInfo = CopyFile::new("source file id", "head.bin")
+///    .range(ByteRange::Inclusive(0, 1023))
+///    .send(auth, client)?;
+///assert_eq!(head.content_length, 1024);
+///
+///// Copy the whole file to another bucket, replacing the content type and file info.
+///let copy: MoreFileInfo = CopyFile::new("source file id", "copy.txt")
+///    .destination_bucket_id("other bucket id")
+///    .metadata(MetadataDirective::Replace {
+///        content_type: "text/plain".parse().unwrap(),
+///        file_info: json!({"author": "unknown"})
+///    })
+///    .send(auth, client)?;
+///# Ok(())
+///# }
+/// ```
+///
+///  [`copy_file`]: ../authorize/struct.B2Authorization.html#method.copy_file
+#[derive(Debug,Clone)]
+pub struct CopyFile<'a, InfoType=JsonValue> {
+    source_file_id: &'a str,
+    file_name: &'a str,
+    destination_bucket_id: Option<&'a str>,
+    range: Option<ByteRange>,
+    metadata: MetadataDirective<InfoType>,
+    source_encryption: &'a ServerSideEncryption,
+    destination_encryption: &'a ServerSideEncryption
+}
+static NO_ENCRYPTION: ServerSideEncryption = ServerSideEncryption::None;
+impl<'a> CopyFile<'a, JsonValue> {
+    /// Creates a copy of the file with the id `source_file_id` named `file_name`.
+    pub fn new(source_file_id: &'a str, file_name: &'a str) -> CopyFile<'a, JsonValue> {
+        CopyFile {
+            source_file_id: source_file_id,
+            file_name: file_name,
+            destination_bucket_id: None,
+            range: None,
+            metadata: MetadataDirective::Copy,
+            source_encryption: &NO_ENCRYPTION,
+            destination_encryption: &NO_ENCRYPTION
+        }
+    }
+}
+impl<'a, IT> CopyFile<'a, IT> {
+    /// Stores the copy in the bucket with the id `bucket_id`.
+    pub fn destination_bucket_id(mut self, bucket_id: &'a str) -> CopyFile<'a, IT> {
+        self.destination_bucket_id = Some(bucket_id);
+        self
+    }
+    /// Only copies the bytes in the range.
+    pub fn range(mut self, range: ByteRange) -> CopyFile<'a, IT> {
+        self.range = Some(range);
+        self
+    }
+    /// Sets whether the content type and file info are copied or replaced.
+    pub fn metadata<T>(self, metadata: MetadataDirective<T>) -> CopyFile<'a, T> {
+        CopyFile {
+            source_file_id: self.source_file_id,
+            file_name: self.file_name,
+            destination_bucket_id: self.destination_bucket_id,
+            range: self.range,
+            metadata: metadata,
+            source_encryption: self.source_encryption,
+            destination_encryption: self.destination_encryption
+        }
+    }
+    /// Sets the key of a source file encrypted with SSE-C, see [`copy_file_with_encryption`].
+    ///
+    ///  [`copy_file_with_encryption`]: ../authorize/struct.B2Authorization.html#method.copy_file_with_encryption
+    pub fn source_encryption(mut self, encryption: &'a ServerSideEncryption) -> CopyFile<'a, IT> {
+        self.source_encryption = encryption;
+        self
+    }
+    /// Stores the copy using the given encryption.
+    pub fn destination_encryption(mut self, encryption: &'a ServerSideEncryption)
+        -> CopyFile<'a, IT>
+    {
+        self.destination_encryption = encryption;
+        self
+    }
+    /// Performs the [`copy_file_with_encryption`] call.
+    ///
+    /// # Errors
+    /// See [`copy_file_with_encryption`].
+    ///
+    ///  [`copy_file_with_encryption`]: ../authorize/struct.B2Authorization.html#method.copy_file_with_encryption
+    pub fn send(self, auth: &B2Authorization, client: &Client)
+        -> Result<MoreFileInfo<IT>, B2Error>
+        where IT: Serialize, for<'de> IT: Deserialize<'de>
+    {
+        auth.copy_file_with_encryption(self.source_file_id, self.file_name,
+                                       self.destination_bucket_id, self.range, self.metadata,
+                                       self.source_encryption, self.destination_encryption,
+                                       client)
+    }
+}
+/// Finds the version of the file `file_name` that was visible at the time `at` in a listing of
+/// file versions, which must be sorted like the listings returned by backblaze, with the versions
+/// of each name newest first. This is the selection logic of [`version_at`].
+///
+/// The listing is only consumed until a version of `file_name` uploaded at or before `at` is
+/// found. If that version is a hide marker, the file was hidden at that time and `None` is
+/// returned. Unfinished large files are skipped.
+///
+/// ```rust
+///use backblaze_b2::raw::files::{find_version_at, FileInfo, FileVersion, HideMarkerInfo};
+///
+///let file = |id: &str, timestamp: u64| FileVersion::File(FileInfo {
+///    file_id: id.to_owned(), file_name: "a.txt".to_owned(), content_length: 0,
+///    content_type: "text/plain".to_owned(), content_sha1: "none".to_owned(),
+///    file_info: Default::default(), upload_timestamp: timestamp
+///});
+///let hide = FileVersion::HideMarker(HideMarkerInfo {
+///    file_id: "hide".to_owned(), file_name: "a.txt".to_owned(), upload_timestamp: 30
+///});
+///let listing: Vec<FileVersion> = vec![hide, file("new", 20), file("old", 10)];
+///
+///let at_25 = find_version_at(listing.clone().into_iter().map(Ok), "a.txt", 25).unwrap();
+///assert_eq!(at_25.unwrap().file_id, "new");
+///assert!(find_version_at(listing.clone().into_iter().map(Ok), "a.txt", 30).unwrap().is_none());
+///assert!(find_version_at(listing.into_iter().map(Ok), "a.txt", 5).unwrap().is_none());
+/// ```
+///
+/// # Errors
+/// Errors returned by the listing are returned.
+///
+///  [`version_at`]: ../authorize/struct.B2Authorization.html#method.version_at
+pub fn find_version_at<IT, I>(versions: I, file_name: &str, at: u64)
+    -> Result<Option<FileInfo<IT>>, B2Error>
+    where I: IntoIterator<Item=Result<FileVersion<IT>, B2Error>>
+{
+    for version in versions {
+        let version = version?;
+        match file_name_cmp(version.file_name(), file_name) {
+            Ordering::Less => continue,
+            Ordering::Greater => break,
+            Ordering::Equal => {}
+        }
+        match version {
+            FileVersion::File(file) => if file.upload_timestamp <= at {
+                return Ok(Some(file));
+            },
+            FileVersion::HideMarker(hide) => if hide.upload_timestamp <= at {
+                return Ok(None);
+            },
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+/// Every uploaded version and hide marker of a single file name, as yielded by
+/// [`group_versions`] and [`versions_grouped`]. This struct owns the versions, so it can be
+/// created from a stream of versions such as [`iter_file_versions`].
+///
+///  [`group_versions`]: fn.group_versions.html
+///  [`versions_grouped`]: struct.FileVersionListing.html#method.versions_grouped
+///  [`iter_file_versions`]: ../authorize/struct.B2Authorization.html#method.iter_file_versions
+#[derive(Debug,Clone)]
+pub struct VersionGroup<InfoType=JsonValue> {
+    pub file_name: String,
+    /// The uploaded versions and hide markers of the file in the order of the listing, which is
+    /// newest first.
+    pub versions: Vec<FileVersion<InfoType>>,
+}
+impl<IT> VersionGroup<IT> {
+    /// Returns the index in `versions` of the newest uploaded version, unless a hide marker is at
+    /// least as new as it.
+    fn latest_visible_index(&self) -> Option<usize> {
+        let mut newest_file: Option<(usize, u64)> = None;
+        let mut newest_hide: Option<u64> = None;
+        for (i, version) in self.versions.iter().enumerate() {
+            match *version {
+                FileVersion::File(ref file) => match newest_file {
+                    Some((_, time)) if time >= file.upload_timestamp => {},
+                    _ => newest_file = Some((i, file.upload_timestamp))
+                },
+                FileVersion::HideMarker(ref hide) => match newest_hide {
+                    Some(time) if time >= hide.upload_timestamp => {},
+                    _ => newest_hide = Some(hide.upload_timestamp)
+                },
+                _ => {}
+            }
+        }
+        match (newest_file, newest_hide) {
+            (Some((_, file)), Some(hide)) if hide >= file => None,
+            (Some((i, _)), _) => Some(i),
+            (None, _) => None
+        }
+    }
+    /// Returns the newest uploaded version, unless a hide marker is at least as new as it.
+    pub fn latest_visible(&self) -> Option<&FileInfo<IT>> {
+        let i = self.latest_visible_index()?;
+        match self.versions[i] {
+            FileVersion::File(ref file) => Some(file),
+            _ => None
+        }
+    }
+    /// Like [`latest_visible`], but returns the version by value.
+    ///
+    ///  [`latest_visible`]: #method.latest_visible
+    pub fn into_latest_visible(mut self) -> Option<FileInfo<IT>> {
+        match self.latest_visible_index().map(|i| self.versions.swap_remove(i)) {
+            Some(FileVersion::File(file)) => Some(file),
+            _ => None
+        }
+    }
+}
+/// Groups a listing of file versions by file name. The listing must be sorted like the listings
+/// returned by backblaze, so that the versions of each name are adjacent. This is typically the
+/// iterator returned by [`iter_file_versions`].
+///
+/// The listing is consumed lazily, and only the versions of a single file name are kept in memory
+/// at a time. Folders and unfinished large files are skipped.
+///
+/// ```rust
+///use backblaze_b2::raw::files::{group_versions, FileInfo, FileVersion, HideMarkerInfo};
+///
+///let file = |name: &str, id: &str, timestamp: u64| FileVersion::File(FileInfo {
+///    file_id: id.to_owned(), file_name: name.to_owned(), content_length: 0,
+///    content_type: "text/plain".to_owned(), content_sha1: "none".to_owned(),
+///    file_info: Default::default(), upload_timestamp: timestamp
+///});
+///let hide = FileVersion::HideMarker(HideMarkerInfo {
+///    file_id: "hide".to_owned(), file_name: "a.txt".to_owned(), upload_timestamp: 30
+///});
+///let listing: Vec<FileVersion> = vec![hide, file("a.txt", "a", 20), file("b.txt", "b", 10)];
+///
+///let groups: Vec<_> = group_versions(listing.into_iter().map(Ok))
+///    .collect::<Result<_, _>>().unwrap();
+///assert_eq!(groups.len(), 2);
+///assert_eq!(groups[0].file_name, "a.txt");
+///assert_eq!(groups[0].versions.len(), 2);
+///assert!(groups[0].latest_visible().is_none());
+///assert_eq!(groups[1].latest_visible().unwrap().file_id, "b");
+/// ```
+///
+/// # Errors
+/// If the listing returns an error, the versions of the group being collected are discarded since
+/// the group is incomplete, and the error is returned. The iterator ends after an error.
+///
+///  [`iter_file_versions`]: ../authorize/struct.B2Authorization.html#method.iter_file_versions
+pub fn group_versions<IT, I>(versions: I) -> VersionGroups<I::IntoIter, IT>
+    where I: IntoIterator<Item=Result<FileVersion<IT>, B2Error>>
+{
+    VersionGroups {
+        versions: versions.into_iter(),
+        next: None,
+        done: false
+    }
+}
+/// Returns the newest visible version of every file name in a listing of file versions, which is
+/// the set of files returned by [`list_file_names`]. The listing must be sorted like the listings
+/// returned by backblaze, and is consumed lazily using [`group_versions`].
+///
+/// A file name is not visible if its newest hide marker is at least as new as its newest upload,
+/// so a file that was hidden and uploaded again is visible, while a file with several hide
+/// markers stays hidden.
+///
+/// # Errors
+/// Errors returned by the listing are returned, after which the iterator ends.
+///
+///  [`list_file_names`]: ../authorize/struct.B2Authorization.html#method.list_file_names
+///  [`group_versions`]: fn.group_versions.html
+pub fn latest_visible_versions<IT, I>(versions: I) -> LatestVisibleVersions<I::IntoIter, IT>
+    where I: IntoIterator<Item=Result<FileVersion<IT>, B2Error>>
+{
+    LatestVisibleVersions {
+        groups: group_versions(versions)
+    }
+}
+/// An iterator over the versions of a listing grouped by file name. This iterator is created by
+/// [`group_versions`].
+///
+///  [`group_versions`]: fn.group_versions.html
+pub struct VersionGroups<I, InfoType> {
+    versions: I,
+    // the first version of the next group
+    next: Option<FileVersion<InfoType>>,
+    done: bool
+}
+impl<I, IT> Iterator for VersionGroups<I, IT>
+    where I: Iterator<Item=Result<FileVersion<IT>, B2Error>>
+{
+    type Item = Result<VersionGroup<IT>, B2Error>;
+    fn next(&mut self) -> Option<Result<VersionGroup<IT>, B2Error>> {
+        let mut group: Option<VersionGroup<IT>> = None;
+        loop {
+            let version = match self.next.take() {
+                Some(version) => version,
+                None if self.done => return group.map(Ok),
+                None => match self.versions.next() {
+                    Some(Ok(version)) => version,
+                    Some(Err(err)) => {
+                        self.done = true;
+                        return Some(Err(err));
+                    },
+                    None => {
+                        self.done = true;
+                        return group.map(Ok);
+                    }
+                }
+            };
+            match version {
+                FileVersion::File(_) | FileVersion::HideMarker(_) => {},
+                _ => continue
+            }
+            match group {
+                Some(ref mut group) if group.file_name == version.file_name() => {
+                    group.versions.push(version);
+                    continue;
+                },
+                Some(_) => {},
+                None => {
+                    group = Some(VersionGroup {
+                        file_name: version.file_name().to_owned(),
+                        versions: vec![version]
+                    });
+                    continue;
+                }
+            }
+            self.next = Some(version);
+            return group.map(Ok);
+        }
+    }
+}
+/// An iterator over the newest visible version of every file name in a listing. This iterator is
+/// created by [`latest_visible_versions`].
+///
+///  [`latest_visible_versions`]: fn.latest_visible_versions.html
+pub struct LatestVisibleVersions<I, InfoType> {
+    groups: VersionGroups<I, InfoType>
+}
+impl<I, IT> Iterator for LatestVisibleVersions<I, IT>
+    where I: Iterator<Item=Result<FileVersion<IT>, B2Error>>
+{
+    type Item = Result<FileInfo<IT>, B2Error>;
+    fn next(&mut self) -> Option<Result<FileInfo<IT>, B2Error>> {
+        loop {
+            match self.groups.next()? {
+                Ok(group) => if let Some(file) = group.into_latest_visible() {
+                    return Some(Ok(file));
+                },
+                Err(err) => return Some(Err(err))
+            }
+        }
+    }
+}
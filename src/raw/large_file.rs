@@ -0,0 +1,1300 @@
+//! This module defines various methods and structs for uploading large files to backblaze.
+//!
+//! Files larger than 5 GB cannot be uploaded with a single request, and must instead be uploaded
+//! in several parts. A large file is created using [start_large_file][1], after which each part
+//! is uploaded using an [UploadPartAuthorization][2] obtained with [get_upload_part_url][3].
+//! Finally the parts are combined into a single file with [finish_large_file][4].
+//!
+//! The method [upload_large_file][5] performs all of these steps for a single [Read][6], and
+//! the builder [UploadLargeFile][7] can also upload several parts in parallel.
+//!
+//!  [1]: ../authorize/struct.B2Authorization.html#method.start_large_file
+//!  [2]: struct.UploadPartAuthorization.html
+//!  [3]: ../authorize/struct.B2Authorization.html#method.get_upload_part_url
+//!  [4]: ../authorize/struct.B2Authorization.html#method.finish_large_file
+//!  [5]: ../authorize/struct.B2Authorization.html#method.upload_large_file
+//!  [6]: https://doc.rust-lang.org/stable/std/io/trait.Read.html
+//!  [7]: struct.UploadLargeFile.html
+
+use std::cmp;
+use std::io::{self, Read, Write};
+use std::panic;
+use std::sync::{mpsc, Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::vec;
+
+use hyper::{self, Client, Url};
+use hyper::client::Body;
+use hyper::client::request::Request;
+use hyper::header::{ContentLength, Headers};
+use hyper::mime::Mime;
+use hyper::method::Method;
+use hyper::net::{NetworkConnector, NetworkStream};
+
+use serde::{Serialize, Deserialize};
+use serde_json::{self, Value as JsonValue};
+
+use sha1::Sha1;
+
+use B2Error;
+use B2AuthHeader;
+use raw::authorize::{B2Authorization, check_header_value, check_response, check_url};
+use raw::download::ByteRange;
+use raw::encryption::ServerSideEncryption;
+use raw::files::{MoreFileInfo, UnfinishedLargeFileInfo, DEFAULT_MAX_PAGES, pagination_error};
+use raw::upload::XBzContentSha1;
+use retry::{upload_recovery_action, RecoveryAction, RetryPolicy, UploadContext};
+
+header! { (XBzPartNumber, "X-Bz-Part-Number") => [u32] }
+
+/// The maximum amount of parts in a large file.
+pub const MAX_PART_COUNT: u32 = 10000;
+/// The amount of times [`upload_large_file`] attempts to upload a part before giving up.
+///
+///  [`upload_large_file`]: ../authorize/struct.B2Authorization.html#method.upload_large_file
+pub const PART_ATTEMPTS: u32 = 3;
+
+/// The policy used to retry parts in [`upload_large_file`]: the default [`RetryPolicy`] limited to
+/// [`PART_ATTEMPTS`] attempts.
+///
+///  [`upload_large_file`]: ../authorize/struct.B2Authorization.html#method.upload_large_file
+///  [`RetryPolicy`]: ../../retry/struct.RetryPolicy.html
+///  [`PART_ATTEMPTS`]: constant.PART_ATTEMPTS.html
+pub(crate) fn part_retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: PART_ATTEMPTS,
+        ..RetryPolicy::default()
+    }
+}
+
+/// Contains the information needed to upload parts of a large file. This struct is usually
+/// obtained from a [B2Authorization][1] using the method [get_upload_part_url][2].
+///
+/// Like an [`UploadAuthorization`], this url may not be used for several uploads in parallel.
+///
+///  [1]: ../authorize/struct.B2Authorization.html
+///  [2]: ../authorize/struct.B2Authorization.html#method.get_upload_part_url
+///  [`UploadAuthorization`]: ../upload/struct.UploadAuthorization.html
+#[derive(Deserialize,Serialize,Clone,Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadPartAuthorization {
+    pub file_id: String,
+    pub upload_url: String,
+    pub authorization_token: String
+}
+impl UploadPartAuthorization {
+    /// Returns a hyper header that authorizes an upload request.
+    pub fn auth_header(&self) -> B2AuthHeader {
+        B2AuthHeader(self.authorization_token.clone())
+    }
+    /// Checks that the authorization token can be used as a header value, and that the upload
+    /// url looks like a url. This is useful when the upload url is loaded from storage, see
+    /// [`B2Authorization::validate`].
+    ///
+    /// # Errors
+    /// This function returns an [`InvalidRequest`] error naming the first invalid field.
+    ///
+    ///  [`B2Authorization::validate`]: ../authorize/struct.B2Authorization.html#method.validate
+    ///  [`InvalidRequest`]: ../../enum.B2Error.html#variant.InvalidRequest
+    pub fn validate(&self) -> Result<(), B2Error> {
+        check_header_value("authorization_token", &self.authorization_token)?;
+        check_url("upload_url", &self.upload_url)?;
+        Ok(())
+    }
+}
+
+/// Contains information about an uploaded part of a large file.
+#[derive(Deserialize,Serialize,Clone,Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PartInfo {
+    pub file_id: String,
+    pub part_number: u32,
+    pub content_length: u64,
+    pub content_sha1: String
+}
+
+/// Contains information about a part of a large file, as returned by [`list_parts`].
+///
+///  [`list_parts`]: ../authorize/struct.B2Authorization.html#method.list_parts
+#[derive(Deserialize,Serialize,Clone,Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedPart {
+    pub file_id: String,
+    pub part_number: u32,
+    pub content_length: u64,
+    pub content_sha1: String,
+    pub upload_timestamp: u64
+}
+
+/// Contains information about a large file that was cancelled.
+#[derive(Deserialize,Serialize,Clone,Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelledLargeFile {
+    pub file_id: String,
+    pub account_id: String,
+    pub bucket_id: String,
+    pub file_name: String
+}
+
+/// Methods related to the [large_file module][1].
+///
+///  [1]: ../large_file/index.html
+impl B2Authorization {
+    /// Performs a [b2_start_large_file][1] api call. The content type is detected by backblaze
+    /// from the file name if no content type is specified.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_bucket_not_found`], [`is_invalid_file_name`] and
+    /// [`is_cap_exceeded`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_start_large_file.html
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    pub fn start_large_file<IT>(&self, bucket_id: &str, file_name: &str,
+                                content_type: Option<Mime>, file_info: IT, client: &Client)
+        -> Result<UnfinishedLargeFileInfo<IT>, B2Error>
+        where IT: Serialize, for<'de> IT: Deserialize<'de>
+    {
+        self.start_large_file_with_encryption(bucket_id, file_name, content_type, file_info,
+                                              &ServerSideEncryption::None, client)
+    }
+    /// Performs a [b2_start_large_file][1] api call like [`start_large_file`], but the large
+    /// file is stored using the given server-side encryption. If the encryption is SSE-C, the
+    /// same key must be passed to [`upload_part_with_encryption`] and
+    /// [`copy_part_with_encryption`] for every part.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_bucket_not_found`], [`is_invalid_file_name`] and
+    /// [`is_cap_exceeded`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_start_large_file.html
+    ///  [`start_large_file`]: #method.start_large_file
+    ///  [`upload_part_with_encryption`]: ../large_file/struct.UploadPartAuthorization.html#method.upload_part_with_encryption
+    ///  [`copy_part_with_encryption`]: #method.copy_part_with_encryption
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    pub fn start_large_file_with_encryption<IT>(&self, bucket_id: &str, file_name: &str,
+                                                content_type: Option<Mime>, file_info: IT,
+                                                encryption: &ServerSideEncryption,
+                                                client: &Client)
+        -> Result<UnfinishedLargeFileInfo<IT>, B2Error>
+        where IT: Serialize, for<'de> IT: Deserialize<'de>
+    {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a, InfoType> {
+            bucket_id: &'a str,
+            file_name: &'a str,
+            content_type: String,
+            file_info: InfoType,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            server_side_encryption: Option<&'a ServerSideEncryption>
+        }
+        let request = Request {
+            bucket_id: bucket_id,
+            file_name: file_name,
+            content_type: match content_type {
+                Some(mime) => mime.to_string(),
+                None => "b2/x-auto".to_owned()
+            },
+            file_info: file_info,
+            server_side_encryption: encryption.request_field()
+        };
+        let body: String = serde_json::to_string(&request)?;
+        let url_string: String = format!("{}/b2api/v1/b2_start_large_file", self.api_url);
+        let url: &str = &url_string;
+        let resp = client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(self.auth_header())
+            .send()?;
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            Ok(serde_json::from_reader(resp)?)
+        }
+    }
+    /// Performs a [b2_get_upload_part_url][1] api call and returns the upload url wrapped in an
+    /// [`UploadPartAuthorization`].
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_get_upload_part_url.html
+    ///  [`UploadPartAuthorization`]: struct.UploadPartAuthorization.html
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    pub fn get_upload_part_url(&self, file_id: &str, client: &Client)
+        -> Result<UploadPartAuthorization, B2Error>
+    {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a> {
+            file_id: &'a str
+        }
+        let request = Request {
+            file_id: file_id
+        };
+        let body: String = serde_json::to_string(&request)?;
+        let url_string: String = format!("{}/b2api/v1/b2_get_upload_part_url", self.api_url);
+        let url: &str = &url_string;
+        let resp = client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(self.auth_header())
+            .send()?;
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            let upload_auth: UploadPartAuthorization = serde_json::from_reader(resp)?;
+            check_response("b2_get_upload_part_url", upload_auth.validate())?;
+            Ok(upload_auth)
+        }
+    }
+    /// Performs a [b2_finish_large_file][1] api call. The `part_sha1_array` must contain the sha1
+    /// of every part in order, starting with part number 1.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`] and [`is_invalid_sha1`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_finish_large_file.html
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_invalid_sha1`]: ../../enum.B2Error.html#method.is_invalid_sha1
+    pub fn finish_large_file<IT>(&self, file_id: &str, part_sha1_array: &[String], client: &Client)
+        -> Result<MoreFileInfo<IT>, B2Error>
+        where for<'de> IT: Deserialize<'de>
+    {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a> {
+            file_id: &'a str,
+            part_sha1_array: &'a [String]
+        }
+        let request = Request {
+            file_id: file_id,
+            part_sha1_array: part_sha1_array
+        };
+        let body: String = serde_json::to_string(&request)?;
+        let url_string: String = format!("{}/b2api/v1/b2_finish_large_file", self.api_url);
+        let url: &str = &url_string;
+        let resp = client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(self.auth_header())
+            .send()?;
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            Ok(serde_json::from_reader(resp)?)
+        }
+    }
+    /// Performs a [b2_copy_part][1] api call, which copies a [`ByteRange`] of an existing file
+    /// into a part of the unfinished large file `large_file_id`. If no range is given, the entire
+    /// source file is copied.
+    ///
+    /// The returned [`PartInfo`] contains the sha1 of the part, which must be passed to
+    /// [`finish_large_file`]. Together these allow combining existing files into a new file
+    /// without downloading them.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`], [`is_range_out_of_bounds`] and
+    /// [`is_cap_exceeded`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_copy_part.html
+    ///  [`ByteRange`]: ../download/enum.ByteRange.html
+    ///  [`PartInfo`]: struct.PartInfo.html
+    ///  [`finish_large_file`]: #method.finish_large_file
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_range_out_of_bounds`]: ../../enum.B2Error.html#method.is_range_out_of_bounds
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    pub fn copy_part(&self, source_file_id: &str, large_file_id: &str, part_number: u32,
+                     range: Option<ByteRange>, client: &Client)
+        -> Result<PartInfo, B2Error>
+    {
+        self.copy_part_with_encryption(source_file_id, large_file_id, part_number, range,
+                                       &ServerSideEncryption::None, &ServerSideEncryption::None,
+                                       client)
+    }
+    /// Performs a [b2_copy_part][1] api call like [`copy_part`], for source files or large files
+    /// encrypted with SSE-C. The `source_encryption` is the encryption of the source file, and
+    /// the `destination_encryption` must be the encryption the large file was started with.
+    /// Only SSE-C keys are sent, since the other modes don't need a key to read or write.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`], [`is_range_out_of_bounds`] and
+    /// [`is_cap_exceeded`]. If a key is missing or wrong, the error returned by backblaze is
+    /// returned.
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_copy_part.html
+    ///  [`copy_part`]: #method.copy_part
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    ///  [`is_range_out_of_bounds`]: ../../enum.B2Error.html#method.is_range_out_of_bounds
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    pub fn copy_part_with_encryption(&self, source_file_id: &str, large_file_id: &str,
+                                     part_number: u32, range: Option<ByteRange>,
+                                     source_encryption: &ServerSideEncryption,
+                                     destination_encryption: &ServerSideEncryption,
+                                     client: &Client)
+        -> Result<PartInfo, B2Error>
+    {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a> {
+            source_file_id: &'a str,
+            large_file_id: &'a str,
+            part_number: u32,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            range: Option<String>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            source_server_side_encryption: Option<&'a ServerSideEncryption>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            destination_server_side_encryption: Option<&'a ServerSideEncryption>
+        }
+        let request = Request {
+            source_file_id: source_file_id,
+            large_file_id: large_file_id,
+            part_number: part_number,
+            range: range.map(|range| range.header_value()),
+            source_server_side_encryption: source_encryption.customer_key_field(),
+            destination_server_side_encryption: destination_encryption.customer_key_field()
+        };
+        let body: String = serde_json::to_string(&request)?;
+        let url_string: String = format!("{}/b2api/v1/b2_copy_part", self.api_url);
+        let url: &str = &url_string;
+        let resp = client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(self.auth_header())
+            .send()?;
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            Ok(serde_json::from_reader(resp)?)
+        }
+    }
+    /// Performs a [b2_cancel_large_file][1] api call, which deletes the parts uploaded so far.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_cancel_large_file.html
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    pub fn cancel_large_file(&self, file_id: &str, client: &Client)
+        -> Result<CancelledLargeFile, B2Error>
+    {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a> {
+            file_id: &'a str
+        }
+        let request = Request {
+            file_id: file_id
+        };
+        let body: String = serde_json::to_string(&request)?;
+        let url_string: String = format!("{}/b2api/v1/b2_cancel_large_file", self.api_url);
+        let url: &str = &url_string;
+        let resp = client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(self.auth_header())
+            .send()?;
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            Ok(serde_json::from_reader(resp)?)
+        }
+    }
+    /// Performs a [b2_list_parts][1] api call. This function returns at most max_part_count
+    /// parts, starting at the part number start_part_number.
+    ///
+    /// In order to list all the parts, pass None as start_part_number on the first call to this
+    /// function and to subsequent calls pass the Option returned by this function, until that
+    /// Option is None. This is also done by the iterator returned by [`iter_parts`].
+    ///
+    /// This can be used to resume an interrupted upload, since the parts that are already
+    /// uploaded do not need to be uploaded again.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_file_not_found`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_list_parts.html
+    ///  [`iter_parts`]: #method.iter_parts
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    pub fn list_parts(&self, file_id: &str, start_part_number: Option<u32>, max_part_count: u32,
+                      client: &Client)
+        -> Result<(Vec<UploadedPart>, Option<u32>), B2Error>
+    {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a> {
+            file_id: &'a str,
+            start_part_number: Option<u32>,
+            max_part_count: u32
+        }
+        let request = Request {
+            file_id: file_id,
+            start_part_number: start_part_number,
+            max_part_count: max_part_count
+        };
+        let body: String = serde_json::to_string(&request)?;
+        let url_string: String = format!("{}/b2api/v1/b2_list_parts", self.api_url);
+        let url: &str = &url_string;
+        let resp = client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(self.auth_header())
+            .send()?;
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            #[derive(Deserialize)]
+            #[serde(rename_all = "camelCase")]
+            struct Response {
+                parts: Vec<UploadedPart>,
+                next_part_number: Option<u32>
+            }
+            let response: Response = serde_json::from_reader(resp)?;
+            Ok((response.parts, response.next_part_number))
+        }
+    }
+    /// Returns an iterator over every uploaded part of the large file, that performs the
+    /// [`list_parts`] calls lazily as the iterator is advanced. If an api call fails, the error is
+    /// returned by the iterator, after which the iterator returns `None`.
+    ///
+    /// # Errors
+    /// The iterator returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, the iterator can fail with [`is_file_not_found`]. If backblaze returns a next part
+    /// number that is not after the previous one, or the listing needs more pages than allowed by
+    /// [`max_pages`], the parts of the last page are returned followed by an error for which
+    /// [`is_pagination_not_advancing`] returns true.
+    ///
+    ///  [`list_parts`]: #method.list_parts
+    ///  [`max_pages`]: ../large_file/struct.PartIter.html#method.max_pages
+    ///  [`is_pagination_not_advancing`]: ../../enum.B2Error.html#method.is_pagination_not_advancing
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_file_not_found`]: ../../enum.B2Error.html#method.is_file_not_found
+    pub fn iter_parts<'a>(&'a self, file_id: &'a str, parts_per_request: u32, client: &'a Client)
+        -> PartIter<'a>
+    {
+        PartIter {
+            auth: self,
+            file_id: file_id,
+            parts_per_request: parts_per_request,
+            client: client,
+            parts: Vec::new().into_iter(),
+            next_part_number: None,
+            pages: 0,
+            max_pages: DEFAULT_MAX_PAGES,
+            error: None,
+            done: false
+        }
+    }
+    /// Performs a [b2_list_unfinished_large_files][1] api call. This function returns at most
+    /// max_file_count unfinished large files in the bucket, starting at the file id
+    /// start_file_id. If `name_prefix` is given, only files whose name starts with the prefix are
+    /// listed.
+    ///
+    /// In order to list all the unfinished large files, pass None as start_file_id on the first
+    /// call to this function and to subsequent calls pass the Option returned by this function,
+    /// until that Option is None. This is also done by the iterator returned by
+    /// [`iter_unfinished_large_files`].
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_bucket_not_found`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_list_unfinished_large_files.html
+    ///  [`iter_unfinished_large_files`]: #method.iter_unfinished_large_files
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    pub fn list_unfinished_large_files<IT>(&self, bucket_id: &str, name_prefix: Option<&str>,
+                                           start_file_id: Option<&str>, max_file_count: u32,
+                                           client: &Client)
+        -> Result<(Vec<UnfinishedLargeFileInfo<IT>>, Option<String>), B2Error>
+        where for<'de> IT: Deserialize<'de>
+    {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a> {
+            bucket_id: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            name_prefix: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            start_file_id: Option<&'a str>,
+            max_file_count: u32
+        }
+        let request = Request {
+            bucket_id: bucket_id,
+            name_prefix: name_prefix,
+            start_file_id: start_file_id,
+            max_file_count: max_file_count
+        };
+        let body: String = serde_json::to_string(&request)?;
+        let url_string: String =
+            format!("{}/b2api/v1/b2_list_unfinished_large_files", self.api_url);
+        let url: &str = &url_string;
+        let resp = client.post(url)
+            .body(Body::BufBody(body.as_bytes(), body.len()))
+            .header(self.auth_header())
+            .send()?;
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            let listing: ListUnfinishedLargeFilesResponse<IT> = serde_json::from_reader(resp)?;
+            Ok((listing.files, listing.next_file_id))
+        }
+    }
+    /// Uses the function [`list_unfinished_large_files`] several times in order to download a
+    /// list of all unfinished large files in the bucket.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_bucket_not_found`].
+    ///
+    ///  [`list_unfinished_large_files`]: #method.list_unfinished_large_files
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    pub fn list_all_unfinished_large_files<IT>(&self, bucket_id: &str, name_prefix: Option<&str>,
+                                               files_per_request: u32, client: &Client)
+        -> Result<Vec<UnfinishedLargeFileInfo<IT>>, B2Error>
+        where for<'de> IT: Deserialize<'de>
+    {
+        self.iter_unfinished_large_files(bucket_id, name_prefix, files_per_request, client)
+            .collect()
+    }
+    /// Returns an iterator over every unfinished large file in the bucket, that performs the
+    /// [`list_unfinished_large_files`] calls lazily as the iterator is advanced. If an api call
+    /// fails, the error is returned by the iterator, after which the iterator returns `None`.
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+    ///use backblaze_b2::raw::files::UnfinishedLargeFileInfo;
+    ///
+    ///// Cancel every upload that was interrupted.
+    ///for file in auth.iter_unfinished_large_files("bucket id", None, 100, client) {
+    ///    let file: UnfinishedLargeFileInfo = file?;
+    ///    auth.cancel_large_file(&file.file_id, client)?;
+    ///}
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// The iterator returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, the iterator can fail with [`is_bucket_not_found`]. If backblaze repeats the
+    /// previous file id, or the listing needs more pages than allowed by [`max_pages`], the files
+    /// of the last page are returned followed by an error for which
+    /// [`is_pagination_not_advancing`] returns true.
+    ///
+    ///  [`list_unfinished_large_files`]: #method.list_unfinished_large_files
+    ///  [`max_pages`]: ../large_file/struct.UnfinishedLargeFileIter.html#method.max_pages
+    ///  [`is_pagination_not_advancing`]: ../../enum.B2Error.html#method.is_pagination_not_advancing
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    pub fn iter_unfinished_large_files<'a, IT>(&'a self, bucket_id: &'a str,
+                                               name_prefix: Option<&'a str>,
+                                               files_per_request: u32, client: &'a Client)
+        -> UnfinishedLargeFileIter<'a, IT>
+        where for<'de> IT: Deserialize<'de>
+    {
+        UnfinishedLargeFileIter {
+            auth: self,
+            bucket_id: bucket_id,
+            name_prefix: name_prefix,
+            files_per_request: files_per_request,
+            client: client,
+            files: Vec::new().into_iter(),
+            next_file_id: None,
+            pages: 0,
+            max_pages: DEFAULT_MAX_PAGES,
+            error: None,
+            done: false
+        }
+    }
+    /// Uploads the contents of `data` as a large file. The data is split into parts of
+    /// `part_size` bytes, which defaults to [`recommended_part_size`], and each part is uploaded
+    /// using [`upload_part`]. Since the sha1 of each part must be sent before the part, each
+    /// part is kept in memory while it is uploaded.
+    ///
+    /// If the upload of a part fails, the part is retried as decided by [`upload_recovery_action`],
+    /// up to [`PART_ATTEMPTS`] attempts in total. Before retrying, the thread sleeps for the delay
+    /// of the `Retry-After` header, or the delay of the default [`RetryPolicy`] if there is no such
+    /// header. If a part cannot be uploaded, the large file is
+    /// cancelled, such that the uploaded parts are not left behind.
+    ///
+    /// The parts are uploaded one at a time, and the `large_file_sha1` file info is not set, since
+    /// the sha1 of the whole file is only known once every part has been read. Use
+    /// [`UploadLargeFile`] to upload several parts in parallel, or to store the sha1 if it is
+    /// known in advance.
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use hyper::net::{NetworkConnector, NetworkStream};
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example<C, S>(auth: &B2Authorization, client: &Client, connector: &C)
+    ///#     -> Result<(), B2Error>
+    ///#     where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    ///# {
+    ///use std::fs::File;
+    ///use backblaze_b2::raw::files::MoreFileInfo;
+    ///
+    ///let mut data = File::open("backup.tar")?;
+    ///let length = data.metadata()?.len();
+    ///let file: MoreFileInfo = auth.upload_large_file("bucket id", "backup.tar", None, &mut data,
+    ///                                                length, None, client, connector)?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_bucket_not_found`], [`is_invalid_file_name`] and
+    /// [`is_cap_exceeded`]. The error [`InvalidRequest`] is returned if the part size is smaller
+    /// than [`absolute_minimum_part_size`], or if the file would have less than two or more than
+    /// [`MAX_PART_COUNT`] parts. An io error with the kind `UnexpectedEof` is returned if `data`
+    /// contains less than `content_length` bytes.
+    ///
+    ///  [`recommended_part_size`]: ../authorize/struct.B2Authorization.html#structfield.recommended_part_size
+    ///  [`absolute_minimum_part_size`]: ../authorize/struct.B2Authorization.html#structfield.absolute_minimum_part_size
+    ///  [`upload_part`]: struct.UploadPartAuthorization.html#method.upload_part
+    ///  [`UploadLargeFile`]: struct.UploadLargeFile.html
+    ///  [`PART_ATTEMPTS`]: constant.PART_ATTEMPTS.html
+    ///  [`RetryPolicy`]: ../../retry/struct.RetryPolicy.html
+    ///  [`MAX_PART_COUNT`]: constant.MAX_PART_COUNT.html
+    ///  [`upload_recovery_action`]: ../../retry/fn.upload_recovery_action.html
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`InvalidRequest`]: ../../enum.B2Error.html#variant.InvalidRequest
+    ///  [`is_bucket_not_found`]: ../../enum.B2Error.html#method.is_bucket_not_found
+    ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    pub fn upload_large_file<IT, R, C, S>(&self, bucket_id: &str, file_name: &str,
+                                          content_type: Option<Mime>, data: &mut R,
+                                          content_length: u64, part_size: Option<u64>,
+                                          client: &Client, connector: &C)
+        -> Result<MoreFileInfo<IT>, B2Error>
+        where for<'de> IT: Deserialize<'de>, R: Read, C: NetworkConnector<Stream=S>,
+              S: Into<Box<NetworkStream + Send>>
+    {
+        let part_size = self.check_part_size(part_size)?;
+        check_part_count(content_length, part_size)?;
+
+        let file_info = JsonValue::Object(serde_json::Map::new());
+        self.upload_large_file_using(bucket_id, file_name, content_type, file_info, client,
+                                     |file_id| self.upload_parts(file_id, data, content_length,
+                                                                 part_size, client, connector))
+    }
+    /// Starts a large file, uploads its parts using `upload_parts` and finishes it. If the parts
+    /// could not be uploaded, the large file is cancelled.
+    fn upload_large_file_using<IT, F>(&self, bucket_id: &str, file_name: &str,
+                                      content_type: Option<Mime>, file_info: JsonValue,
+                                      client: &Client, upload_parts: F)
+        -> Result<MoreFileInfo<IT>, B2Error>
+        where for<'de> IT: Deserialize<'de>, F: FnOnce(&str) -> Result<Vec<String>, B2Error>
+    {
+        let file: UnfinishedLargeFileInfo<JsonValue> = self.start_large_file(
+            bucket_id, file_name, content_type, file_info, client)?;
+        match upload_parts(&file.file_id) {
+            Ok(part_sha1_array) => self.finish_large_file(&file.file_id, &part_sha1_array, client),
+            Err(err) => {
+                // the original error is more interesting than a failure to cancel
+                let _ = self.cancel_large_file(&file.file_id, client);
+                Err(err)
+            }
+        }
+    }
+    /// Uploads every part of a large file and returns the sha1 of each part.
+    fn upload_parts<R, C, S>(&self, file_id: &str, data: &mut R, content_length: u64,
+                             part_size: u64, client: &Client, connector: &C)
+        -> Result<Vec<String>, B2Error>
+        where R: Read, C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    {
+        let mut upload_auth = self.get_upload_part_url(file_id, client)?;
+        let mut part_sha1_array = Vec::new();
+        let mut buffer = Vec::new();
+        let mut remaining = content_length;
+        let mut part_number = 1;
+        while remaining > 0 {
+            let length = if remaining < part_size { remaining } else { part_size };
+            let sha1 = read_part(data, length, &mut buffer)?;
+            self.upload_part_with_retries(&mut upload_auth, &buffer, part_number, &sha1,
+                                          &part_retry_policy(), client, connector)?;
+            part_sha1_array.push(sha1);
+            remaining -= length;
+            part_number += 1;
+        }
+        Ok(part_sha1_array)
+    }
+    /// Like `upload_parts`, but uploads up to `concurrency` parts at the same time, each on its
+    /// own thread with its own upload url. The parts are read on the calling thread, and handed
+    /// to a thread once it is done with its previous part, so at most `concurrency + 1` parts are
+    /// kept in memory.
+    fn upload_parts_concurrently<R, C, S>(&self, file_id: &str, data: &mut R,
+                                          content_length: u64, part_size: u64,
+                                          concurrency: usize, client: &Client, connector: &C)
+        -> Result<Vec<String>, B2Error>
+        where R: Read, C: NetworkConnector<Stream=S> + Sync, S: Into<Box<NetworkStream + Send>>
+    {
+        let part_count = ((content_length + part_size - 1) / part_size) as usize;
+        // the upload urls are obtained before any part is read, so failing to obtain one does
+        // not waste an upload
+        let mut upload_auths = Vec::new();
+        for _ in 0..cmp::min(concurrency, part_count) {
+            upload_auths.push(self.get_upload_part_url(file_id, client)?);
+        }
+        let failed = AtomicBool::new(false);
+        thread::scope(|scope| {
+            let (sender, receiver) = mpsc::sync_channel::<(u32, Vec<u8>, String)>(0);
+            // only the threads hold the receiver, so sending fails once every thread has stopped
+            let receiver = Arc::new(Mutex::new(receiver));
+            let threads: Vec<_> = upload_auths.into_iter().map(|mut upload_auth| {
+                let receiver = receiver.clone();
+                let failed = &failed;
+                scope.spawn(move || -> Result<Vec<(u32, String)>, B2Error> {
+                    let mut uploaded = Vec::new();
+                    while !failed.load(Ordering::SeqCst) {
+                        let part = receiver.lock().unwrap().recv();
+                        let (part_number, buffer, sha1) = match part {
+                            Ok(part) => part,
+                            Err(_) => break
+                        };
+                        if let Err(err) = self.upload_part_with_retries(
+                            &mut upload_auth, &buffer, part_number, &sha1, &part_retry_policy(),
+                            client, connector)
+                        {
+                            failed.store(true, Ordering::SeqCst);
+                            return Err(err);
+                        }
+                        uploaded.push((part_number, sha1));
+                    }
+                    Ok(uploaded)
+                })
+            }).collect();
+            drop(receiver);
+
+            let mut error = None;
+            let mut remaining = content_length;
+            let mut part_number = 1;
+            while remaining > 0 && !failed.load(Ordering::SeqCst) {
+                let length = if remaining < part_size { remaining } else { part_size };
+                let mut buffer = Vec::new();
+                match read_part(data, length, &mut buffer) {
+                    Ok(sha1) => if sender.send((part_number, buffer, sha1)).is_err() {
+                        break;
+                    },
+                    Err(err) => {
+                        failed.store(true, Ordering::SeqCst);
+                        error = Some(err);
+                        break;
+                    }
+                }
+                remaining -= length;
+                part_number += 1;
+            }
+            drop(sender);
+
+            let mut part_sha1_array = vec![String::new(); part_count];
+            for thread in threads {
+                match thread.join() {
+                    Ok(Ok(uploaded)) => for (part_number, sha1) in uploaded {
+                        part_sha1_array[part_number as usize - 1] = sha1;
+                    },
+                    // an error reading the data is reported before the errors it caused
+                    Ok(Err(err)) => if error.is_none() {
+                        error = Some(err);
+                    },
+                    Err(panic) => panic::resume_unwind(panic)
+                }
+            }
+            match error {
+                Some(err) => Err(err),
+                None => Ok(part_sha1_array)
+            }
+        })
+    }
+    /// Returns the part size to use for a large file, which is `part_size` or the recommended
+    /// part size, and fails if it is smaller than the absolute minimum part size.
+    pub(crate) fn check_part_size(&self, part_size: Option<u64>) -> Result<u64, B2Error> {
+        let part_size = part_size.unwrap_or(self.recommended_part_size as u64);
+        if part_size < self.absolute_minimum_part_size as u64 {
+            return Err(B2Error::InvalidRequest {
+                field: "part_size",
+                reason: format!("the part size must be at least {} bytes",
+                                self.absolute_minimum_part_size)
+            });
+        }
+        Ok(part_size)
+    }
+    /// Uploads a single part, retrying as decided by `upload_recovery_action` up to the
+    /// `max_attempts` of `policy`. When backing off, the delay of the `Retry-After` header is used
+    /// if backblaze sent one, and otherwise the delay of `policy`. If a new upload url is needed,
+    /// `upload_auth` is replaced.
+    pub(crate) fn upload_part_with_retries<C, S>(&self, upload_auth: &mut UploadPartAuthorization,
+                                                 data: &[u8], part_number: u32, sha1: &str,
+                                                 policy: &RetryPolicy, client: &Client,
+                                                 connector: &C)
+        -> Result<PartInfo, B2Error>
+        where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    {
+        let mut attempt = 1;
+        loop {
+            match upload_auth.upload_part(&mut &data[..], part_number, data.len() as u64, sha1,
+                                          connector) {
+                Ok(part) => return Ok(part),
+                Err(err) => {
+                    let action = upload_recovery_action(&err, UploadContext::Upload);
+                    if action == RecoveryAction::Fail || action == RecoveryAction::Reauthorize
+                        || attempt >= policy.max_attempts {
+                        return Err(err);
+                    }
+                    if action.should_back_off() {
+                        thread::sleep(err.retry_after().unwrap_or_else(|| policy.delay(attempt)));
+                    }
+                    if action.needs_new_upload_url() {
+                        *upload_auth = self.get_upload_part_url(&upload_auth.file_id, client)?;
+                    }
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Replaces the contents of `buffer` with the next `length` bytes of `data`, and returns their
+/// sha1.
+fn read_part<R: Read>(data: &mut R, length: u64, buffer: &mut Vec<u8>) -> Result<String, B2Error> {
+    buffer.clear();
+    data.by_ref().take(length).read_to_end(buffer)?;
+    if (buffer.len() as u64) < length {
+        return Err(B2Error::IOError(io::Error::new(io::ErrorKind::UnexpectedEof,
+            "the reader contained less than content_length bytes")));
+    }
+    let mut sha1 = Sha1::new();
+    sha1.update(buffer);
+    Ok(sha1.digest().to_string())
+}
+
+/// Fails unless a file of `content_length` bytes has between 2 and `MAX_PART_COUNT` parts of
+/// `part_size` bytes.
+pub(crate) fn check_part_count(content_length: u64, part_size: u64) -> Result<(), B2Error> {
+    let part_count = (content_length + part_size - 1) / part_size;
+    if part_count < 2 || part_count > MAX_PART_COUNT as u64 {
+        return Err(B2Error::InvalidRequest {
+            field: "content_length",
+            reason: format!("a large file must have between 2 and {} parts, but a file of {} \
+                             bytes has {} parts of {} bytes", MAX_PART_COUNT, content_length,
+                            part_count, part_size)
+        });
+    }
+    Ok(())
+}
+
+/// A builder for [`upload_large_file`] with the settings the method does not take: the amount
+/// of parts uploaded at the same time, and the sha1 of the whole file. By default the part size
+/// is the [`recommended_part_size`] and one part is uploaded at a time.
+///
+/// Backblaze does not compute the sha1 of a large file, and the `large_file_sha1` file info can
+/// only be set when the large file is started, which is before the data is read. If the sha1 is
+/// known, set it using [`large_file_sha1`], such that downloads can verify the file with
+/// [`effective_sha1`].
+///
+/// ```rust,no_run
+///# extern crate hyper;
+///# extern crate backblaze_b2;
+///# use hyper::Client;
+///# use hyper::net::{NetworkConnector, NetworkStream};
+///# use backblaze_b2::B2Error;
+///# use backblaze_b2::raw::authorize::B2Authorization;
+///# fn main() {}
+///# fn example<C, S>(auth: &B2Authorization, client: &Client, connector: &C)
+///#     -> Result<(), B2Error>
+///#     where C: NetworkConnector<Stream=S> + Sync, S: Into<Box<NetworkStream + Send>>
+///# {
+///use std::fs::File;
+///use backblaze_b2::raw::files::MoreFileInfo;
+///use backblaze_b2::raw::large_file::UploadLargeFile;
+///
+///let mut data = File::open("backup.tar")?;
+///let length = data.metadata()?.len();
+///let file: MoreFileInfo = UploadLargeFile::new("bucket id", "backup.tar")
+///    .part_size(100 * 1000 * 1000)
+///    .concurrency(4)
+///    .large_file_sha1("356a192b7913b04c54574d18c28d46e6395428ab")
+///    .send(auth, &mut data, length, client, connector)?;
+///# Ok(())
+///# }
+/// ```
+///
+///  [`upload_large_file`]: ../authorize/struct.B2Authorization.html#method.upload_large_file
+///  [`recommended_part_size`]: ../authorize/struct.B2Authorization.html#structfield.recommended_part_size
+///  [`large_file_sha1`]: #method.large_file_sha1
+///  [`effective_sha1`]: ../files/fn.effective_sha1.html
+#[derive(Debug,Clone)]
+pub struct UploadLargeFile<'a> {
+    bucket_id: &'a str,
+    file_name: &'a str,
+    content_type: Option<Mime>,
+    part_size: Option<u64>,
+    concurrency: usize,
+    large_file_sha1: Option<&'a str>
+}
+impl<'a> UploadLargeFile<'a> {
+    /// Uploads a file named `file_name` to the bucket with the id `bucket_id`.
+    pub fn new(bucket_id: &'a str, file_name: &'a str) -> UploadLargeFile<'a> {
+        UploadLargeFile {
+            bucket_id: bucket_id,
+            file_name: file_name,
+            content_type: None,
+            part_size: None,
+            concurrency: 1,
+            large_file_sha1: None
+        }
+    }
+    /// Sets the content type. If it is not set, backblaze picks it based on the file name.
+    pub fn content_type(mut self, content_type: Mime) -> UploadLargeFile<'a> {
+        self.content_type = Some(content_type);
+        self
+    }
+    /// Sets the size of the parts.
+    pub fn part_size(mut self, part_size: u64) -> UploadLargeFile<'a> {
+        self.part_size = Some(part_size);
+        self
+    }
+    /// Sets the amount of parts uploaded at the same time. Every part is uploaded on a separate
+    /// thread with its own upload url, and up to `concurrency + 1` parts are kept in memory.
+    pub fn concurrency(mut self, concurrency: usize) -> UploadLargeFile<'a> {
+        self.concurrency = concurrency;
+        self
+    }
+    /// Stores the sha1 of the whole file in the `large_file_sha1` file info.
+    pub fn large_file_sha1(mut self, sha1: &'a str) -> UploadLargeFile<'a> {
+        self.large_file_sha1 = Some(sha1);
+        self
+    }
+    /// Uploads `content_length` bytes from `data` like [`upload_large_file`]. The data is always
+    /// read on the calling thread.
+    ///
+    /// # Errors
+    /// See [`upload_large_file`]. Additionally [`InvalidRequest`] is returned if the concurrency
+    /// is zero, or if the sha1 is not 40 hexadecimal digits. If several parts fail at the same
+    /// time, the error of one of them is returned.
+    ///
+    ///  [`upload_large_file`]: ../authorize/struct.B2Authorization.html#method.upload_large_file
+    ///  [`InvalidRequest`]: ../../enum.B2Error.html#variant.InvalidRequest
+    pub fn send<IT, R, C, S>(self, auth: &B2Authorization, data: &mut R, content_length: u64,
+                             client: &Client, connector: &C)
+        -> Result<MoreFileInfo<IT>, B2Error>
+        where for<'de> IT: Deserialize<'de>, R: Read, C: NetworkConnector<Stream=S> + Sync,
+              S: Into<Box<NetworkStream + Send>>
+    {
+        let part_size = auth.check_part_size(self.part_size)?;
+        check_part_count(content_length, part_size)?;
+        if self.concurrency == 0 {
+            return Err(B2Error::InvalidRequest {
+                field: "concurrency",
+                reason: "at least one part must be uploaded at a time".to_owned()
+            });
+        }
+        let mut file_info = serde_json::Map::new();
+        if let Some(sha1) = self.large_file_sha1 {
+            if sha1.len() != 40 || !sha1.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(B2Error::InvalidRequest {
+                    field: "large_file_sha1",
+                    reason: format!("{:?} is not a sha1", sha1)
+                });
+            }
+            file_info.insert("large_file_sha1".to_owned(), JsonValue::String(sha1.to_owned()));
+        }
+        let concurrency = self.concurrency;
+        auth.upload_large_file_using(
+            self.bucket_id, self.file_name, self.content_type, JsonValue::Object(file_info),
+            client, |file_id| if concurrency == 1 {
+                auth.upload_parts(file_id, data, content_length, part_size, client, connector)
+            } else {
+                auth.upload_parts_concurrently(file_id, data, content_length, part_size,
+                                               concurrency, client, connector)
+            })
+    }
+}
+
+impl UploadPartAuthorization {
+    /// Performs a [b2_upload_part][1] api call, uploading `content_length` bytes from `data` as
+    /// the part with the number `part_number`. Part numbers start at 1.
+    ///
+    /// Every part except the last must be at least [`absolute_minimum_part_size`] bytes. As with
+    /// [`create_upload_file_request`], the string `do_not_verify` can be passed as the sha1.
+    ///
+    /// ```rust,no_run
+    ///#[macro_use]
+    ///extern crate serde_json;
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use hyper::net::{NetworkConnector, NetworkStream};
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example<C, S>(auth: &B2Authorization, client: &Client, connector: &C)
+    ///#     -> Result<(), B2Error>
+    ///#     where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    ///# {
+    ///use backblaze_b2::raw::files::{MoreFileInfo, UnfinishedLargeFileInfo};
+    ///
+    ///let large: UnfinishedLargeFileInfo = auth.start_large_file(
+    ///    "bucket id", "large.bin", None, json!({}), client)?;
+    ///let part_auth = auth.get_upload_part_url(&large.file_id, client)?;
+    ///
+    ///// Every part except the last must be at least absolute_minimum_part_size bytes.
+    ///let parts = vec![vec![0u8; auth.absolute_minimum_part_size], vec![1u8; 10]];
+    ///let part_sha1_array = vec![
+    ///    "<sha1 of the first part>".to_owned(),
+    ///    "<sha1 of the second part>".to_owned(),
+    ///];
+    ///for (i, part) in parts.iter().enumerate() {
+    ///    // Part numbers start at 1.
+    ///    part_auth.upload_part(&mut &part[..], i as u32 + 1, part.len() as u64,
+    ///                          &part_sha1_array[i], connector)?;
+    ///}
+    ///let file: MoreFileInfo = auth.finish_large_file(&large.file_id, &part_sha1_array, client)?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_cap_exceeded`] and [`is_invalid_sha1`].
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_upload_part.html
+    ///  [`absolute_minimum_part_size`]: ../authorize/struct.B2Authorization.html#structfield.absolute_minimum_part_size
+    ///  [`create_upload_file_request`]: ../upload/struct.UploadAuthorization.html#method.create_upload_file_request
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    ///  [`is_invalid_sha1`]: ../../enum.B2Error.html#method.is_invalid_sha1
+    pub fn upload_part<R, C, S>(&self, data: &mut R, part_number: u32, content_length: u64,
+                                content_sha1: &str, connector: &C)
+        -> Result<PartInfo, B2Error>
+        where R: Read, C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    {
+        self.upload_part_with_encryption(data, part_number, content_length, content_sha1,
+                                         &ServerSideEncryption::None, connector)
+    }
+    /// Performs a [b2_upload_part][1] api call like [`upload_part`], for a large file started
+    /// with [`start_large_file_with_encryption`]. The encryption must be the one the large file
+    /// was started with. Only SSE-C adds headers to the request, since the parts of a large file
+    /// encrypted with SSE-B2 are encrypted without further instructions.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_cap_exceeded`] and [`is_invalid_sha1`]. If the
+    /// SSE-C key is missing or wrong, the error returned by backblaze is returned.
+    ///
+    ///  [1]: https://www.backblaze.com/b2/docs/b2_upload_part.html
+    ///  [`upload_part`]: #method.upload_part
+    ///  [`start_large_file_with_encryption`]: ../authorize/struct.B2Authorization.html#method.start_large_file_with_encryption
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    ///  [`is_invalid_sha1`]: ../../enum.B2Error.html#method.is_invalid_sha1
+    pub fn upload_part_with_encryption<R, C, S>(&self, data: &mut R, part_number: u32,
+                                                content_length: u64, content_sha1: &str,
+                                                encryption: &ServerSideEncryption,
+                                                connector: &C)
+        -> Result<PartInfo, B2Error>
+        where R: Read, C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    {
+        let url: Url = Url::parse(&self.upload_url)?;
+        let mut request = Request::with_connector(Method::Post, url, connector)?;
+        request.headers_mut().extend(self.upload_part_headers(
+                part_number, content_length, content_sha1, encryption).iter());
+        let mut request = request.start()?;
+        io::copy(&mut data.take(content_length), &mut request)?;
+        request.flush()?;
+        let resp = request.send()?;
+        if resp.status != hyper::status::StatusCode::Ok {
+            Err(B2Error::from_response(resp))
+        } else {
+            Ok(serde_json::from_reader(resp)?)
+        }
+    }
+    /// Returns the headers sent by [`upload_part_with_encryption`]. This can be used to inspect
+    /// exactly what is sent to backblaze without performing a request.
+    ///
+    ///  [`upload_part_with_encryption`]: #method.upload_part_with_encryption
+    pub fn upload_part_headers(&self, part_number: u32, content_length: u64, content_sha1: &str,
+                               encryption: &ServerSideEncryption) -> Headers
+    {
+        let mut headers = Headers::new();
+        headers.set(self.auth_header());
+        headers.set(XBzPartNumber(part_number));
+        headers.set(XBzContentSha1(content_sha1.to_owned()));
+        headers.set(ContentLength(content_length));
+        encryption.set_upload_part_headers(&mut headers);
+        headers
+    }
+}
+
+/// An iterator over the parts of a large file. This iterator is created by [`iter_parts`].
+///
+///  [`iter_parts`]: ../authorize/struct.B2Authorization.html#method.iter_parts
+pub struct PartIter<'a> {
+    auth: &'a B2Authorization,
+    file_id: &'a str,
+    parts_per_request: u32,
+    client: &'a Client,
+    parts: vec::IntoIter<UploadedPart>,
+    next_part_number: Option<u32>,
+    pages: u64,
+    max_pages: u64,
+    error: Option<B2Error>,
+    done: bool
+}
+impl<'a> PartIter<'a> {
+    /// Sets the amount of pages fetched before the iterator fails with
+    /// [`PaginationNotAdvancing`]. The default is [`DEFAULT_MAX_PAGES`].
+    ///
+    ///  [`PaginationNotAdvancing`]: ../../enum.B2Error.html#variant.PaginationNotAdvancing
+    ///  [`DEFAULT_MAX_PAGES`]: ../files/constant.DEFAULT_MAX_PAGES.html
+    pub fn max_pages(mut self, max_pages: u64) -> PartIter<'a> {
+        self.max_pages = max_pages;
+        self
+    }
+}
+impl<'a> Iterator for PartIter<'a> {
+    type Item = Result<UploadedPart, B2Error>;
+    fn next(&mut self) -> Option<Result<UploadedPart, B2Error>> {
+        loop {
+            if let Some(part) = self.parts.next() {
+                return Some(Ok(part));
+            }
+            if let Some(err) = self.error.take() {
+                return Some(Err(err));
+            }
+            if self.done {
+                return None;
+            }
+            let res = self.auth.list_parts(self.file_id, self.next_part_number,
+                                           self.parts_per_request, self.client);
+            self.pages += 1;
+            match res {
+                Ok((parts, next_part_number)) => {
+                    // a buggy server repeating the cursor would make this loop forever, so the
+                    // error is returned after the parts of the page
+                    self.error = next_part_number.and_then(|next| {
+                        let advanced = match self.next_part_number {
+                            Some(prev) => next > prev,
+                            None => true
+                        };
+                        pagination_error(advanced, self.pages, self.max_pages, next)
+                    });
+                    self.parts = parts.into_iter();
+                    self.done = next_part_number.is_none() || self.error.is_some();
+                    self.next_part_number = next_part_number;
+                },
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+/// The response of a [`list_unfinished_large_files`] call.
+///
+///  [`list_unfinished_large_files`]: ../authorize/struct.B2Authorization.html#method.list_unfinished_large_files
+#[derive(Serialize,Deserialize,Debug,Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListUnfinishedLargeFilesResponse<InfoType=JsonValue> {
+    pub files: Vec<UnfinishedLargeFileInfo<InfoType>>,
+    pub next_file_id: Option<String>
+}
+
+/// An iterator over the unfinished large files of a bucket. This iterator is created by
+/// [`iter_unfinished_large_files`].
+///
+///  [`iter_unfinished_large_files`]: ../authorize/struct.B2Authorization.html#method.iter_unfinished_large_files
+pub struct UnfinishedLargeFileIter<'a, IT> {
+    auth: &'a B2Authorization,
+    bucket_id: &'a str,
+    name_prefix: Option<&'a str>,
+    files_per_request: u32,
+    client: &'a Client,
+    files: vec::IntoIter<UnfinishedLargeFileInfo<IT>>,
+    next_file_id: Option<String>,
+    pages: u64,
+    max_pages: u64,
+    error: Option<B2Error>,
+    done: bool
+}
+impl<'a, IT> UnfinishedLargeFileIter<'a, IT> {
+    /// Sets the amount of pages fetched before the iterator fails with
+    /// [`PaginationNotAdvancing`]. The default is [`DEFAULT_MAX_PAGES`].
+    ///
+    ///  [`PaginationNotAdvancing`]: ../../enum.B2Error.html#variant.PaginationNotAdvancing
+    ///  [`DEFAULT_MAX_PAGES`]: ../files/constant.DEFAULT_MAX_PAGES.html
+    pub fn max_pages(mut self, max_pages: u64) -> UnfinishedLargeFileIter<'a, IT> {
+        self.max_pages = max_pages;
+        self
+    }
+}
+impl<'a, IT> Iterator for UnfinishedLargeFileIter<'a, IT>
+    where for<'de> IT: Deserialize<'de>
+{
+    type Item = Result<UnfinishedLargeFileInfo<IT>, B2Error>;
+    fn next(&mut self) -> Option<Result<UnfinishedLargeFileInfo<IT>, B2Error>> {
+        loop {
+            if let Some(file) = self.files.next() {
+                return Some(Ok(file));
+            }
+            if let Some(err) = self.error.take() {
+                return Some(Err(err));
+            }
+            if self.done {
+                return None;
+            }
+            let res = self.auth.list_unfinished_large_files(
+                self.bucket_id, self.name_prefix,
+                self.next_file_id.as_ref().map(|s| s.as_str()),
+                self.files_per_request, self.client);
+            self.pages += 1;
+            match res {
+                Ok((files, next_file_id)) => {
+                    // a buggy server repeating the cursor would make this loop forever, so the
+                    // error is returned after the files of the page. File ids are not ordered,
+                    // so only a repeated cursor is detected.
+                    self.error = next_file_id.as_ref().and_then(|next| {
+                        let advanced = self.next_file_id.as_ref() != Some(next);
+                        pagination_error(advanced, self.pages, self.max_pages, next)
+                    });
+                    self.files = files.into_iter();
+                    self.done = next_file_id.is_none() || self.error.is_some();
+                    self.next_file_id = next_file_id;
+                },
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
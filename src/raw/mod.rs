@@ -1,4 +1,4 @@
-//! This module contains five different modules, each with different functions for accessing the
+//! This module contains seven different modules, each with different functions for accessing the
 //! b2 api directly.
 //!
 //! The various methods for accessing the backblaze api are implemented on an Authorization struct.
@@ -45,4 +45,6 @@ pub mod buckets;
 pub mod files;
 pub mod upload;
 pub mod download;
+pub mod large_file;
+pub mod encryption;
 
@@ -21,10 +21,93 @@ use hyper::net::{Streaming, NetworkConnector, NetworkStream};
 use serde::Deserialize;
 use serde_json;
 
+use sha1::Sha1;
+
 use B2Error;
 use B2AuthHeader;
-use raw::authorize::B2Authorization;
+use encoding::percent_encode;
+use raw::authorize::{B2Authorization, check_header_value, check_response, check_url};
 use raw::files::MoreFileInfo;
+use raw::encryption::ServerSideEncryption;
+
+/// The largest file that can be uploaded using [b2_upload_file][1]. Larger files must be uploaded
+/// using [upload_large_file][2].
+///
+///  [1]: https://www.backblaze.com/b2/docs/b2_upload_file.html
+///  [2]: ../authorize/struct.B2Authorization.html#method.upload_large_file
+pub const MAX_SIMPLE_UPLOAD_SIZE: u64 = 5_000_000_000;
+
+fn check_simple_upload_size(content_length: u64) -> Result<(), B2Error> {
+    if content_length > MAX_SIMPLE_UPLOAD_SIZE {
+        Err(B2Error::UploadTooLarge {
+            size: content_length,
+            limit: MAX_SIMPLE_UPLOAD_SIZE
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// The maximum amount of file info entries backblaze allows on a single file.
+pub const MAX_FILE_INFO_ENTRIES: usize = 10;
+
+/// Adds an `X-Bz-Info-*` header for every entry of the file info. The values are percent-encoded,
+/// such that they can contain arbitrary UTF-8. Backblaze decodes the values before storing them,
+/// and downloads decode them again.
+///
+/// ```rust
+///extern crate backblaze_b2;
+///extern crate hyper;
+///use hyper::header::Headers;
+///use backblaze_b2::raw::upload::set_file_info_headers;
+///
+///# fn main() {
+///let mut headers = Headers::new();
+///set_file_info_headers(&mut headers, vec![("author", "ø hello, world")]).unwrap();
+///assert_eq!(headers.get_raw("X-Bz-Info-author").unwrap()[0],
+///           b"%C3%B8%20hello%2C%20world".to_vec());
+///
+///let err = set_file_info_headers(&mut headers, vec![("bad name", "value")]).unwrap_err();
+///assert_eq!(err.field(), Some("file_info"));
+///# }
+/// ```
+///
+/// # Errors
+/// If a name contains characters other than letters, digits, `-`, `_` and `.`, or if there are
+/// more than [`MAX_FILE_INFO_ENTRIES`] entries, [`InvalidRequest`] is returned and no headers are
+/// added.
+///
+///  [`MAX_FILE_INFO_ENTRIES`]: constant.MAX_FILE_INFO_ENTRIES.html
+///  [`InvalidRequest`]: ../../enum.B2Error.html#variant.InvalidRequest
+pub fn set_file_info_headers<'a, I>(headers: &mut Headers, file_info: I) -> Result<(), B2Error>
+    where I: IntoIterator<Item=(&'a str, &'a str)>
+{
+    let mut encoded = Vec::new();
+    for (name, value) in file_info {
+        let valid = !name.is_empty() && name.len() <= 50 && name.bytes().all(|b| {
+            b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b == b'.'
+        });
+        if !valid {
+            return Err(B2Error::InvalidRequest {
+                field: "file_info",
+                reason: format!("{:?} is not a valid file info name", name)
+            });
+        }
+        encoded.push((format!("X-Bz-Info-{}", name), percent_encode(value)));
+    }
+    if encoded.len() > MAX_FILE_INFO_ENTRIES {
+        return Err(B2Error::InvalidRequest {
+            field: "file_info",
+            reason: format!("{} entries exceeds the limit of {}", encoded.len(),
+                            MAX_FILE_INFO_ENTRIES)
+        });
+    }
+    for (name, value) in encoded {
+        headers.set_raw(name, vec![value.into_bytes()]);
+    }
+    Ok(())
+}
+
 /// Contains the information needed to authorize an upload to b2. This struct is usually obtained
 /// from a [B2Authorization][1] using the method [get_upload_url][2].
 ///
@@ -84,14 +167,54 @@ impl B2Authorization {
         if resp.status != hyper::status::StatusCode::Ok {
             Err(B2Error::from_response(resp))
         } else {
-            Ok(serde_json::from_reader(resp)?)
+            let upload_auth: UploadAuthorization = serde_json::from_reader(resp)?;
+            check_response("b2_get_upload_url", upload_auth.validate())?;
+            Ok(upload_auth)
         }
     }
 }
 impl UploadAuthorization {
+    /// Checks that the authorization token can be used as a header value, and that the upload
+    /// url looks like a url. This is useful when the upload url is loaded from storage, see
+    /// [`B2Authorization::validate`].
+    ///
+    /// # Errors
+    /// This function returns an [`InvalidRequest`] error naming the first invalid field.
+    ///
+    ///  [`B2Authorization::validate`]: ../authorize/struct.B2Authorization.html#method.validate
+    ///  [`InvalidRequest`]: ../../enum.B2Error.html#variant.InvalidRequest
+    pub fn validate(&self) -> Result<(), B2Error> {
+        check_header_value("authorization_token", &self.authorization_token)?;
+        check_url("upload_url", &self.upload_url)?;
+        Ok(())
+    }
     /// Equivalent to calling [create_upload_file_request][1], writing everything in the Read to
     /// the Writer and calling finish.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use hyper::net::{NetworkConnector, NetworkStream};
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example<C, S>(auth: &B2Authorization, client: &Client, connector: &C)
+    ///#     -> Result<(), B2Error>
+    ///#     where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    ///# {
+    ///use backblaze_b2::raw::files::MoreFileInfo;
+    ///
+    ///let data = b"hello world";
+    ///let upload_auth = auth.get_upload_url("bucket id", client)?;
+    ///// The sha1 is the hex encoding of the sha1 of the data.
+    ///let file: MoreFileInfo = upload_auth.upload_file(
+    ///    &mut &data[..], "hello.txt".to_owned(), None, data.len() as u64,
+    ///    "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed".to_owned(), connector)?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     ///  [1]: struct.UploadAuthorization.html#method.create_upload_file_request
     pub fn upload_file<InfoType, R: Read, C, S>(&self, file: &mut R, file_name: String, content_type: Option<Mime>,
                                  content_length: u64, content_sha1: String, connector: &C)
@@ -104,6 +227,48 @@ impl UploadAuthorization {
         copy(file, &mut ufr)?;
         ufr.finish()
     }
+    /// Equivalent to calling [create_upload_file_request_sha1_at_end][1], writing everything in
+    /// the Read to the Writer and calling [finish_with_computed_sha1][2]. The sha1 is computed
+    /// while the data is sent, so the data is only read once.
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use hyper::net::{NetworkConnector, NetworkStream};
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example<C, S>(auth: &B2Authorization, client: &Client, connector: &C)
+    ///#     -> Result<(), B2Error>
+    ///#     where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    ///# {
+    ///use std::fs::File;
+    ///use backblaze_b2::raw::files::MoreFileInfo;
+    ///
+    ///let mut file = File::open("backup.tar")?;
+    ///let length = file.metadata()?.len();
+    ///let upload_auth = auth.get_upload_url("bucket id", client)?;
+    ///let file: MoreFileInfo = upload_auth.upload_file_sha1_at_end(
+    ///    &mut file, "backup.tar".to_owned(), None, length, connector)?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    ///  [1]: struct.UploadAuthorization.html#method.create_upload_file_request_sha1_at_end
+    ///  [2]: struct.UploadFileRequestSha1End.html#method.finish_with_computed_sha1
+    pub fn upload_file_sha1_at_end<InfoType, R: Read, C, S>(&self, file: &mut R, file_name: String,
+                                                             content_type: Option<Mime>,
+                                                             content_length: u64, connector: &C)
+        -> Result<MoreFileInfo<InfoType>, B2Error>
+        where for<'de> InfoType: Deserialize<'de>, R: Sized, C: NetworkConnector<Stream=S>,
+              S: Into<Box<NetworkStream + Send>>
+    {
+        let mut ufr = self.create_upload_file_request_sha1_at_end(
+            file_name, content_type, content_length, connector)?;
+        copy(file, &mut ufr)?;
+        ufr.finish_with_computed_sha1()
+    }
     /// Starts a request to upload a file to backblaze b2. This function returns an
     /// [UploadFileRequest][1], which implements [Write][2]. When writing to this object, the
     /// data is sent to backblaze b2. This method of uploading can be used to
@@ -122,9 +287,37 @@ impl UploadAuthorization {
     ///
     /// Read the [backblaze api documentation][6] for more information.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use hyper::net::{NetworkConnector, NetworkStream};
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example<C, S>(auth: &B2Authorization, client: &Client, connector: &C)
+    ///#     -> Result<(), B2Error>
+    ///#     where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    ///# {
+    ///use std::io::Write;
+    ///use backblaze_b2::raw::files::MoreFileInfo;
+    ///
+    ///let upload_auth = auth.get_upload_url("bucket id", client)?;
+    ///// The data is not verified by backblaze if the sha1 is do_not_verify.
+    ///let mut request = upload_auth.create_upload_file_request(
+    ///    "hello.txt".to_owned(), Some("text/plain".parse().unwrap()), 11,
+    ///    "do_not_verify".to_owned(), connector)?;
+    ///request.write_all(b"hello world")?;
+    ///let file: MoreFileInfo = request.finish()?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
-    /// errors, this function can fail with [`is_invalid_file_name`] and [`is_cap_exceeded`].
+    /// errors, this function can fail with [`is_invalid_file_name`] and [`is_cap_exceeded`]. If
+    /// `content_length` is larger than [`MAX_SIMPLE_UPLOAD_SIZE`], the request is not started and
+    /// [`is_upload_too_large`] is true.
     ///
     ///  [1]: struct.UploadFileRequest.html
     ///  [2]: https://doc.rust-lang.org/stable/std/io/trait.Write.html
@@ -135,6 +328,8 @@ impl UploadAuthorization {
     ///  [`B2Error`]: ../authorize/enum.B2Error.html
     ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
     ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    ///  [`MAX_SIMPLE_UPLOAD_SIZE`]: constant.MAX_SIMPLE_UPLOAD_SIZE.html
+    ///  [`is_upload_too_large`]: ../../enum.B2Error.html#method.is_upload_too_large
     pub fn create_upload_file_request<C,S>(&self, file_name: String,
                                            content_type: Option<Mime>,
                                            content_length: u64, content_sha1: String,
@@ -142,19 +337,99 @@ impl UploadAuthorization {
         -> Result<UploadFileRequest, B2Error>
         where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
     {
+        self.create_upload_file_request_with_encryption(
+            file_name, content_type, content_length, content_sha1,
+            &ServerSideEncryption::None, connector)
+    }
+    /// Starts a request to upload a file like [`create_upload_file_request`], but the file is
+    /// stored using the given server-side encryption.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_invalid_file_name`] and [`is_cap_exceeded`]. If
+    /// `content_length` is larger than [`MAX_SIMPLE_UPLOAD_SIZE`], the request is not started and
+    /// [`is_upload_too_large`] is true.
+    ///
+    ///  [`create_upload_file_request`]: #method.create_upload_file_request
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    ///  [`MAX_SIMPLE_UPLOAD_SIZE`]: constant.MAX_SIMPLE_UPLOAD_SIZE.html
+    ///  [`is_upload_too_large`]: ../../enum.B2Error.html#method.is_upload_too_large
+    pub fn create_upload_file_request_with_encryption<C,S>(&self, file_name: String,
+                                                           content_type: Option<Mime>,
+                                                           content_length: u64,
+                                                           content_sha1: String,
+                                                           encryption: &ServerSideEncryption,
+                                                           connector: &C)
+        -> Result<UploadFileRequest, B2Error>
+        where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    {
+        let mut headers = Headers::new();
+        encryption.set_upload_headers(&mut headers);
+        self.create_upload_file_request_with_headers(
+            file_name, content_type, content_length, content_sha1, headers, connector)
+    }
+    /// Starts a request to upload a file like [`create_upload_file_request`], and adds the given
+    /// headers to the request. This can be used to upload a file with file info, using
+    /// [`set_file_info_headers`].
+    ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use hyper::net::{NetworkConnector, NetworkStream};
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example<C, S>(auth: &B2Authorization, client: &Client, connector: &C)
+    ///#     -> Result<(), B2Error>
+    ///#     where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    ///# {
+    ///use std::io::Write;
+    ///use hyper::header::Headers;
+    ///use backblaze_b2::raw::files::MoreFileInfo;
+    ///use backblaze_b2::raw::upload::set_file_info_headers;
+    ///
+    ///let mut headers = Headers::new();
+    ///set_file_info_headers(&mut headers, vec![("author", "Jørgen")])?;
+    ///let upload_auth = auth.get_upload_url("bucket id", client)?;
+    ///let mut request = upload_auth.create_upload_file_request_with_headers(
+    ///    "hello.txt".to_owned(), None, 11, "do_not_verify".to_owned(), headers, connector)?;
+    ///request.write_all(b"hello world")?;
+    ///let file: MoreFileInfo = request.finish()?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_invalid_file_name`] and [`is_cap_exceeded`]. If
+    /// `content_length` is larger than [`MAX_SIMPLE_UPLOAD_SIZE`], the request is not started and
+    /// [`is_upload_too_large`] is true.
+    ///
+    ///  [`create_upload_file_request`]: #method.create_upload_file_request
+    ///  [`set_file_info_headers`]: fn.set_file_info_headers.html
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    ///  [`MAX_SIMPLE_UPLOAD_SIZE`]: constant.MAX_SIMPLE_UPLOAD_SIZE.html
+    ///  [`is_upload_too_large`]: ../../enum.B2Error.html#method.is_upload_too_large
+    pub fn create_upload_file_request_with_headers<C,S>(&self, file_name: String,
+                                                        content_type: Option<Mime>,
+                                                        content_length: u64,
+                                                        content_sha1: String,
+                                                        headers: Headers,
+                                                        connector: &C)
+        -> Result<UploadFileRequest, B2Error>
+        where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    {
+        check_simple_upload_size(content_length)?;
         let url: Url = Url::parse(&self.upload_url)?;
         let mut request = Request::with_connector(Method::Post, url, connector)?;
-        {
-            let headers: &mut Headers = request.headers_mut();
-            headers.set(self.auth_header());
-            headers.set(XBzFileName(file_name));
-            headers.set(XBzContentSha1(content_sha1));
-            headers.set(ContentLength(content_length));
-            headers.set(ContentType(match content_type {
-                Some(v) => v,
-                None => "b2/x-auto".parse().unwrap()
-            }));
-        }
+        request.headers_mut().extend(self.upload_file_headers(
+                &file_name, content_type, content_length, &content_sha1).iter());
+        request.headers_mut().extend(headers.iter());
         Ok(UploadFileRequest { request: request.start()? })
     }
     /// Starts a request to upload a file to backblaze b2. This function returns an
@@ -170,9 +445,36 @@ impl UploadAuthorization {
     ///
     /// Read the [backblaze api documentation][4] for more information.
     ///
+    /// ```rust,no_run
+    ///# extern crate hyper;
+    ///# extern crate backblaze_b2;
+    ///# use hyper::Client;
+    ///# use hyper::net::{NetworkConnector, NetworkStream};
+    ///# use backblaze_b2::B2Error;
+    ///# use backblaze_b2::raw::authorize::B2Authorization;
+    ///# fn main() {}
+    ///# fn example<C, S>(auth: &B2Authorization, client: &Client, connector: &C)
+    ///#     -> Result<(), B2Error>
+    ///#     where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+    ///# {
+    ///use std::io::Write;
+    ///use backblaze_b2::raw::files::MoreFileInfo;
+    ///
+    ///let upload_auth = auth.get_upload_url("bucket id", client)?;
+    ///// The content length does not include the 40 bytes of the sha1.
+    ///let mut request = upload_auth.create_upload_file_request_sha1_at_end(
+    ///    "hello.txt".to_owned(), None, 11, connector)?;
+    ///request.write_all(b"hello world")?;
+    ///let file: MoreFileInfo = request.finish("2aae6c35c94fcfb415dbe95f408b9ce91ee846ed")?;
+    ///# Ok(())
+    ///# }
+    /// ```
+    ///
     /// # Errors
     /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
-    /// errors, this function can fail with [`is_invalid_file_name`] and [`is_cap_exceeded`].
+    /// errors, this function can fail with [`is_invalid_file_name`] and [`is_cap_exceeded`]. If
+    /// `content_length` is larger than [`MAX_SIMPLE_UPLOAD_SIZE`], the request is not started and
+    /// [`is_upload_too_large`] is true.
     ///
     ///  [1]: struct.UploadFileRequestSha1End.html
     ///  [2]: https://doc.rust-lang.org/stable/std/io/trait.Write.html
@@ -181,6 +483,8 @@ impl UploadAuthorization {
     ///  [`B2Error`]: ../authorize/enum.B2Error.html
     ///  [`is_invalid_file_name`]: ../../enum.B2Error.html#method.is_invalid_file_name
     ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    ///  [`MAX_SIMPLE_UPLOAD_SIZE`]: constant.MAX_SIMPLE_UPLOAD_SIZE.html
+    ///  [`is_upload_too_large`]: ../../enum.B2Error.html#method.is_upload_too_large
     pub fn create_upload_file_request_sha1_at_end<C,S>(&self, file_name: String,
                                                        content_type: Option<Mime>,
                                                        content_length: u64,
@@ -188,20 +492,40 @@ impl UploadAuthorization {
         -> Result<UploadFileRequestSha1End, B2Error>
         where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
     {
+        check_simple_upload_size(content_length)?;
         let url: Url = Url::parse(&self.upload_url)?;
         let mut request = Request::with_connector(Method::Post, url, connector)?;
-        {
-            let headers: &mut Headers = request.headers_mut();
-            headers.set(self.auth_header());
-            headers.set(XBzFileName(file_name));
-            headers.set(XBzContentSha1("hex_digits_at_end".to_owned()));
-            headers.set(ContentLength(content_length + 40));
-            headers.set(ContentType(match content_type {
-                Some(v) => v,
-                None => "b2/x-auto".parse().unwrap()
-            }));
-        }
-        Ok(UploadFileRequestSha1End { request: request.start()? })
+        request.headers_mut().extend(self.upload_file_headers_sha1_at_end(
+                &file_name, content_type, content_length).iter());
+        Ok(UploadFileRequestSha1End { request: request.start()?, sha1: Sha1::new() })
+    }
+    /// Returns the headers sent by [`create_upload_file_request`]. This can be used to inspect
+    /// exactly what is sent to backblaze without performing a request.
+    ///
+    ///  [`create_upload_file_request`]: #method.create_upload_file_request
+    pub fn upload_file_headers(&self, file_name: &str, content_type: Option<Mime>,
+                               content_length: u64, content_sha1: &str) -> Headers
+    {
+        let mut headers = Headers::new();
+        headers.set(self.auth_header());
+        headers.set(XBzFileName(percent_encode(file_name)));
+        headers.set(XBzContentSha1(content_sha1.to_owned()));
+        headers.set(ContentLength(content_length));
+        headers.set(ContentType(match content_type {
+            Some(v) => v,
+            None => "b2/x-auto".parse().unwrap()
+        }));
+        headers
+    }
+    /// Returns the headers sent by [`create_upload_file_request_sha1_at_end`]. The
+    /// `content_length` must not include the 40 bytes of the sha1.
+    ///
+    ///  [`create_upload_file_request_sha1_at_end`]: #method.create_upload_file_request_sha1_at_end
+    pub fn upload_file_headers_sha1_at_end(&self, file_name: &str, content_type: Option<Mime>,
+                                           content_length: u64) -> Headers
+    {
+        self.upload_file_headers(file_name, content_type, content_length + 40,
+                                 "hex_digits_at_end")
     }
 }
 header! { (XBzFileName, "X-Bz-File-Name") => [String] }
@@ -254,23 +578,44 @@ impl UploadFileRequest {
 ///
 ///  [1]: struct.UploadAuthorization.html#method.create_upload_file_request_sha1_at_end
 pub struct UploadFileRequestSha1End {
-    request: Request<Streaming>
+    request: Request<Streaming>,
+    sha1: Sha1
 }
 impl Write for UploadFileRequestSha1End {
     fn write(&mut self, msg: &[u8]) -> ::std::io::Result<usize> {
-        self.request.write(msg)
+        let n = self.request.write(msg)?;
+        self.sha1.update(&msg[..n]);
+        Ok(n)
     }
     fn flush(&mut self) -> ::std::io::Result<()> {
         self.request.flush()
     }
     fn write_all(&mut self, buf: &[u8]) -> Result<(), ::std::io::Error> {
-        self.request.write_all(buf)
-    }
-    fn write_fmt(&mut self, fmt: ::core::fmt::Arguments) -> Result<(), ::std::io::Error> {
-        self.request.write_fmt(fmt)
+        self.request.write_all(buf)?;
+        self.sha1.update(buf);
+        Ok(())
     }
 }
 impl UploadFileRequestSha1End {
+    /// Returns the sha1 of the data written so far.
+    pub fn current_sha1(&self) -> String {
+        self.sha1.digest().to_string()
+    }
+    /// Finishes the upload of the file using the sha1 of the data written to this request, and
+    /// returns information about the uploaded file.
+    ///
+    /// # Errors
+    /// This function returns a [`B2Error`] in case something goes wrong. Besides the standard
+    /// errors, this function can fail with [`is_cap_exceeded`].
+    ///
+    ///  [`B2Error`]: ../authorize/enum.B2Error.html
+    ///  [`is_cap_exceeded`]: ../../enum.B2Error.html#method.is_cap_exceeded
+    pub fn finish_with_computed_sha1<InfoType>(self) -> Result<MoreFileInfo<InfoType>, B2Error>
+        where for<'de> InfoType: Deserialize<'de>
+    {
+        let sha1 = self.current_sha1();
+        self.finish(&sha1)
+    }
     /// Finishes the upload of the file and returns information about the uploaded file. The `sha1`
     /// argument must be the ascii encoding of the sha1 of the file.
     ///
@@ -0,0 +1,318 @@
+//! This module provides a retry loop with exponential back off for api calls.
+//!
+//! Only errors where [`should_back_off`] or [`is_transient_io_error`] returns true are retried.
+//! Errors that require new authentication are not retried, since the api call would fail again
+//! with the same authorization.
+//!
+//! Uploads read their data from the body passed to the upload function, so a failed upload has
+//! consumed some of the body. Use [`retry_upload`] for uploads, which rewinds the body before
+//! every attempt, and returns [`UploadNotReplayable`] if the body cannot be rewound.
+//!
+//!  [`should_back_off`]: ../enum.B2Error.html#method.should_back_off
+//!  [`is_transient_io_error`]: ../enum.B2Error.html#method.is_transient_io_error
+//!  [`retry_upload`]: fn.retry_upload.html
+//!  [`UploadNotReplayable`]: ../enum.B2Error.html#variant.UploadNotReplayable
+
+use std::cmp;
+use std::io::{Read, Seek, SeekFrom};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use B2Error;
+
+/// Describes how many times and how long to wait between attempts in [`retry`].
+///
+/// The delay before attempt `n + 1` is `base_delay * 2^(n-1)`, capped at `max_delay`. If
+/// `jitter` is true, a random delay between zero and the computed delay is used instead. If
+/// backblaze sends a `Retry-After` header, that delay is used instead.
+///
+/// ```rust
+///use std::time::Duration;
+///use backblaze_b2::retry::RetryPolicy;
+///
+///let policy = RetryPolicy {
+///    max_attempts: 4,
+///    base_delay: Duration::from_secs(1),
+///    max_delay: Duration::from_secs(3),
+///    jitter: false
+///};
+///assert_eq!(policy.delay(1), Duration::from_secs(1));
+///assert_eq!(policy.delay(2), Duration::from_secs(2));
+///assert_eq!(policy.delay(3), Duration::from_secs(3));
+/// ```
+///
+///  [`retry`]: fn.retry.html
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct RetryPolicy {
+    /// The total amount of attempts, including the first.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool
+}
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(64),
+            jitter: true
+        }
+    }
+}
+impl RetryPolicy {
+    /// Returns the delay after the failed attempt with the given number, where the first attempt
+    /// has number 1.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::max_value());
+        let delay = match self.base_delay.checked_mul(factor) {
+            Some(delay) => cmp::min(delay, self.max_delay),
+            None => self.max_delay
+        };
+        if self.jitter {
+            scale(delay, random_fraction())
+        } else {
+            delay
+        }
+    }
+    /// Returns true if the error may go away if the api call is made again.
+    pub fn should_retry(&self, err: &B2Error) -> bool {
+        err.should_back_off() || err.is_transient_io_error()
+    }
+}
+
+/// Calls the closure until it succeeds, it fails with an error that should not be retried, or
+/// the `max_attempts` of the policy is reached. The thread sleeps between the attempts as
+/// described on [`RetryPolicy`]. Use [`retry_upload`] if the call reads from a body.
+///
+/// ```rust,no_run
+///# extern crate hyper;
+///# extern crate backblaze_b2;
+///# use hyper::Client;
+///# use backblaze_b2::B2Error;
+///# use backblaze_b2::raw::authorize::B2Authorization;
+///# fn main() {}
+///# fn example(auth: &B2Authorization, client: &Client) -> Result<(), B2Error> {
+///use backblaze_b2::raw::buckets::Bucket;
+///use backblaze_b2::retry::{retry, RetryPolicy};
+///
+///let buckets: Vec<Bucket> = retry(&RetryPolicy::default(), || auth.list_buckets(client))?;
+///# Ok(())
+///# }
+/// ```
+///
+/// # Errors
+/// The error of the last attempt is returned.
+///
+///  [`RetryPolicy`]: struct.RetryPolicy.html
+///  [`retry_upload`]: fn.retry_upload.html
+pub fn retry<T, F>(policy: &RetryPolicy, mut call: F) -> Result<T, B2Error>
+    where F: FnMut() -> Result<T, B2Error>
+{
+    let mut attempt = 1;
+    loop {
+        match call() {
+            Ok(value) => return Ok(value),
+            Err(err) => {
+                if attempt >= policy.max_attempts || !policy.should_retry(&err) {
+                    return Err(err);
+                }
+                let delay = err.retry_after().unwrap_or_else(|| policy.delay(attempt));
+                thread::sleep(delay);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Calls the closure with the body until it succeeds, like [`retry`]. Before the upload is
+/// retried, the body is rewound to the position it had when this function was called, so every
+/// attempt sends the same data.
+///
+/// ```rust,no_run
+///# extern crate hyper;
+///# extern crate backblaze_b2;
+///# use hyper::net::{NetworkConnector, NetworkStream};
+///# use backblaze_b2::B2Error;
+///# use backblaze_b2::raw::large_file::UploadPartAuthorization;
+///# fn main() {}
+///# fn example<C, S>(part_auth: &UploadPartAuthorization, connector: &C) -> Result<(), B2Error>
+///#     where C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+///# {
+///use std::fs::File;
+///use backblaze_b2::retry::{retry_upload, RetryPolicy};
+///
+///let mut file = File::open("part.bin")?;
+///let length = file.metadata()?.len();
+///retry_upload(&RetryPolicy::default(), &mut file, |body| {
+///    part_auth.upload_part(body, 1, length, "do_not_verify", connector)
+///})?;
+///# Ok(())
+///# }
+/// ```
+///
+/// # Errors
+/// The error of the last attempt is returned. If the error could be retried, but the body
+/// cannot be rewound, [`UploadNotReplayable`] containing the error is returned instead.
+///
+///  [`retry`]: fn.retry.html
+///  [`UploadNotReplayable`]: ../enum.B2Error.html#variant.UploadNotReplayable
+pub fn retry_upload<R, T, F>(policy: &RetryPolicy, body: &mut R, mut call: F)
+    -> Result<T, B2Error>
+    where R: Read + Seek, F: FnMut(&mut R) -> Result<T, B2Error>
+{
+    // if the position is unknown, the first attempt is still made, but it can't be repeated
+    let start = body.seek(SeekFrom::Current(0)).ok();
+    let mut attempt = 1;
+    loop {
+        match call(body) {
+            Ok(value) => return Ok(value),
+            Err(err) => {
+                if attempt >= policy.max_attempts || !policy.should_retry(&err) {
+                    return Err(err);
+                }
+                let rewound = match start {
+                    Some(start) => body.seek(SeekFrom::Start(start)).is_ok(),
+                    None => false
+                };
+                if !rewound {
+                    return Err(B2Error::UploadNotReplayable(Box::new(err)));
+                }
+                let delay = err.retry_after().unwrap_or_else(|| policy.delay(attempt));
+                thread::sleep(delay);
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Returns a number in [0, 1). The jitter doesn't need a good random number, so the clock is used
+/// instead of depending on a random number generator.
+fn random_fraction() -> f64 {
+    let nanos = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.subsec_nanos(),
+        Err(_) => 0
+    };
+    f64::from(nanos) / 1_000_000_000.0
+}
+fn scale(duration: Duration, factor: f64) -> Duration {
+    let nanos = (duration.as_secs() as f64 * 1_000_000_000.0 + f64::from(duration.subsec_nanos()))
+        * factor;
+    Duration::new((nanos / 1_000_000_000.0) as u64, (nanos % 1_000_000_000.0) as u32)
+}
+
+/// The part of an upload that failed, see [`upload_recovery_action`].
+///
+///  [`upload_recovery_action`]: fn.upload_recovery_action.html
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum UploadContext {
+    /// The call to [`get_upload_url`] or [`get_upload_part_url`] failed. These calls use the
+    /// token of the [`B2Authorization`].
+    ///
+    ///  [`get_upload_url`]: ../raw/authorize/struct.B2Authorization.html#method.get_upload_url
+    ///  [`get_upload_part_url`]: ../raw/authorize/struct.B2Authorization.html#method.get_upload_part_url
+    ///  [`B2Authorization`]: ../raw/authorize/struct.B2Authorization.html
+    GetUploadUrl,
+    /// The upload of a file or a part failed. These calls use the token of the upload url.
+    Upload
+}
+
+/// What to do after an upload failed, see [`upload_recovery_action`].
+///
+///  [`upload_recovery_action`]: fn.upload_recovery_action.html
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum RecoveryAction {
+    /// Retry the call after waiting.
+    BackOff,
+    /// Obtain a new upload url and retry the upload immediately.
+    NewUploadUrl,
+    /// Wait, then obtain a new upload url and retry the upload.
+    BackOffAndNewUploadUrl,
+    /// Obtain a new [`B2Authorization`] using [`authorize`] and start over.
+    ///
+    ///  [`B2Authorization`]: ../raw/authorize/struct.B2Authorization.html
+    ///  [`authorize`]: ../raw/authorize/struct.B2Credentials.html#method.authorize
+    Reauthorize,
+    /// The error cannot be fixed by retrying.
+    Fail
+}
+impl RecoveryAction {
+    /// Returns true if the action involves waiting before the next attempt.
+    pub fn should_back_off(&self) -> bool {
+        match *self {
+            RecoveryAction::BackOff | RecoveryAction::BackOffAndNewUploadUrl => true,
+            _ => false
+        }
+    }
+    /// Returns true if the action involves obtaining a new upload url.
+    pub fn needs_new_upload_url(&self) -> bool {
+        match *self {
+            RecoveryAction::NewUploadUrl | RecoveryAction::BackOffAndNewUploadUrl => true,
+            _ => false
+        }
+    }
+}
+
+/// Decides how to recover from a failed upload, following the upload error handling described
+/// in the [backblaze documentation][1]. The retry loop of [`upload_large_file`] uses this
+/// function, and retry loops for other uploads can use it too.
+///
+/// | Error | `GetUploadUrl` | `Upload` |
+/// |-------|----------------|----------|
+/// | 401 `expired_auth_token` or `bad_auth_token` | `Reauthorize` | `NewUploadUrl` |
+/// | 401 with any other code | `Fail` | `Fail` |
+/// | 402 or 403 | `Fail` | `Fail` |
+/// | 408 | `BackOff` | `BackOffAndNewUploadUrl` |
+/// | 429 | `BackOff` | `BackOff` |
+/// | 500 and other 5xx | `BackOff` | `NewUploadUrl` |
+/// | 503 | `BackOff` | `BackOffAndNewUploadUrl` |
+/// | any other status | `Fail` | `Fail` |
+/// | io error where [`should_obtain_new_authentication`] is true | `BackOff` | `NewUploadUrl` |
+/// | any other error | `Fail` | `Fail` |
+///
+/// The status decides the action even if the body of the response is not an error message from
+/// backblaze, such as an [`UnexpectedResponse`] sent by a proxy. Without an error code, a 401
+/// cannot be told apart from other authorization failures, so it is not retried.
+///
+/// A connection failure during an upload usually means the pod serving the upload url is busy
+/// or gone, which is why a new upload url is obtained.
+///
+/// ```rust
+///use std::io;
+///use backblaze_b2::B2Error;
+///use backblaze_b2::retry::{upload_recovery_action, RecoveryAction, UploadContext};
+///
+///let err = B2Error::from(io::Error::new(io::ErrorKind::ConnectionReset, "reset"));
+///assert_eq!(upload_recovery_action(&err, UploadContext::Upload),
+///           RecoveryAction::NewUploadUrl);
+///assert_eq!(upload_recovery_action(&err, UploadContext::GetUploadUrl),
+///           RecoveryAction::BackOff);
+/// ```
+///
+///  [1]: https://www.backblaze.com/b2/docs/integration_checklist.html
+///  [`upload_large_file`]: ../raw/authorize/struct.B2Authorization.html#method.upload_large_file
+///  [`should_obtain_new_authentication`]: ../enum.B2Error.html#method.should_obtain_new_authentication
+///  [`UnexpectedResponse`]: ../enum.B2Error.html#variant.UnexpectedResponse
+pub fn upload_recovery_action(err: &B2Error, context: UploadContext) -> RecoveryAction {
+    let upload = context == UploadContext::Upload;
+    match err.http_status() {
+        Some(401) => match *err {
+            B2Error::B2Error(_, ref message) => match message.code.as_str() {
+                "expired_auth_token" | "bad_auth_token" if upload => RecoveryAction::NewUploadUrl,
+                "expired_auth_token" | "bad_auth_token" => RecoveryAction::Reauthorize,
+                _ => RecoveryAction::Fail
+            },
+            _ => RecoveryAction::Fail
+        },
+        Some(408) | Some(503) if upload => RecoveryAction::BackOffAndNewUploadUrl,
+        Some(408) | Some(429) => RecoveryAction::BackOff,
+        Some(status) if status >= 500 && status <= 599 => {
+            if upload { RecoveryAction::NewUploadUrl } else { RecoveryAction::BackOff }
+        },
+        Some(_) => RecoveryAction::Fail,
+        None if err.should_obtain_new_authentication() => {
+            if upload { RecoveryAction::NewUploadUrl } else { RecoveryAction::BackOff }
+        },
+        None => RecoveryAction::Fail
+    }
+}
@@ -0,0 +1,261 @@
+//! This module defines helpers for moving files between backblaze accounts.
+//!
+//! The [b2_copy_file][1] api call only works within a single account, so moving a file to a
+//! different account requires downloading it with the credentials of the source account and
+//! uploading it with the credentials of the destination account. The [`copy_between_accounts`]
+//! function does this without storing the file locally: the file is downloaded in ranges of at
+//! most one part, and each range is uploaded before the next is downloaded.
+//!
+//!  [1]: https://www.backblaze.com/b2/docs/b2_copy_file.html
+//!  [`copy_between_accounts`]: fn.copy_between_accounts.html
+
+use std::io::{self, Read};
+
+use hyper::Client;
+use hyper::client::Response;
+use hyper::header::{ContentRange, ContentRangeSpec, Headers};
+use hyper::mime::Mime;
+use hyper::net::{NetworkConnector, NetworkStream};
+
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue};
+
+use sha1::Sha1;
+
+use B2Error;
+use raw::authorize::B2Authorization;
+use raw::download::{ByteRange, DownloadAuthorization, FileSelector, XBzFileId};
+use raw::files::{FileInfo, MoreFileInfo, UnfinishedLargeFileInfo};
+use raw::large_file::{check_part_count, part_retry_policy};
+use raw::upload::set_file_info_headers;
+
+/// Options for [`copy_between_accounts`]. The default copies no file info and uses the
+/// recommended part size of the destination account.
+///
+///  [`copy_between_accounts`]: fn.copy_between_accounts.html
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct TransferOptions {
+    /// The size of the ranges downloaded from the source account, and of the parts uploaded to
+    /// the destination account. At most this many bytes of the file are kept in memory. Defaults
+    /// to the [`recommended_part_size`] of the destination account.
+    ///
+    ///  [`recommended_part_size`]: ../raw/authorize/struct.B2Authorization.html#structfield.recommended_part_size
+    pub part_size: Option<u64>,
+    /// The names of the file info entries copied to the new file. Entries the source file does
+    /// not have are skipped.
+    pub file_info_keys: Vec<String>
+}
+
+/// The result of a successful [`copy_between_accounts`] call.
+///
+///  [`copy_between_accounts`]: fn.copy_between_accounts.html
+#[derive(Debug,Clone)]
+pub struct TransferReport<InfoType=JsonValue> {
+    /// Information about the new file in the destination account.
+    pub file: MoreFileInfo<InfoType>,
+    /// The amount of bytes sent from the source account to the destination account.
+    pub bytes_transferred: u64
+}
+
+/// Copies the file selected by `file` from the account of `src` into the bucket with the id
+/// `bucket_id` in the account of `dst`, where it is stored with the name `file_name`. Both
+/// variants of [`FileSelector`] are supported.
+///
+/// The file is downloaded in ranges of the part size given in `options`, so at most one part is
+/// kept in memory. If the file fits in a single part, it is uploaded using
+/// [b2_upload_file][1]. Otherwise it is uploaded as a large file, where each range is uploaded
+/// as a part before the next range is downloaded, and failed parts are retried like in
+/// [`upload_large_file`]. The content type and the file info entries named in `options` are
+/// preserved, and the sha1 of the source file is stored in the `large_file_sha1` file info of
+/// large copies.
+///
+/// The sha1 of the copied data is compared with the [`effective_sha1`] of the source file, if it
+/// is known. If a file selected by name is replaced while it is copied, the copy fails instead
+/// of mixing the two versions.
+///
+/// # Errors
+/// This function returns a [`B2Error`] in case something goes wrong. Besides the standard errors,
+/// this function can fail with [`is_file_not_found`], [`is_invalid_file_name`] and
+/// [`is_cap_exceeded`]. The error [`InvalidRequest`] is returned if the part size is smaller
+/// than the [`absolute_minimum_part_size`] of `dst`, if the file would have more than
+/// [`MAX_PART_COUNT`] parts. It is also returned if one of the file info entries named in
+/// `options` is not a string, or if the file info of the copy would have more than
+/// [`MAX_FILE_INFO_ENTRIES`] entries, which is checked the same way for small and large copies.
+/// An io error with the kind
+/// `InvalidData` is returned if the copied data does not match the sha1 of the source file, and
+/// [`is_conflict`] is true if the source file was replaced while it was copied.
+/// [`ApiInconsistency`] is returned if the source account sends a content type or range that
+/// cannot be parsed. If a large copy fails, the unfinished large file is cancelled.
+///
+///  [1]: https://www.backblaze.com/b2/docs/b2_upload_file.html
+///  [`FileSelector`]: ../raw/download/enum.FileSelector.html
+///  [`upload_large_file`]: ../raw/authorize/struct.B2Authorization.html#method.upload_large_file
+///  [`effective_sha1`]: ../raw/files/fn.effective_sha1.html
+///  [`absolute_minimum_part_size`]: ../raw/authorize/struct.B2Authorization.html#structfield.absolute_minimum_part_size
+///  [`MAX_PART_COUNT`]: ../raw/large_file/constant.MAX_PART_COUNT.html
+///  [`MAX_FILE_INFO_ENTRIES`]: ../raw/upload/constant.MAX_FILE_INFO_ENTRIES.html
+///  [`B2Error`]: ../enum.B2Error.html
+///  [`InvalidRequest`]: ../enum.B2Error.html#variant.InvalidRequest
+///  [`ApiInconsistency`]: ../enum.B2Error.html#variant.ApiInconsistency
+///  [`is_file_not_found`]: ../enum.B2Error.html#method.is_file_not_found
+///  [`is_invalid_file_name`]: ../enum.B2Error.html#method.is_invalid_file_name
+///  [`is_cap_exceeded`]: ../enum.B2Error.html#method.is_cap_exceeded
+///  [`is_conflict`]: ../enum.B2Error.html#method.is_conflict
+pub fn copy_between_accounts<'a, F, InfoType, C, S>(src: &DownloadAuthorization, file: F,
+                                                     dst: &B2Authorization, bucket_id: &str,
+                                                     file_name: &str, options: &TransferOptions,
+                                                     client: &Client, connector: &C)
+    -> Result<TransferReport<InfoType>, B2Error>
+    where F: Into<FileSelector<'a>>, for<'de> InfoType: Deserialize<'de>,
+          C: NetworkConnector<Stream=S>, S: Into<Box<NetworkStream + Send>>
+{
+    let file = file.into();
+    let part_size = dst.check_part_size(options.part_size)?;
+
+    let (mut resp, info, total) = match src.download_byte_range(
+        file, ByteRange::Inclusive(0, part_size - 1), client)
+    {
+        Ok((resp, info)) => {
+            let total = content_range_length(&resp.headers)?;
+            (resp, info, total)
+        },
+        // backblaze does not allow range requests for empty files
+        Err(ref err) if err.is_range_out_of_bounds() => {
+            let (resp, info) = src.download_file(file, client)?;
+            (resp, info, 0)
+        },
+        Err(err) => return Err(err)
+    };
+    let info: FileInfo<JsonValue> = match info {
+        Some(info) => info,
+        None => return Err(B2Error::ApiInconsistency(
+                "download response did not contain the file headers".to_owned()))
+    };
+    let content_type: Mime = match info.content_type.parse() {
+        Ok(mime) => mime,
+        Err(_) => return Err(B2Error::ApiInconsistency(
+                format!("the content type {:?} cannot be parsed", info.content_type)))
+    };
+    let source_sha1 = info.effective_sha1().sha1().map(|sha1| sha1.to_owned());
+    let mut file_info = Map::new();
+    for key in &options.file_info_keys {
+        match info.file_info.get(key) {
+            Some(value @ &JsonValue::String(_)) => {
+                file_info.insert(key.clone(), value.clone());
+            },
+            Some(_) => return Err(B2Error::InvalidRequest {
+                field: "file_info_keys",
+                reason: format!("the file info {:?} of the source file is not a string", key)
+            }),
+            None => {}
+        }
+    }
+    if total > part_size {
+        check_part_count(total, part_size)?;
+        if let Some(ref sha1) = source_sha1 {
+            file_info.insert("large_file_sha1".to_owned(), JsonValue::String(sha1.clone()));
+        }
+    }
+    // both paths send the file info as headers or as json, but validate it the same way, such
+    // that a copy does not fail or succeed depending on the size of the file
+    let mut headers = Headers::new();
+    set_file_info_headers(&mut headers, file_info.iter().map(|(key, value)| {
+        // every value is a string, as checked when the file info was collected
+        (key.as_str(), value.as_str().unwrap_or(""))
+    }))?;
+
+    let mut buffer = Vec::new();
+    read_range(&mut resp, total.min(part_size), &mut buffer)?;
+
+    if total <= part_size {
+        let sha1 = sha1_of(&buffer);
+        check_sha1(source_sha1.as_ref().map(|sha1| sha1.as_str()), &sha1)?;
+        let upload_auth = dst.get_upload_url(bucket_id, client)?;
+        let mut ufr = upload_auth.create_upload_file_request_with_headers(
+            file_name.to_owned(), Some(content_type), total, sha1, headers, connector)?;
+        io::copy(&mut &buffer[..], &mut ufr)?;
+        return Ok(TransferReport {
+            file: ufr.finish()?,
+            bytes_transferred: total
+        });
+    }
+
+    let large: UnfinishedLargeFileInfo<JsonValue> = dst.start_large_file(
+        bucket_id, file_name, Some(content_type), JsonValue::Object(file_info), client)?;
+    let mut copy = || -> Result<Vec<String>, B2Error> {
+        let mut upload_auth = dst.get_upload_part_url(&large.file_id, client)?;
+        let mut whole_sha1 = Sha1::new();
+        let mut part_sha1_array = Vec::new();
+        let mut position = 0;
+        loop {
+            whole_sha1.update(&buffer);
+            let sha1 = sha1_of(&buffer);
+            dst.upload_part_with_retries(&mut upload_auth, &buffer, part_sha1_array.len() as u32 + 1,
+                                         &sha1, &part_retry_policy(), client, connector)?;
+            part_sha1_array.push(sha1);
+            position += buffer.len() as u64;
+            if position >= total {
+                break;
+            }
+            let end = (position + part_size).min(total);
+            let (mut resp, _): (Response, Option<FileInfo<JsonValue>>) =
+                src.download_byte_range(file, ByteRange::Inclusive(position, end - 1), client)?;
+            if resp.headers.get::<XBzFileId>().map(|id| id.0.as_str()) != Some(&info.file_id) {
+                return Err(B2Error::Conflict {
+                    file_name: info.file_name.clone(),
+                    reason: format!("the range {}-{} belongs to a different file than {}",
+                                    position, end - 1, info.file_id)
+                });
+            }
+            read_range(&mut resp, end - position, &mut buffer)?;
+        }
+        check_sha1(source_sha1.as_ref().map(|sha1| sha1.as_str()),
+                   &whole_sha1.digest().to_string())?;
+        Ok(part_sha1_array)
+    };
+    match copy() {
+        Ok(part_sha1_array) => Ok(TransferReport {
+            file: dst.finish_large_file(&large.file_id, &part_sha1_array, client)?,
+            bytes_transferred: total
+        }),
+        Err(err) => {
+            // the original error is more interesting than a failure to cancel
+            let _ = dst.cancel_large_file(&large.file_id, client);
+            Err(err)
+        }
+    }
+}
+
+/// Returns the length of the whole file from the `Content-Range` header of a range response.
+fn content_range_length(headers: &Headers) -> Result<u64, B2Error> {
+    match headers.get::<ContentRange>() {
+        Some(&ContentRange(ContentRangeSpec::Bytes { instance_length: Some(length), .. })) =>
+            Ok(length),
+        _ => Err(B2Error::ApiInconsistency(
+                "range response did not contain the length of the file".to_owned()))
+    }
+}
+/// Replaces the contents of `buffer` with exactly `length` bytes from `resp`.
+fn read_range<R: Read>(resp: &mut R, length: u64, buffer: &mut Vec<u8>) -> Result<(), B2Error> {
+    buffer.clear();
+    Read::read_to_end(&mut resp.take(length), buffer)?;
+    if (buffer.len() as u64) < length {
+        return Err(B2Error::IOError(io::Error::new(io::ErrorKind::UnexpectedEof,
+            "the download ended before the end of the range")));
+    }
+    Ok(())
+}
+fn sha1_of(data: &[u8]) -> String {
+    let mut sha1 = Sha1::new();
+    sha1.update(data);
+    sha1.digest().to_string()
+}
+fn check_sha1(expected: Option<&str>, actual: &str) -> Result<(), B2Error> {
+    match expected {
+        Some(expected) if expected != actual => Err(B2Error::IOError(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("the copied data has the sha1 {}, but the source file has the sha1 {}",
+                    actual, expected)))),
+        _ => Ok(())
+    }
+}
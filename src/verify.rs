@@ -0,0 +1,265 @@
+//! This module defines helpers for checking that the files in a bucket match a local manifest.
+//!
+//! The manifest is compared to the file listing of the bucket in a single pass, such that neither
+//! the manifest nor the listing has to be kept in memory. This requires the manifest to be sorted
+//! in the order used by backblaze, which is the order defined by [`file_name_cmp`].
+//!
+//!  [`file_name_cmp`]: ../raw/files/fn.file_name_cmp.html
+
+use std::cmp::Ordering;
+use std::io::{self, Read};
+use std::time::Instant;
+
+use hyper::Client;
+
+use serde_json::Value as JsonValue;
+
+use sha1::Sha1;
+
+use B2Error;
+use raw::authorize::B2Authorization;
+use raw::files::{FileInfo, Sha1Availability, file_name_cmp};
+
+/// A file that is expected to be found in the bucket.
+#[derive(Debug,Clone,PartialEq,Eq)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub size: u64,
+    /// The hex encoded sha1 of the file.
+    pub sha1: String
+}
+
+/// Options for [`verify_manifest`].
+///
+///  [`verify_manifest`]: fn.verify_manifest.html
+#[derive(Debug,Clone,Copy)]
+pub struct VerifyOptions {
+    /// Large files have no sha1 stored on backblaze. If this is true, such files and files with a
+    /// malformed sha1 are downloaded and hashed, otherwise they are reported as unverified.
+    pub deep: bool,
+    /// If the deadline passes, the verification stops and the report is marked as incomplete.
+    pub deadline: Option<Instant>,
+    /// The amount of files requested per [`list_file_names`] call.
+    ///
+    ///  [`list_file_names`]: ../raw/authorize/struct.B2Authorization.html#method.list_file_names
+    pub files_per_request: u32
+}
+impl Default for VerifyOptions {
+    fn default() -> VerifyOptions {
+        VerifyOptions {
+            deep: false,
+            deadline: None,
+            files_per_request: 1000
+        }
+    }
+}
+
+/// A difference between the manifest and the bucket.
+#[derive(Debug,Clone)]
+pub enum Discrepancy {
+    /// The file is in the manifest, but not in the bucket.
+    Missing(ManifestEntry),
+    /// The file is in the bucket, but not in the manifest.
+    Extra(FileInfo),
+    /// The file in the bucket has a different size than the manifest entry.
+    SizeMismatch { expected: ManifestEntry, actual: FileInfo },
+    /// The file in the bucket has a different sha1 than the manifest entry.
+    Sha1Mismatch { expected: ManifestEntry, actual: FileInfo }
+}
+
+/// The result of comparing a manifest with a bucket.
+#[derive(Debug,Clone)]
+pub struct VerifyReport {
+    /// The amount of manifest entries that were found in the bucket.
+    pub checked: u64,
+    pub discrepancies: Vec<Discrepancy>,
+    /// The names of files whose sha1 was not verified, because they have no valid sha1 on
+    /// backblaze and deep verification was disabled.
+    pub unverified: Vec<String>,
+    /// False if the deadline passed before the comparison finished. In this case files after the
+    /// last compared name were not compared.
+    pub complete: bool
+}
+impl VerifyReport {
+    /// Returns true if the comparison finished and found no discrepancies.
+    pub fn is_ok(&self) -> bool {
+        self.complete && self.discrepancies.is_empty()
+    }
+}
+
+/// Compares every file in the bucket `bucket_id` with the manifest, which must be sorted
+/// according to [`file_name_cmp`]. Files are compared by size and sha1.
+///
+/// If `deep` is set in the options, large files are downloaded in order to compute their sha1.
+///
+/// # Errors
+/// This function returns a [`B2Error`] in case something goes wrong. Besides the standard errors,
+/// this function can fail with [`is_bucket_not_found`]. If the manifest is not sorted,
+/// [`InvalidRequest`] is returned.
+///
+///  [`file_name_cmp`]: ../raw/files/fn.file_name_cmp.html
+///  [`B2Error`]: ../enum.B2Error.html
+///  [`is_bucket_not_found`]: ../enum.B2Error.html#method.is_bucket_not_found
+///  [`InvalidRequest`]: ../enum.B2Error.html#variant.InvalidRequest
+pub fn verify_manifest<M>(auth: &B2Authorization, bucket_id: &str, manifest: M,
+                          options: &VerifyOptions, client: &Client)
+    -> Result<VerifyReport, B2Error>
+    where M: IntoIterator<Item=ManifestEntry>
+{
+    let download_auth = auth.to_download_authorization();
+    let listing = auth.iter_file_names::<JsonValue>(bucket_id, options.files_per_request, None,
+                                                    client);
+    let deep = options.deep;
+    compare_manifest(manifest, listing, options.deadline, |file| {
+        if !deep {
+            return Ok(None);
+        }
+        let (mut resp, _): (_, Option<FileInfo>) =
+            download_auth.download_file_by_id(&file.file_id, client)?;
+        let mut sha1 = Sha1::new();
+        let mut buf = [0; 64 * 1024];
+        loop {
+            match resp.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => sha1.update(&buf[..n]),
+                Err(ref err) if err.kind() == io::ErrorKind::Interrupted => {},
+                Err(err) => return Err(B2Error::from(err))
+            }
+        }
+        Ok(Some(sha1.digest().to_string()))
+    })
+}
+
+/// Performs the comparison of [`verify_manifest`] on an arbitrary listing, which must be sorted
+/// like the listings returned by backblaze. The sha1 of each file is decided by
+/// [`effective_sha1`]. The closure is called for files without a known sha1, such as large files
+/// without a `large_file_sha1` file info and files with a malformed sha1, and should return their
+/// sha1, or `None` if they should be reported as unverified.
+///
+/// ```rust
+///use backblaze_b2::raw::files::FileInfo;
+///use backblaze_b2::verify::*;
+///
+///let file = |name: &str, size: u64, sha1: &str| FileInfo {
+///    file_id: format!("id of {}", name), file_name: name.to_owned(),
+///    content_length: size, content_type: "text/plain".to_owned(),
+///    content_sha1: sha1.to_owned(), file_info: Default::default(), upload_timestamp: 0
+///};
+///let entry = |name: &str, size: u64, sha1: &str| ManifestEntry {
+///    name: name.to_owned(), size: size, sha1: sha1.to_owned()
+///};
+///let manifest = vec![entry("a", 1, "aa"), entry("b", 2, "bb"), entry("c", 3, "cc")];
+///let listing = vec![file("a", 1, "aa"), file("c", 4, "cc"), file("d", 5, "dd")];
+///
+///let report = compare_manifest(manifest, listing.into_iter().map(Ok), None, |_| Ok(None))
+///    .unwrap();
+///assert_eq!(report.checked, 2);
+///assert_eq!(report.discrepancies.len(), 3);
+///match report.discrepancies[0] {
+///    Discrepancy::Missing(ref entry) => assert_eq!(entry.name, "b"),
+///    ref other => panic!("{:?}", other)
+///}
+/// ```
+///
+/// # Errors
+/// Errors returned by the listing or the closure are returned. If the manifest is not sorted,
+/// [`InvalidRequest`] is returned.
+///
+///  [`verify_manifest`]: fn.verify_manifest.html
+///  [`effective_sha1`]: ../raw/files/fn.effective_sha1.html
+///  [`InvalidRequest`]: ../enum.B2Error.html#variant.InvalidRequest
+pub fn compare_manifest<M, L, F>(manifest: M, listing: L, deadline: Option<Instant>,
+                                 mut large_file_sha1: F)
+    -> Result<VerifyReport, B2Error>
+    where M: IntoIterator<Item=ManifestEntry>, L: IntoIterator<Item=Result<FileInfo, B2Error>>,
+          F: FnMut(&FileInfo) -> Result<Option<String>, B2Error>
+{
+    let mut report = VerifyReport {
+        checked: 0,
+        discrepancies: Vec::new(),
+        unverified: Vec::new(),
+        complete: true
+    };
+    let mut manifest = manifest.into_iter();
+    let mut listing = listing.into_iter();
+    let mut previous_name: Option<String> = None;
+    let mut next_entry = |manifest: &mut M::IntoIter| -> Result<Option<ManifestEntry>, B2Error> {
+        let entry = manifest.next();
+        if let Some(ref entry) = entry {
+            if let Some(ref previous) = previous_name {
+                if file_name_cmp(previous, &entry.name) != Ordering::Less {
+                    return Err(B2Error::InvalidRequest {
+                        field: "manifest",
+                        reason: format!("{} is not sorted after {}", entry.name, previous)
+                    });
+                }
+            }
+            previous_name = Some(entry.name.clone());
+        }
+        Ok(entry)
+    };
+    let mut entry = next_entry(&mut manifest)?;
+    let mut file = match listing.next() {
+        Some(file) => Some(file?),
+        None => None
+    };
+    loop {
+        if let Some(deadline) = deadline {
+            if Instant::now() >= deadline {
+                report.complete = false;
+                return Ok(report);
+            }
+        }
+        let order = match (&entry, &file) {
+            (&None, &None) => return Ok(report),
+            (&Some(_), &None) => Ordering::Less,
+            (&None, &Some(_)) => Ordering::Greater,
+            (&Some(ref entry), &Some(ref file)) => file_name_cmp(&entry.name, &file.file_name)
+        };
+        if order != Ordering::Greater {
+            let current = entry.take().unwrap();
+            if order == Ordering::Less {
+                report.discrepancies.push(Discrepancy::Missing(current));
+            } else {
+                let current_file = file.take().unwrap();
+                report.checked += 1;
+                compare_entry(current, current_file, &mut large_file_sha1, &mut report)?;
+            }
+            entry = next_entry(&mut manifest)?;
+        } else {
+            report.discrepancies.push(Discrepancy::Extra(file.take().unwrap()));
+        }
+        if file.is_none() {
+            file = match listing.next() {
+                Some(file) => Some(file?),
+                None => None
+            };
+        }
+    }
+}
+
+fn compare_entry<F>(entry: ManifestEntry, file: FileInfo, large_file_sha1: &mut F,
+                    report: &mut VerifyReport) -> Result<(), B2Error>
+    where F: FnMut(&FileInfo) -> Result<Option<String>, B2Error>
+{
+    if entry.size != file.content_length {
+        report.discrepancies.push(Discrepancy::SizeMismatch { expected: entry, actual: file });
+        return Ok(());
+    }
+    // a malformed sha1 is unavailable rather than a mismatch, and unverified: is stripped
+    let sha1 = match file.effective_sha1() {
+        Sha1Availability::Exact(sha1) => sha1,
+        Sha1Availability::LargeFileDeclared(sha1) => sha1,
+        Sha1Availability::Unavailable => match large_file_sha1(&file)? {
+            Some(sha1) => sha1,
+            None => {
+                report.unverified.push(file.file_name);
+                return Ok(());
+            }
+        }
+    };
+    if !sha1.eq_ignore_ascii_case(&entry.sha1) {
+        report.discrepancies.push(Discrepancy::Sha1Mismatch { expected: entry, actual: file });
+    }
+    Ok(())
+}
@@ -0,0 +1,78 @@
+extern crate backblaze_b2;
+extern crate hyper;
+extern crate serde_json;
+
+mod mock;
+
+use std::io;
+
+use backblaze_b2::raw::authorize::B2Credentials;
+
+use mock::MockConnector;
+
+fn credentials() -> B2Credentials {
+    B2Credentials::new("account".to_owned(), "key".to_owned()).unwrap()
+}
+fn push_authorization(mock: &MockConnector) {
+    mock.push_json("200 OK", r#"{"authorizationToken":"token","apiUrl":"http://api.example",
+                                 "downloadUrl":"http://download.example",
+                                 "recommendedPartSize":100000000,
+                                 "absoluteMinimumPartSize":5000000}"#);
+}
+
+#[test]
+fn falls_back_when_the_primary_host_refuses() {
+    let mock = MockConnector::new();
+    mock.push_connect_error(io::ErrorKind::ConnectionRefused, "connection refused");
+    push_authorization(&mock);
+
+    let (auth, host) = credentials()
+        .authorize_with_fallbacks(&["http://fallback.example"], &mock.client()).unwrap();
+    assert_eq!(host, "http://fallback.example");
+    assert_eq!(auth.api_url, "http://api.example");
+    let hosts: Vec<String> = mock.requests().into_iter().map(|req| req.host).collect();
+    assert_eq!(hosts, vec!["api.backblazeb2.com", "fallback.example"]);
+}
+
+#[test]
+fn falls_back_on_dns_errors_and_connect_timeouts() {
+    let mock = MockConnector::new();
+    mock.push_connect_error(io::ErrorKind::Other,
+                            "failed to lookup address information: Name or service not known");
+    mock.push_connect_error(io::ErrorKind::TimedOut, "connection timed out");
+    push_authorization(&mock);
+
+    let (_, host) = credentials()
+        .authorize_with_fallbacks(&["http://first.example", "http://second.example"],
+                                  &mock.client()).unwrap();
+    assert_eq!(host, "http://second.example");
+    assert_eq!(mock.replies_left(), 0);
+}
+
+#[test]
+fn other_errors_do_not_fall_back() {
+    let mock = MockConnector::new();
+    mock.push_connect_error(io::ErrorKind::PermissionDenied, "permission denied");
+    let err = credentials()
+        .authorize_with_fallbacks(&["http://fallback.example"], &mock.client()).unwrap_err();
+    assert!(err.http_status().is_none());
+
+    // the host was reachable, so its answer is final
+    mock.push_json("503 Service Unavailable",
+                   r#"{"status":503,"code":"service_unavailable","message":"busy"}"#);
+    let err = credentials()
+        .authorize_with_fallbacks(&["http://fallback.example"], &mock.client()).unwrap_err();
+    assert_eq!(err.http_status(), Some(503));
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn last_connect_failure_is_returned() {
+    let mock = MockConnector::new();
+    mock.push_connect_error(io::ErrorKind::ConnectionRefused, "connection refused");
+    mock.push_connect_error(io::ErrorKind::TimedOut, "connection timed out");
+    let err = credentials()
+        .authorize_with_fallbacks(&["http://fallback.example"], &mock.client()).unwrap_err();
+    assert!(err.is_timeout());
+    assert_eq!(mock.requests().len(), 2);
+}
@@ -0,0 +1,111 @@
+extern crate backblaze_b2;
+#[macro_use]
+extern crate serde_json;
+
+use serde_json::Value;
+
+use backblaze_b2::raw::buckets::*;
+use backblaze_b2::raw::encryption::DefaultServerSideEncryption;
+
+fn bucket() -> Bucket {
+    Bucket {
+        account_id: "account".to_owned(),
+        bucket_id: "bucket".to_owned(),
+        bucket_name: "my-bucket".to_owned(),
+        bucket_type: BucketType::Private,
+        bucket_info: json!({ "owner": "ops" }),
+        lifecycle_rules: vec![LifecycleRule {
+            file_name_prefix: "logs/".to_owned(),
+            days_from_uploading_to_hiding: None,
+            days_from_hiding_to_deleting: Some(7)
+        }],
+        revision: 3,
+        default_server_side_encryption: None,
+        file_lock_configuration: None,
+        replication_configuration: None,
+        options: Some(vec!["s3".to_owned()])
+    }
+}
+
+#[test]
+fn differs_only_by_account() {
+    let (a, mut b) = (bucket(), bucket());
+    b.account_id = "other account".to_owned();
+    assert!(!a.same_identity(&b));
+    assert!(!b.same_identity(&a));
+    assert!(a.same_settings(&b));
+    assert_eq!(a.is_newer_than(&b), None);
+    b.revision = 4;
+    assert_eq!(b.is_newer_than(&a), None);
+}
+#[test]
+fn differs_only_by_bucket_id() {
+    let (a, mut b) = (bucket(), bucket());
+    b.bucket_id = "other bucket".to_owned();
+    assert!(!a.same_identity(&b));
+    assert!(a.same_settings(&b));
+    assert_eq!(a.is_newer_than(&b), None);
+}
+#[test]
+fn differs_only_by_revision() {
+    let (a, mut b) = (bucket(), bucket());
+    b.revision = 4;
+    assert!(a.same_identity(&b));
+    assert!(a.same_settings(&b));
+    assert_eq!(b.is_newer_than(&a), Some(true));
+    assert_eq!(a.is_newer_than(&b), Some(false));
+    // equal revisions are not newer in either direction
+    assert_eq!(a.is_newer_than(&bucket()), Some(false));
+}
+#[test]
+fn every_setting_is_compared() {
+    let changes: Vec<fn(&mut Bucket)> = vec![
+        |b| b.bucket_name = "other-name".to_owned(),
+        |b| b.bucket_type = BucketType::Public,
+        |b| b.bucket_info = json!({ "owner": "dev" }),
+        |b| b.lifecycle_rules.clear(),
+        |b| b.default_server_side_encryption = Some(DefaultServerSideEncryption {
+            is_client_authorized_to_read: false,
+            value: None
+        }),
+        |b| b.file_lock_configuration = Some(FileLockConfiguration {
+            is_client_authorized_to_read: false,
+            value: None
+        }),
+        |b| b.replication_configuration = Some(ReplicationConfigurationSetting {
+            is_client_authorized_to_read: false,
+            value: None
+        }),
+        |b| b.options = None,
+    ];
+    for (i, change) in changes.into_iter().enumerate() {
+        let (a, mut b) = (bucket(), bucket());
+        change(&mut b);
+        assert!(!a.same_settings(&b), "change {} was not detected", i);
+        assert!(!b.same_settings(&a), "change {} was not detected", i);
+        // the settings do not affect the identity or the revision
+        assert!(a.same_identity(&b));
+        assert_eq!(a.is_newer_than(&b), Some(false));
+    }
+}
+#[test]
+fn identity_ignores_the_info_type() {
+    let a = bucket();
+    let b: Bucket<Value> = serde_json::from_value(serde_json::to_value(&a).unwrap()).unwrap();
+    let c: Bucket<()> = Bucket {
+        account_id: b.account_id.clone(),
+        bucket_id: b.bucket_id.clone(),
+        bucket_name: b.bucket_name.clone(),
+        bucket_type: b.bucket_type,
+        bucket_info: (),
+        lifecycle_rules: Vec::new(),
+        revision: 5,
+        default_server_side_encryption: None,
+        file_lock_configuration: None,
+        replication_configuration: None,
+        options: None
+    };
+    assert!(a.same_settings(&b));
+    assert!(a.same_identity(&c));
+    assert_eq!(c.is_newer_than(&a), Some(true));
+}
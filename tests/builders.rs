@@ -0,0 +1,284 @@
+extern crate backblaze_b2;
+extern crate hyper;
+#[macro_use]
+extern crate serde_json;
+
+mod mock;
+
+use serde_json::Value;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::buckets::*;
+use backblaze_b2::raw::download::ByteRange;
+use backblaze_b2::raw::encryption::ServerSideEncryption;
+use backblaze_b2::raw::files::*;
+
+use mock::{auth, MockConnector};
+
+fn push_file(mock: &MockConnector) {
+    mock.push_json("200 OK", &json!({
+        "fileId": "copy", "fileName": "copy.bin", "accountId": "account", "bucketId": "bucket",
+        "contentLength": 1, "contentSha1": "none", "contentType": "text/plain",
+        "fileInfo": {}, "action": "upload", "uploadTimestamp": 10
+    }).to_string());
+}
+fn push_bucket(mock: &MockConnector) {
+    mock.push_json("200 OK", &json!({
+        "accountId": "account", "bucketId": "bucket", "bucketName": "my-bucket",
+        "bucketType": "allPrivate", "bucketInfo": {}, "lifecycleRules": [], "revision": 1
+    }).to_string());
+}
+fn push_listing(mock: &MockConnector) {
+    mock.push_json("200 OK", r#"{"files":[],"nextFileName":null,"nextFileId":null}"#);
+}
+/// Returns the bodies of the two requests sent by `mock`.
+fn bodies(mock: &MockConnector) -> (Value, Value) {
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    (requests[0].json(), requests[1].json())
+}
+fn rules() -> Vec<LifecycleRule> {
+    vec![LifecycleRule {
+        file_name_prefix: "logs/".to_owned(),
+        days_from_uploading_to_hiding: None,
+        days_from_hiding_to_deleting: Some(7)
+    }]
+}
+
+#[test]
+fn copy_file_defaults() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    push_file(&mock);
+    push_file(&mock);
+    let _: MoreFileInfo = auth.copy_file("source", "copy.bin", None, None,
+                                         MetadataDirective::Copy, &client).unwrap();
+    let _: MoreFileInfo = CopyFile::new("source", "copy.bin").send(&auth, &client).unwrap();
+    let (positional, builder) = bodies(&mock);
+    assert_eq!(positional, builder);
+    assert_eq!(builder, json!({
+        "sourceFileId": "source", "fileName": "copy.bin", "metadataDirective": "COPY"
+    }));
+}
+#[test]
+fn copy_file_with_every_argument() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    let key = ServerSideEncryption::SseC {
+        algorithm: "AES256".to_owned(),
+        key: "key".to_owned(),
+        key_md5: "md5".to_owned()
+    };
+    let metadata = || MetadataDirective::Replace {
+        content_type: "text/plain".parse().unwrap(),
+        file_info: json!({ "author": "unknown" })
+    };
+    push_file(&mock);
+    push_file(&mock);
+    let _: MoreFileInfo = auth.copy_file_with_encryption(
+        "source", "copy.bin", Some("other"), Some(ByteRange::Inclusive(0, 9)), metadata(),
+        &key, &ServerSideEncryption::SseB2, &client).unwrap();
+    let _: MoreFileInfo = CopyFile::new("source", "copy.bin")
+        .destination_bucket_id("other")
+        .range(ByteRange::Inclusive(0, 9))
+        .metadata(metadata())
+        .source_encryption(&key)
+        .destination_encryption(&ServerSideEncryption::SseB2)
+        .send(&auth, &client).unwrap();
+    let (positional, builder) = bodies(&mock);
+    assert_eq!(positional, builder);
+    assert_eq!(builder["range"], json!("bytes=0-9"));
+    assert_eq!(builder["destinationBucketId"], json!("other"));
+}
+#[test]
+fn create_bucket_defaults() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    push_bucket(&mock);
+    push_bucket(&mock);
+    auth.create_bucket_no_info("my-bucket", CreatableBucketType::Private, Vec::new(), &client).unwrap();
+    let _: Bucket = CreateBucket::new("my-bucket", CreatableBucketType::Private)
+        .send(&auth, &client).unwrap();
+    let (positional, builder) = bodies(&mock);
+    assert_eq!(positional, builder);
+}
+#[test]
+#[allow(deprecated)]
+fn create_bucket_with_every_argument() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    push_bucket(&mock);
+    push_bucket(&mock);
+    let options = CreateBucketOptions {
+        default_server_side_encryption: Some(ServerSideEncryption::SseB2),
+        file_lock_enabled: true,
+        replication_configuration: Some(ReplicationConfiguration {
+            as_replication_source: None,
+            as_replication_destination: None
+        })
+    };
+    let _: Bucket = auth.create_bucket_with_options(
+        "my-bucket", CreatableBucketType::Private, json!({ "owner": "ops" }), rules(), &options, &client)
+        .unwrap();
+    let _: Bucket = CreateBucket::new("my-bucket", CreatableBucketType::Private)
+        .bucket_info(json!({ "owner": "ops" }))
+        .lifecycle_rules(rules())
+        .default_server_side_encryption(ServerSideEncryption::SseB2)
+        .file_lock_enabled(true)
+        .replication_configuration(ReplicationConfiguration {
+            as_replication_source: None,
+            as_replication_destination: None
+        })
+        .send(&auth, &client).unwrap();
+    let (positional, builder) = bodies(&mock);
+    assert_eq!(positional, builder);
+    assert_eq!(builder["fileLockEnabled"], json!(true));
+}
+#[test]
+fn update_bucket_defaults() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    push_bucket(&mock);
+    push_bucket(&mock);
+    let _: Bucket = auth.update_bucket("bucket", None, None, None, None, &client).unwrap();
+    let _: Bucket = UpdateBucket::new("bucket").send(&auth, &client).unwrap();
+    assert!(mock.requests()[0].request_line().contains("/b2api/v1/b2_update_bucket "));
+    let (positional, builder) = bodies(&mock);
+    assert_eq!(positional, builder);
+    assert_eq!(builder, json!({ "accountId": "account", "bucketId": "bucket" }));
+}
+#[test]
+fn update_bucket_with_every_argument() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    push_bucket(&mock);
+    push_bucket(&mock);
+    let _: Bucket = auth.update_bucket("bucket", Some(CreatableBucketType::Public),
+                                       Some(json!({ "owner": "ops" })), Some(rules()), Some(1),
+                                       &client).unwrap();
+    let _: Bucket = UpdateBucket::new("bucket")
+        .bucket_type(CreatableBucketType::Public)
+        .bucket_info(json!({ "owner": "ops" }))
+        .lifecycle_rules(rules())
+        .if_revision_is(1)
+        .send(&auth, &client).unwrap();
+    let (positional, builder) = bodies(&mock);
+    assert_eq!(positional, builder);
+    assert_eq!(builder["bucketType"], json!("allPublic"));
+    assert_eq!(builder["ifRevisionIs"], json!(1));
+    assert_eq!(builder["lifecycleRules"][0]["fileNamePrefix"], json!("logs/"));
+
+    push_bucket(&mock);
+    let _: Bucket = UpdateBucket::new("bucket")
+        .default_server_side_encryption(ServerSideEncryption::SseB2)
+        .default_retention(DefaultRetention {
+            mode: None,
+            period: None
+        })
+        .file_lock_enabled(true)
+        .replication_configuration(ReplicationConfiguration::default())
+        .send(&auth, &client).unwrap();
+    let body = mock.requests()[2].json();
+    assert_eq!(body["fileLockEnabled"], json!(true));
+    assert_eq!(body["defaultRetention"], json!({ "mode": null, "period": null }));
+    assert_eq!(body["replicationConfiguration"], json!({}));
+    assert!(body.get("defaultServerSideEncryption").is_some());
+    assert!(body.get("bucketInfo").is_none());
+}
+#[test]
+fn update_bucket_revision_mismatch_is_a_conflict() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    mock.push_json("409 Conflict",
+                   r#"{"status":409,"code":"conflict","message":"ifRevisionIs is 1, but 2"}"#);
+    let err = UpdateBucket::new("bucket")
+        .if_revision_is(1)
+        .send(&auth, &client)
+        .map(|bucket: Bucket| bucket)
+        .unwrap_err();
+    assert!(err.is_conflict());
+}
+#[test]
+fn bucket_cache_control_is_sent_under_the_cache_control_key() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    push_bucket(&mock);
+    push_bucket(&mock);
+    let _: Bucket = CreateBucket::new("my-bucket", CreatableBucketType::Public)
+        .cache_control("public, max-age=3600")
+        .send(&auth, &client).unwrap();
+    let _: Bucket = UpdateBucket::new("bucket")
+        .cache_control("no-cache")
+        .send(&auth, &client).unwrap();
+    let (create, update) = bodies(&mock);
+    assert_eq!(create["bucketInfo"], json!({ "Cache-Control": "public, max-age=3600" }));
+    assert_eq!(update["bucketInfo"], json!({ "Cache-Control": "no-cache" }));
+
+    // the other keys of the bucket info are kept
+    push_bucket(&mock);
+    let _: Bucket = UpdateBucket::new("bucket")
+        .bucket_info(json!({ "owner": "ops" }))
+        .cache_control("max-age=60")
+        .send(&auth, &client).unwrap();
+    let body = mock.requests()[2].json();
+    assert_eq!(body["bucketInfo"], json!({ "owner": "ops", "Cache-Control": "max-age=60" }));
+}
+#[test]
+fn invalid_bucket_cache_control_is_rejected_before_sending() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    let err = CreateBucket::new("my-bucket", CreatableBucketType::Public)
+        .cache_control("max-age")
+        .send(&auth, &client)
+        .map(|bucket: Bucket| bucket)
+        .unwrap_err();
+    match err {
+        B2Error::InvalidRequest { field, .. } => assert_eq!(field, "cache_control"),
+        other => panic!("expected an invalid request, got {:?}", other)
+    }
+    let err = UpdateBucket::new("bucket")
+        .cache_control("max-age=soon")
+        .send(&auth, &client)
+        .map(|bucket: Bucket| bucket)
+        .unwrap_err();
+    match err {
+        B2Error::InvalidRequest { field, .. } => assert_eq!(field, "cache_control"),
+        other => panic!("expected an invalid request, got {:?}", other)
+    }
+    assert!(mock.requests().is_empty());
+}
+#[test]
+fn list_file_names() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    push_listing(&mock);
+    push_listing(&mock);
+    let _: (FileNameListing, _) = auth.list_file_names(
+        "bucket", Some("photos/a"), 10, Some("photos/"), Some('/'), &client).unwrap();
+    let _: (FileNameListing, _) = ListFileNames::new("bucket")
+        .start_file_name("photos/a")
+        .max_file_count(10)
+        .prefix("photos/")
+        .delimiter('/')
+        .send(&auth, &client).unwrap();
+    let (positional, builder) = bodies(&mock);
+    assert_eq!(positional, builder);
+}
+#[test]
+fn list_file_versions() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    push_listing(&mock);
+    push_listing(&mock);
+    let _: (FileVersionListing, _, _) = auth.list_file_versions(
+        "bucket", Some("photos/a"), Some("id"), 10, Some("photos/"), Some('/'), &client)
+        .unwrap();
+    let _: (FileVersionListing, _, _) = ListFileVersions::new("bucket")
+        .start("photos/a", Some("id"))
+        .max_file_count(10)
+        .prefix("photos/")
+        .delimiter('/')
+        .send(&auth, &client).unwrap();
+    let (positional, builder) = bodies(&mock);
+    assert_eq!(positional, builder);
+}
@@ -0,0 +1,37 @@
+extern crate backblaze_b2;
+extern crate hyper;
+
+use std::io;
+use std::net::ToSocketAddrs;
+
+use backblaze_b2::B2Error;
+
+fn hyper_io(kind: io::ErrorKind, message: &str) -> B2Error {
+    B2Error::HyperError(hyper::Error::Io(io::Error::new(kind, message)))
+}
+
+#[test]
+fn dns_errors_from_the_standard_library_are_recognized() {
+    // the .invalid top level domain never resolves, so this is the error a real lookup gives
+    let lookup = "host.invalid:443".to_socket_addrs().map(|_| ()).unwrap_err();
+    let err = B2Error::HyperError(hyper::Error::Io(lookup));
+    assert!(err.is_dns_error(), "not recognized as a dns error: {:?}", err);
+    assert!(!err.is_timeout());
+    assert!(!err.is_tls_error());
+}
+#[test]
+fn dns_errors_are_matched_by_message() {
+    assert!(!hyper_io(io::ErrorKind::Other, "connection closed").is_dns_error());
+    assert!(hyper_io(io::ErrorKind::Other, "failed to lookup address information: Name or \
+                                            service not known").is_dns_error());
+    assert!(!B2Error::ApiInconsistency("failed to lookup address".to_owned()).is_dns_error());
+}
+#[test]
+fn timeouts() {
+    assert!(hyper_io(io::ErrorKind::TimedOut, "connection timed out").is_timeout());
+    assert!(hyper_io(io::ErrorKind::WouldBlock, "resource temporarily unavailable").is_timeout());
+    // a timeout while reading a body is reported the same way
+    let body = B2Error::IOError(io::Error::new(io::ErrorKind::TimedOut, "read timed out"));
+    assert!(body.is_timeout());
+    assert!(!hyper_io(io::ErrorKind::ConnectionRefused, "connection refused").is_timeout());
+}
@@ -0,0 +1,96 @@
+extern crate backblaze_b2;
+
+use std::time::{Duration, Instant};
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::download::{DownloadAuthCache, DownloadAuthorization};
+
+const HOUR: u64 = 60 * 60;
+
+/// Performs a lookup, returning the token and whether a new authorization was created.
+fn lookup(cache: &mut DownloadAuthCache, bucket_id: &str, prefix: &str, min_remaining: u64,
+          now: Instant) -> (String, bool)
+{
+    let mut created = false;
+    let auth = cache.get_or_create_with(bucket_id, prefix, Duration::from_secs(min_remaining),
+                                        now, |bucket_id, prefix, seconds| {
+        created = true;
+        Ok(DownloadAuthorization {
+            authorization_token: format!("{} {} {}", bucket_id, prefix, seconds),
+            bucket_id: Some(bucket_id.to_owned()),
+            file_name_prefix: prefix.to_owned(),
+            download_url: "https://f001.backblazeb2.com".to_owned()
+        })
+    }).unwrap();
+    (auth.authorization_token, created)
+}
+
+#[test]
+fn broader_prefix_is_reused() {
+    let mut cache = DownloadAuthCache::new(Duration::from_secs(24 * HOUR));
+    let now = Instant::now();
+    assert_eq!(lookup(&mut cache, "b", "photos/", HOUR, now), ("b photos/ 86400".to_owned(), true));
+    assert_eq!(lookup(&mut cache, "b", "photos/2024/", HOUR, now).1, false);
+    assert_eq!(lookup(&mut cache, "b", "photos/", HOUR, now).1, false);
+    assert_eq!(cache.len(), 1);
+}
+#[test]
+fn narrower_or_other_prefix_is_not_reused() {
+    let mut cache = DownloadAuthCache::new(Duration::from_secs(24 * HOUR));
+    let now = Instant::now();
+    assert!(lookup(&mut cache, "b", "photos/2024/", HOUR, now).1);
+    assert!(lookup(&mut cache, "b", "photos/", HOUR, now).1);
+    assert!(lookup(&mut cache, "b", "videos/", HOUR, now).1);
+    assert!(!lookup(&mut cache, "b", "photos/2025/", HOUR, now).1);
+    // prefixes are plain string prefixes, not folders
+    assert!(lookup(&mut cache, "b", "photo", HOUR, now).1);
+    assert!(!lookup(&mut cache, "b", "photography/", HOUR, now).1);
+}
+#[test]
+fn other_bucket_is_not_reused() {
+    let mut cache = DownloadAuthCache::new(Duration::from_secs(24 * HOUR));
+    let now = Instant::now();
+    assert!(lookup(&mut cache, "a", "", HOUR, now).1);
+    assert!(lookup(&mut cache, "b", "photos/", HOUR, now).1);
+    assert_eq!(cache.len(), 2);
+}
+#[test]
+fn remaining_validity() {
+    let mut cache = DownloadAuthCache::new(Duration::from_secs(24 * HOUR));
+    let now = Instant::now();
+    assert!(lookup(&mut cache, "b", "", HOUR, now).1);
+    // 23 hours later, one hour is left
+    let later = now + Duration::from_secs(23 * HOUR);
+    assert!(!lookup(&mut cache, "b", "", HOUR, later).1);
+    assert!(lookup(&mut cache, "b", "", 2 * HOUR, later).1);
+    // a longer validity than the default is requested when needed
+    let (token, _) = lookup(&mut cache, "b", "x", 48 * HOUR, now);
+    assert_eq!(token, "b x 172800");
+}
+#[test]
+fn expired_entries_are_dropped() {
+    let mut cache = DownloadAuthCache::new(Duration::from_secs(HOUR));
+    let now = Instant::now();
+    lookup(&mut cache, "b", "a/", 0, now);
+    lookup(&mut cache, "b", "b/", 0, now);
+    assert_eq!(cache.len(), 2);
+    lookup(&mut cache, "b", "c/", 0, now + Duration::from_secs(2 * HOUR));
+    assert_eq!(cache.len(), 1);
+}
+#[test]
+fn too_long_validity() {
+    let mut cache = DownloadAuthCache::new(Duration::from_secs(HOUR));
+    let err = cache.get_or_create_with("b", "", Duration::from_secs(8 * 24 * HOUR),
+                                       Instant::now(), |_, _, _| -> Result<_, B2Error> {
+        panic!("no authorization should be created")
+    }).unwrap_err();
+    assert_eq!(err.field(), Some("min_remaining_validity"));
+    assert!(cache.is_empty());
+
+    let mut cache = DownloadAuthCache::new(Duration::from_secs(8 * 24 * HOUR));
+    let err = cache.get_or_create_with("b", "", Duration::from_secs(HOUR), Instant::now(),
+                                       |_, _, _| -> Result<_, B2Error> {
+        panic!("no authorization should be created")
+    }).unwrap_err();
+    assert_eq!(err.field(), Some("valid_duration"));
+}
@@ -0,0 +1,94 @@
+extern crate backblaze_b2;
+
+use backblaze_b2::encoding::{content_disposition_filename, percent_decode, percent_encode};
+
+// the printable ascii characters backblaze does not require to be encoded
+const B2_SAFE: &str = concat!("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789",
+                              "._-/~!$'()*;=:@");
+// the attr-chars of RFC 5987
+const ATTR_CHARS: &str = concat!("abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789",
+                                 "!#$&+-.^_`|~");
+
+#[test]
+fn safe_set() {
+    assert_eq!(percent_encode(B2_SAFE), B2_SAFE);
+    for b in 0u8..128 {
+        let s = (b as char).to_string();
+        let expected = if B2_SAFE.contains(&*s) { s.clone() } else { format!("%{:02X}", b) };
+        assert_eq!(percent_encode(&s), expected, "byte {}", b);
+    }
+}
+#[test]
+fn reserved_characters_are_encoded() {
+    assert_eq!(percent_encode("a b+c"), "a%20b%2Bc");
+    assert_eq!(percent_encode("100%"), "100%25");
+    assert_eq!(percent_encode("?x=1&y=#2"), "%3Fx=1%26y=%232");
+    assert_eq!(percent_encode("\"quoted\", \\escaped\\"), "%22quoted%22%2C%20%5Cescaped%5C");
+    assert_eq!(percent_encode("line\nbreak\t"), "line%0Abreak%09");
+}
+#[test]
+fn non_ascii_is_encoded_as_utf8() {
+    assert_eq!(percent_encode("\u{f8}"), "%C3%B8");
+    assert_eq!(percent_encode("\u{65e5}\u{672c}"), "%E6%97%A5%E6%9C%AC");
+    assert_eq!(percent_encode("\u{1f431}.png"), "%F0%9F%90%B1.png");
+    assert_eq!(percent_encode("\u{7f}\u{80}"), "%7F%C2%80");
+}
+#[test]
+fn plus_is_decoded_as_space() {
+    assert_eq!(percent_decode("a+b").unwrap(), "a b");
+    assert_eq!(percent_decode("a%20b").unwrap(), "a b");
+    // an encoded plus is a plus
+    assert_eq!(percent_decode("a%2Bb").unwrap(), "a+b");
+    assert_eq!(percent_decode(&percent_encode("1 + 1")).unwrap(), "1 + 1");
+}
+#[test]
+fn decoding() {
+    assert_eq!(percent_decode("").unwrap(), "");
+    assert_eq!(percent_decode(B2_SAFE).unwrap(), B2_SAFE);
+    // both cases of hex digits are accepted
+    assert_eq!(percent_decode("%C3%B8%c3%b8").unwrap(), "\u{f8}\u{f8}");
+    // characters that should have been encoded are passed through
+    assert_eq!(percent_decode("\u{f8} ,").unwrap(), "\u{f8} ,");
+    assert_eq!(percent_decode("%25%41").unwrap(), "%A");
+}
+#[test]
+fn invalid_escapes() {
+    for s in &["%", "%4", "a%", "%G0", "%0G", "%%41", "%-1"] {
+        assert_eq!(percent_decode(s), None, "{:?}", s);
+    }
+    // the decoded bytes must be utf-8
+    for s in &["%FF", "%C3", "%C3%28", "%ED%A0%80"] {
+        assert_eq!(percent_decode(s), None, "{:?}", s);
+    }
+}
+#[test]
+fn roundtrip() {
+    let all_ascii: String = (0u8..128).map(|b| b as char).collect();
+    for s in &[all_ascii.as_str(), "\u{f8} hello, world", "\u{1f431}/\u{65e5}\u{672c}+%20"] {
+        assert_eq!(percent_decode(&percent_encode(s)).unwrap(), *s);
+    }
+}
+#[test]
+fn content_disposition_attr_chars() {
+    assert_eq!(content_disposition_filename(ATTR_CHARS),
+               format!("filename*=UTF-8''{}", ATTR_CHARS));
+    for b in 0u8..128 {
+        let s = (b as char).to_string();
+        let expected = if ATTR_CHARS.contains(&*s) { s.clone() } else { format!("%{:02X}", b) };
+        assert_eq!(content_disposition_filename(&s), format!("filename*=UTF-8''{}", expected),
+                   "byte {}", b);
+    }
+}
+#[test]
+fn content_disposition_quoting() {
+    // quotes cannot end the parameter, and a single quote cannot end the charset or language
+    assert_eq!(content_disposition_filename("\"cat\".png"), "filename*=UTF-8''%22cat%22.png");
+    assert_eq!(content_disposition_filename("cat's.png"), "filename*=UTF-8''cat%27s.png");
+    // separators of header parameters are encoded
+    assert_eq!(content_disposition_filename("a b;c,d=e"), "filename*=UTF-8''a%20b%3Bc%2Cd%3De");
+    // unlike in file names, slashes and percent signs are encoded
+    assert_eq!(content_disposition_filename("photos/100%.png"),
+               "filename*=UTF-8''photos%2F100%25.png");
+    assert_eq!(content_disposition_filename("r\u{e9}sum\u{e9} \u{1f431}.pdf"),
+               "filename*=UTF-8''r%C3%A9sum%C3%A9%20%F0%9F%90%B1.pdf");
+}
@@ -0,0 +1,114 @@
+extern crate backblaze_b2;
+extern crate hyper;
+#[macro_use]
+extern crate serde_json;
+
+mod mock;
+
+use serde_json::Value;
+
+use backblaze_b2::raw::download::ByteRange;
+use backblaze_b2::raw::encryption::ServerSideEncryption;
+use backblaze_b2::raw::files::{MetadataDirective, MoreFileInfo, UnfinishedLargeFileInfo};
+
+use mock::{auth, MockConnector};
+
+fn sse_c() -> ServerSideEncryption {
+    ServerSideEncryption::SseC {
+        algorithm: "AES256".to_owned(),
+        key: "key".to_owned(),
+        key_md5: "md5".to_owned()
+    }
+}
+fn sse_c_json() -> Value {
+    json!({ "mode": "SSE-C", "algorithm": "AES256", "customerKey": "key", "customerKeyMd5": "md5" })
+}
+fn push_file(mock: &MockConnector) {
+    mock.push_json("200 OK", &json!({
+        "fileId": "copy", "fileName": "copy.bin", "accountId": "account", "bucketId": "bucket",
+        "contentLength": 1, "contentSha1": "none", "contentType": "text/plain",
+        "fileInfo": {}, "action": "upload", "uploadTimestamp": 10
+    }).to_string());
+}
+fn push_part(mock: &MockConnector) {
+    mock.push_json("200 OK", r#"{"fileId":"large","partNumber":1,"contentLength":1,
+                                 "contentSha1":"none"}"#);
+}
+fn last_body(mock: &MockConnector) -> Value {
+    mock.requests().last().unwrap().json()
+}
+
+#[test]
+fn start_large_file_sends_the_encryption() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    for &(ref encryption, ref expected) in &[
+        (ServerSideEncryption::None, Value::Null),
+        (ServerSideEncryption::SseB2, json!({ "mode": "SSE-B2", "algorithm": "AES256" })),
+        (sse_c(), sse_c_json()),
+    ] {
+        mock.push_json("200 OK", r#"{"fileId":"large","fileName":"large.bin","bucketId":"bucket",
+                                     "contentType":"text/plain","fileInfo":{},
+                                     "uploadTimestamp":10}"#);
+        let _: UnfinishedLargeFileInfo = auth.start_large_file_with_encryption(
+            "bucket", "large.bin", None, json!({}), encryption, &client).unwrap();
+        let body = last_body(&mock);
+        assert_eq!(body.get("serverSideEncryption").unwrap_or(&Value::Null), expected);
+    }
+}
+
+#[test]
+fn copy_file_sends_only_the_needed_encryption() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+
+    push_file(&mock);
+    let _: MoreFileInfo = auth.copy_file("source", "copy.bin", None, None,
+                                         MetadataDirective::Copy, &client).unwrap();
+    let body = last_body(&mock);
+    assert!(body.get("sourceServerSideEncryption").is_none());
+    assert!(body.get("destinationServerSideEncryption").is_none());
+
+    // the source is only readable with its key, and the copy is encrypted by backblaze
+    push_file(&mock);
+    let _: MoreFileInfo = auth.copy_file_with_encryption(
+        "source", "copy.bin", None, None, MetadataDirective::Copy, &sse_c(),
+        &ServerSideEncryption::SseB2, &client).unwrap();
+    let body = last_body(&mock);
+    assert_eq!(body["sourceServerSideEncryption"], sse_c_json());
+    assert_eq!(body["destinationServerSideEncryption"],
+               json!({ "mode": "SSE-B2", "algorithm": "AES256" }));
+
+    // a source encrypted by backblaze needs no key
+    push_file(&mock);
+    let _: MoreFileInfo = auth.copy_file_with_encryption(
+        "source", "copy.bin", None, None, MetadataDirective::Copy, &ServerSideEncryption::SseB2,
+        &sse_c(), &client).unwrap();
+    let body = last_body(&mock);
+    assert!(body.get("sourceServerSideEncryption").is_none());
+    assert_eq!(body["destinationServerSideEncryption"], sse_c_json());
+}
+
+#[test]
+fn copy_part_sends_only_customer_keys() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+
+    push_part(&mock);
+    auth.copy_part_with_encryption("source", "large", 1, Some(ByteRange::Inclusive(0, 0)),
+                                   &sse_c(), &sse_c(), &client).unwrap();
+    let body = last_body(&mock);
+    assert_eq!(body["sourceServerSideEncryption"], sse_c_json());
+    assert_eq!(body["destinationServerSideEncryption"], sse_c_json());
+    assert_eq!(body["range"], "bytes=0-0");
+
+    // the parts of a large file encrypted with SSE-B2 need no instructions
+    push_part(&mock);
+    auth.copy_part_with_encryption("source", "large", 1, Some(ByteRange::From(5)),
+                                   &ServerSideEncryption::SseB2, &ServerSideEncryption::SseB2,
+                                   &client).unwrap();
+    let body = last_body(&mock);
+    assert!(body.get("sourceServerSideEncryption").is_none());
+    assert!(body.get("destinationServerSideEncryption").is_none());
+    assert_eq!(body["range"], "bytes=5-");
+}
@@ -0,0 +1,113 @@
+extern crate backblaze_b2;
+extern crate hyper;
+extern crate serde_json;
+
+mod mock;
+
+use std::io::{self, Read};
+
+use hyper::status::StatusCode;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::download::ByteRange;
+use backblaze_b2::raw::files::FileInfo;
+
+use mock::{download_auth, MockConnector};
+
+fn download_error(mock: &MockConnector) -> B2Error {
+    let (auth, client) = (download_auth(), mock.client());
+    let res: Result<(_, Option<FileInfo>), B2Error> =
+        auth.download_byte_range("file", ByteRange::Inclusive(0, 9), &client);
+    res.unwrap_err()
+}
+fn unexpected_body(err: &B2Error) -> &str {
+    match *err {
+        B2Error::UnexpectedResponse { ref body, .. } => body,
+        ref other => panic!("expected an unexpected response, got {:?}", other)
+    }
+}
+
+#[test]
+fn partial_content_is_a_successful_download() {
+    let mock = MockConnector::new();
+    mock.push_response("206 Partial Content", &[
+        ("X-Bz-File-Id", "file"),
+        ("X-Bz-File-Name", "file.bin"),
+        ("Content-Type", "application/octet-stream"),
+        ("X-Bz-Content-Sha1", "none"),
+        ("X-Bz-Upload-Timestamp", "1500000000000"),
+        ("Content-Range", "bytes 0-9/30"),
+    ], b"0123456789");
+
+    let (auth, client) = (download_auth(), mock.client());
+    let (mut resp, info): (_, Option<FileInfo>) =
+        auth.download_byte_range("file", ByteRange::Inclusive(0, 9), &client).unwrap();
+    let mut body = Vec::new();
+    resp.read_to_end(&mut body).unwrap();
+    assert_eq!(body, b"0123456789");
+    let info = info.unwrap();
+    assert_eq!(info.file_name, "file.bin");
+    assert_eq!(info.content_length, 10);
+    assert_eq!(mock.requests()[0].header("Range"), Some("bytes=0-9".to_owned()));
+}
+#[test]
+fn not_found_is_parsed_from_the_json_body() {
+    let mock = MockConnector::new();
+    mock.push_json("404 Not Found",
+                   r#"{"status":404,"code":"not_found","message":"File not present: file"}"#);
+
+    let err = download_error(&mock);
+    assert_eq!(err.http_status(), Some(404));
+    assert!(err.is_file_not_found());
+    match err {
+        B2Error::B2Error(StatusCode::NotFound, _) => {},
+        ref other => panic!("expected an error message, got {:?}", other)
+    }
+}
+#[test]
+fn non_json_body_is_an_unexpected_response() {
+    let mock = MockConnector::new();
+    mock.push_response("500 Internal Server Error", &[("Content-Type", "text/plain")],
+                       b"internal error \xff");
+
+    let err = download_error(&mock);
+    assert_eq!(err.http_status(), Some(500));
+    // the body is kept even if it is not valid utf-8
+    assert_eq!(unexpected_body(&err), "internal error \u{fffd}");
+}
+#[test]
+fn only_the_start_of_an_oversized_error_is_kept() {
+    // the message is cut off when reading stops, so the json cannot be parsed
+    let message: String = "x".repeat(100 * 1024);
+    let json = format!(r#"{{"status":400,"code":"bad_request","message":"{}"}}"#, message);
+    let mock = MockConnector::new();
+    mock.push_json("400 Bad Request", &json);
+
+    let err = download_error(&mock);
+    assert_eq!(err.http_status(), Some(400));
+    let body = unexpected_body(&err);
+    assert_eq!(body.len(), 1024);
+    assert!(json.starts_with(body));
+}
+#[test]
+fn oversized_error_body_is_not_read_to_the_end() {
+    // the connection fails after 100 KiB, but reading stops before that
+    let mock = MockConnector::new();
+    mock.push_broken_response("502 Bad Gateway", &[("Content-Type", "text/html")],
+                              &vec![b'x'; 100 * 1024], 1024, io::ErrorKind::ConnectionReset);
+
+    let err = download_error(&mock);
+    assert_eq!(err.http_status(), Some(502));
+    assert_eq!(unexpected_body(&err).len(), 1024);
+
+    // a body ending before the limit is read to the end, so the failure is reported
+    let mock = MockConnector::new();
+    mock.push_broken_response("502 Bad Gateway", &[("Content-Type", "text/html")],
+                              &vec![b'x'; 1024], 1024, io::ErrorKind::ConnectionReset);
+    match download_error(&mock) {
+        B2Error::IOError(ref err) => assert_eq!(err.kind(), io::ErrorKind::ConnectionReset),
+        B2Error::HyperError(hyper::Error::Io(ref err)) =>
+            assert_eq!(err.kind(), io::ErrorKind::ConnectionReset),
+        other => panic!("expected an io error, got {:?}", other)
+    }
+}
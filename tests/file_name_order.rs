@@ -0,0 +1,107 @@
+extern crate backblaze_b2;
+extern crate serde_json;
+
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use backblaze_b2::raw::files::{file_name_cmp, next_name_after};
+
+/// The names of a file name listing, in the order documented by backblaze: sorted by the bytes of
+/// their UTF-8 encoding.
+fn fixture() -> Vec<String> {
+    let listing: Value = serde_json::from_str(include_str!("wire/list_file_names_order.json"))
+        .unwrap();
+    listing["files"].as_array().unwrap().iter()
+        .map(|file| file["fileName"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+/// A small xorshift generator, so the property tests are reproducible.
+struct Rng(u64);
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+/// Characters around the edges of the orderings that commonly differ from the byte order.
+const CHARS: &[char] = &[' ', '!', '-', '.', '/', '0', 'A', 'Z', '_', 'a', 'z', '~', '\u{80}',
+                         '\u{e9}', '\u{301}', '\u{7ff}', '\u{800}', '\u{d7ff}', '\u{e000}',
+                         '\u{ff21}', '\u{ffff}', '\u{10000}', '\u{1f600}', '\u{10ffff}'];
+fn random_name(rng: &mut Rng) -> String {
+    let len = 1 + rng.below(6);
+    (0..len).map(|_| CHARS[rng.below(CHARS.len())]).collect()
+}
+
+#[test]
+fn fixture_is_sorted() {
+    let names = fixture();
+    for pair in names.windows(2) {
+        assert_eq!(file_name_cmp(&pair[0], &pair[1]), Ordering::Less,
+                   "{:?} should come before {:?}", pair[0], pair[1]);
+    }
+}
+#[test]
+fn sorting_reproduces_the_fixture() {
+    let names = fixture();
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..20 {
+        let mut shuffled = names.clone();
+        for i in (1..shuffled.len()).rev() {
+            let j = rng.below(i + 1);
+            shuffled.swap(i, j);
+        }
+        shuffled.sort_by(|a, b| file_name_cmp(a, b));
+        assert_eq!(shuffled, names);
+    }
+}
+#[test]
+fn fixture_differs_from_utf16_order() {
+    // the fixture would catch a comparator that sorts by UTF-16 code units
+    let names = fixture();
+    let mut utf16 = names.clone();
+    utf16.sort_by(|a, b| a.encode_utf16().cmp(b.encode_utf16()));
+    assert!(utf16 != names);
+}
+#[test]
+fn comparison_matches_the_utf8_bytes() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..10_000 {
+        let (a, b) = (random_name(&mut rng), random_name(&mut rng));
+        assert_eq!(file_name_cmp(&a, &b), a.as_bytes().cmp(b.as_bytes()), "{:?} {:?}", a, b);
+        assert_eq!(file_name_cmp(&a, &b), file_name_cmp(&b, &a).reverse());
+        assert_eq!(file_name_cmp(&a, &a), Ordering::Equal);
+    }
+}
+#[test]
+fn next_name_is_the_minimal_successor() {
+    let mut rng = Rng(0xdead_beef_cafe_f00d);
+    for _ in 0..10_000 {
+        let (name, other) = (random_name(&mut rng), random_name(&mut rng));
+        let next = next_name_after(&name);
+        assert_eq!(file_name_cmp(&name, &next), Ordering::Less);
+        // no valid name lies strictly between a name and its successor
+        if file_name_cmp(&other, &name) == Ordering::Greater {
+            assert!(file_name_cmp(&other, &next) != Ordering::Less,
+                    "{:?} lies between {:?} and {:?}", other, name, next);
+        }
+    }
+}
+#[test]
+fn next_name_in_the_fixture() {
+    let names = fixture();
+    for (i, name) in names.iter().enumerate() {
+        let next = next_name_after(name);
+        // starting a listing at the successor skips exactly the names up to and including name
+        let position = names.iter()
+            .position(|other| file_name_cmp(other, &next) != Ordering::Less)
+            .unwrap_or(names.len());
+        assert_eq!(position, i + 1, "{:?}", name);
+    }
+}
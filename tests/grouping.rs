@@ -0,0 +1,187 @@
+extern crate backblaze_b2;
+extern crate hyper;
+#[macro_use]
+extern crate serde_json;
+
+mod mock;
+
+use std::cell::Cell;
+
+use serde_json::Value;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::files::*;
+
+use mock::{auth, file, hide, version_json, MockConnector};
+
+fn visible(listing: &[FileVersion]) -> Vec<String> {
+    latest_visible_versions(listing.iter().cloned().map(Ok))
+        .map(|file| file.unwrap().file_id)
+        .collect()
+}
+fn groups(listing: &[FileVersion]) -> Vec<(String, Vec<String>)> {
+    group_versions(listing.iter().cloned().map(Ok))
+        .map(|group| {
+            let group = group.unwrap();
+            let ids = group.versions.iter().map(|version| match *version {
+                FileVersion::File(ref file) => file.file_id.clone(),
+                FileVersion::HideMarker(ref hide) => hide.file_id.clone(),
+                _ => panic!("unexpected version {:?}", version)
+            }).collect();
+            (group.file_name, ids)
+        })
+        .collect()
+}
+
+#[test]
+fn hide_newest() {
+    let listing = vec![hide("a", "h", 20), file("a", "1", 10), file("b", "2", 10)];
+    assert_eq!(visible(&listing), vec!["2"]);
+    assert_eq!(groups(&listing), vec![("a".to_owned(), vec!["h".to_owned(), "1".to_owned()]),
+                                      ("b".to_owned(), vec!["2".to_owned()])]);
+}
+#[test]
+fn hide_then_reupload() {
+    let listing = vec![file("a", "2", 30), hide("a", "h", 20), file("a", "1", 10)];
+    assert_eq!(visible(&listing), vec!["2"]);
+}
+#[test]
+fn multiple_hides() {
+    let listing = vec![hide("a", "h2", 30), hide("a", "h1", 20), file("a", "1", 10),
+                       hide("b", "h3", 30), file("b", "2", 20), hide("b", "h4", 10),
+                       file("c", "3", 10)];
+    assert_eq!(visible(&listing), vec!["3"]);
+}
+#[test]
+fn hide_marker_with_the_same_timestamp_hides() {
+    // the order of versions uploaded at the same time is not specified
+    assert_eq!(visible(&[file("a", "1", 10), hide("a", "h", 10)]), Vec::<String>::new());
+    assert_eq!(visible(&[hide("a", "h", 10), file("a", "1", 10)]), Vec::<String>::new());
+}
+#[test]
+fn only_hide_markers() {
+    assert_eq!(visible(&[hide("a", "h", 10), file("b", "1", 10)]), vec!["1"]);
+}
+#[test]
+fn folders_and_unfinished_large_files_are_skipped() {
+    let listing = vec![
+        FileVersion::Folder(FolderInfo { file_name: "a/".to_owned() }),
+        file("b", "1", 10),
+        FileVersion::UnfinishedLargeFile(UnfinishedLargeFileInfo {
+            file_id: "large".to_owned(),
+            file_name: "b".to_owned(),
+            bucket_id: None,
+            content_type: "application/octet-stream".to_owned(),
+            file_info: Value::Null,
+            upload_timestamp: 20
+        }),
+        file("b", "0", 5),
+    ];
+    assert_eq!(groups(&listing), vec![("b".to_owned(), vec!["1".to_owned(), "0".to_owned()])]);
+    assert_eq!(visible(&listing), vec!["1"]);
+}
+#[test]
+fn groups_are_yielded_before_the_next_group_is_read() {
+    let listing = vec![file("a", "1", 20), file("a", "0", 10), file("b", "2", 10),
+                       file("b", "3", 5), file("c", "4", 10)];
+    let consumed = Cell::new(0);
+    let mut groups = group_versions(listing.into_iter().map(|version| {
+        consumed.set(consumed.get() + 1);
+        Ok(version)
+    }));
+    assert_eq!(groups.next().unwrap().unwrap().file_name, "a");
+    // the first version of "b" was read to find the end of "a"
+    assert_eq!(consumed.get(), 3);
+    assert_eq!(groups.next().unwrap().unwrap().versions.len(), 2);
+    assert_eq!(consumed.get(), 5);
+    assert_eq!(groups.next().unwrap().unwrap().file_name, "c");
+    assert!(groups.next().is_none());
+}
+#[test]
+fn errors_discard_the_incomplete_group() {
+    let listing: Vec<Result<FileVersion, B2Error>> = vec![
+        Ok(file("a", "1", 10)), Ok(file("b", "2", 20)),
+        Err(B2Error::ApiInconsistency("broken".to_owned())),
+        Ok(file("b", "3", 10)),
+    ];
+    let mut files = latest_visible_versions(listing);
+    assert_eq!(files.next().unwrap().unwrap().file_id, "1");
+    match files.next() {
+        Some(Err(B2Error::ApiInconsistency(msg))) => assert_eq!(msg, "broken"),
+        res => panic!("unexpected result {:?}", res)
+    }
+    assert!(files.next().is_none());
+}
+#[test]
+fn listing_delegates_to_the_streaming_grouping() {
+    // the listing api returns files and hide markers in separate arrays
+    let versions = vec![file("b", "2", 10), file("a/b", "3", 10), file("a", "1", 10),
+                        file("a", "0", 30), file("\u{e9}", "4", 10), file("a-b", "5", 10),
+                        hide("a", "h1", 20), hide("\u{ff21}", "h2", 20),
+                        hide("b", "h3", 10)];
+    let listing = FileVersionListing {
+        files: versions.iter().filter_map(|version| match *version {
+            FileVersion::File(ref file) => Some(file.clone()),
+            _ => None
+        }).collect(),
+        folders: Vec::new(),
+        hide_markers: versions.iter().filter_map(|version| match *version {
+            FileVersion::HideMarker(ref hide) => Some(hide.clone()),
+            _ => None
+        }).collect(),
+        unfinished_large_files: Vec::new()
+    };
+    // the order of a listing from backblaze, by file name and then newest first
+    let mut sorted = versions.clone();
+    sorted.sort_by(|a, b| file_name_cmp(a.file_name(), b.file_name()).then_with(|| {
+        let time = |version: &FileVersion| match *version {
+            FileVersion::File(ref file) => file.upload_timestamp,
+            FileVersion::HideMarker(ref hide) => hide.upload_timestamp,
+            _ => unreachable!()
+        };
+        time(b).cmp(&time(a))
+    }));
+
+    let grouped: Vec<(String, Vec<String>)> = listing.clone().versions_grouped().into_iter()
+        .map(|group| {
+            let ids = group.versions.iter().map(|version| match *version {
+                FileVersion::File(ref file) => file.file_id.clone(),
+                FileVersion::HideMarker(ref hide) => hide.file_id.clone(),
+                _ => panic!("unexpected version {:?}", version)
+            }).collect();
+            (group.file_name, ids)
+        })
+        .collect();
+    assert_eq!(grouped, groups(&sorted));
+    let names: Vec<&str> = grouped.iter().map(|&(ref name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["a", "a-b", "a/b", "b", "\u{e9}", "\u{ff21}"]);
+
+    let ids: Vec<String> = listing.latest_visible_versions().into_iter()
+        .map(|file| file.file_id)
+        .collect();
+    assert_eq!(ids, visible(&sorted));
+    assert_eq!(ids, vec!["0", "5", "3", "4"]);
+}
+
+#[test]
+fn groups_span_pages_of_the_listing() {
+    let mock = MockConnector::new();
+    let pages = vec![
+        (vec![file("a", "1", 10), hide("b", "h", 30)], json!("b"), json!("2")),
+        (vec![file("b", "2", 20)], json!("c"), json!("3")),
+        (vec![file("c", "3", 10)], Value::Null, Value::Null),
+    ];
+    for (files, next_name, next_id) in pages {
+        let files: Vec<Value> = files.iter().map(version_json).collect();
+        let body = json!({ "files": files, "nextFileName": next_name, "nextFileId": next_id });
+        mock.push_json("200 OK", &body.to_string());
+    }
+    let auth = auth();
+    let client = mock.client();
+    let files: Vec<FileInfo> = auth.iter_file_versions("bucket", 2, None, None, &client)
+        .latest_visible()
+        .collect::<Result<_, _>>().unwrap();
+    let ids: Vec<&str> = files.iter().map(|file| file.file_id.as_str()).collect();
+    assert_eq!(ids, vec!["1", "3"]);
+    assert_eq!(mock.requests().len(), 3);
+}
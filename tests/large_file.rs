@@ -0,0 +1,210 @@
+extern crate backblaze_b2;
+extern crate hyper;
+extern crate serde_json;
+extern crate sha1;
+
+mod mock;
+
+use std::io;
+
+use serde_json::Value;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::files::MoreFileInfo;
+use backblaze_b2::raw::large_file::UploadLargeFile;
+
+use mock::{auth, MockConnector, Request};
+
+fn sha1_of(data: &[u8]) -> String {
+    let mut sha1 = sha1::Sha1::new();
+    sha1.update(data);
+    sha1.digest().to_string()
+}
+fn push_start(mock: &MockConnector) {
+    mock.push_json("200 OK", r#"{"fileId":"large","fileName":"big.bin","bucketId":"bucket",
+                                 "contentType":"b2/x-auto","fileInfo":{},
+                                 "uploadTimestamp":10}"#);
+}
+fn push_upload_url(mock: &MockConnector, n: u32) {
+    mock.push_json("200 OK", &format!(
+        r#"{{"fileId":"large","uploadUrl":"http://upload{}.example/part",
+             "authorizationToken":"part token {}"}}"#, n, n));
+}
+/// The response to a part upload. The response is not compared with the uploaded part, so the
+/// same response can be used for every part.
+fn push_part(mock: &MockConnector) {
+    mock.push_json("200 OK", r#"{"fileId":"large","partNumber":1,"contentLength":10,
+                                 "contentSha1":"none"}"#);
+}
+fn push_finish(mock: &MockConnector, length: usize) {
+    mock.push_json("200 OK", &format!(
+        r#"{{"fileId":"large","fileName":"big.bin","accountId":"account","bucketId":"bucket",
+             "contentLength":{},"contentSha1":"none","contentType":"application/octet-stream",
+             "fileInfo":{{}},"action":"upload","uploadTimestamp":10}}"#, length));
+}
+fn requests_to(mock: &MockConnector, path: &str) -> Vec<Request> {
+    mock.requests().into_iter().filter(|req| req.request_line().contains(path)).collect()
+}
+/// Returns the uploaded parts ordered by part number.
+fn uploaded_parts(mock: &MockConnector) -> Vec<Vec<u8>> {
+    let mut parts: Vec<(u32, Vec<u8>)> = requests_to(mock, "POST /part ").iter()
+        .map(|req| {
+            let number = req.header("X-Bz-Part-Number").unwrap().parse().unwrap();
+            assert_eq!(req.header("X-Bz-Content-Sha1"), Some(sha1_of(req.body())));
+            (number, req.body().to_vec())
+        })
+        .collect();
+    parts.sort();
+    parts.into_iter().map(|(_, part)| part).collect()
+}
+
+#[test]
+fn parts_are_uploaded_in_order_with_the_large_file_sha1() {
+    let data: Vec<u8> = (0..25).collect();
+    let sha1 = sha1_of(&data);
+    let mock = MockConnector::new();
+    push_start(&mock);
+    push_upload_url(&mock, 1);
+    for _ in 0..3 {
+        push_part(&mock);
+    }
+    push_finish(&mock, data.len());
+
+    let (auth, client) = (auth(), mock.client());
+    let file: MoreFileInfo = UploadLargeFile::new("bucket", "big.bin")
+        .part_size(10)
+        .large_file_sha1(&sha1)
+        .send(&auth, &mut &data[..], data.len() as u64, &client, &mock).unwrap();
+    assert_eq!(file.content_length, 25);
+    assert_eq!(mock.replies_left(), 0);
+
+    let start = &requests_to(&mock, "b2_start_large_file")[0];
+    assert_eq!(start.json()["fileInfo"]["large_file_sha1"], sha1.as_str());
+    let numbers: Vec<String> = requests_to(&mock, "POST /part ").iter()
+        .map(|req| req.header("X-Bz-Part-Number").unwrap())
+        .collect();
+    assert_eq!(numbers, vec!["1", "2", "3"]);
+    let parts: Vec<Vec<u8>> = data.chunks(10).map(|part| part.to_vec()).collect();
+    assert_eq!(uploaded_parts(&mock), parts);
+}
+#[test]
+fn parts_are_uploaded_concurrently() {
+    let data: Vec<u8> = (0..45).collect();
+    let mock = MockConnector::new();
+    push_start(&mock);
+    for n in 1..4 {
+        push_upload_url(&mock, n);
+    }
+    for _ in 0..5 {
+        push_part(&mock);
+    }
+    push_finish(&mock, data.len());
+
+    let (auth, client) = (auth(), mock.client());
+    let _: MoreFileInfo = UploadLargeFile::new("bucket", "big.bin")
+        .part_size(10)
+        .concurrency(3)
+        .send(&auth, &mut &data[..], data.len() as u64, &client, &mock).unwrap();
+    assert_eq!(mock.replies_left(), 0);
+
+    // every thread has its own upload url
+    assert_eq!(requests_to(&mock, "b2_get_upload_part_url").len(), 3);
+    let start = &requests_to(&mock, "b2_start_large_file")[0];
+    assert!(start.json()["fileInfo"].get("large_file_sha1").is_none());
+    let parts: Vec<Vec<u8>> = data.chunks(10).map(|part| part.to_vec()).collect();
+    assert_eq!(uploaded_parts(&mock), parts);
+    // the sha1 array is ordered by part number, whichever part finished first
+    let finish = &requests_to(&mock, "b2_finish_large_file")[0];
+    let expected: Vec<String> = data.chunks(10).map(sha1_of).collect();
+    assert_eq!(finish.json()["partSha1Array"], serde_json::to_value(&expected).unwrap());
+}
+#[test]
+fn no_more_threads_than_parts() {
+    let data: Vec<u8> = (0..15).collect();
+    let mock = MockConnector::new();
+    push_start(&mock);
+    push_upload_url(&mock, 1);
+    push_upload_url(&mock, 2);
+    push_part(&mock);
+    push_part(&mock);
+    push_finish(&mock, data.len());
+
+    let (auth, client) = (auth(), mock.client());
+    let _: MoreFileInfo = UploadLargeFile::new("bucket", "big.bin")
+        .part_size(10)
+        .concurrency(8)
+        .send(&auth, &mut &data[..], data.len() as u64, &client, &mock).unwrap();
+    assert_eq!(mock.replies_left(), 0);
+    assert_eq!(requests_to(&mock, "b2_get_upload_part_url").len(), 2);
+}
+#[test]
+fn failed_part_cancels_the_large_file() {
+    let data: Vec<u8> = (0..45).collect();
+    let mock = MockConnector::new();
+    push_start(&mock);
+    push_upload_url(&mock, 1);
+    push_upload_url(&mock, 2);
+    // depending on the timing, the second thread may or may not get a part before the first
+    // thread fails
+    for _ in 0..2 {
+        mock.push_json("400 Bad Request",
+                       r#"{"status":400,"code":"bad_request","message":"bad part"}"#);
+    }
+    mock.push_json("200 OK", r#"{"fileId":"large","accountId":"account","bucketId":"bucket",
+                                 "fileName":"big.bin"}"#);
+
+    let (auth, client) = (auth(), mock.client());
+    let err = UploadLargeFile::new("bucket", "big.bin")
+        .part_size(10)
+        .concurrency(2)
+        .send::<Value, _, _, _>(&auth, &mut &data[..], data.len() as u64, &client, &mock)
+        .unwrap_err();
+    assert_eq!(err.http_status(), Some(400));
+    let parts = requests_to(&mock, "POST /part ").len();
+    assert!(parts == 1 || parts == 2, "{} parts were uploaded", parts);
+    assert_eq!(requests_to(&mock, "b2_cancel_large_file").len(), 1);
+    assert!(requests_to(&mock, "b2_finish_large_file").is_empty());
+}
+#[test]
+fn short_data_cancels_the_large_file() {
+    let data: Vec<u8> = (0..25).collect();
+    let mock = MockConnector::new();
+    push_start(&mock);
+    push_upload_url(&mock, 1);
+    push_upload_url(&mock, 2);
+    push_part(&mock);
+    push_part(&mock);
+    mock.push_json("200 OK", r#"{"fileId":"large","accountId":"account","bucketId":"bucket",
+                                 "fileName":"big.bin"}"#);
+
+    let (auth, client) = (auth(), mock.client());
+    let err = UploadLargeFile::new("bucket", "big.bin")
+        .part_size(10)
+        .concurrency(2)
+        .send::<Value, _, _, _>(&auth, &mut &data[..], 45, &client, &mock)
+        .unwrap_err();
+    match err {
+        B2Error::IOError(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => {},
+        err => panic!("unexpected error {:?}", err)
+    }
+    // the parts that were read are uploaded before the large file is cancelled
+    assert_eq!(requests_to(&mock, "POST /part ").len(), 2);
+    assert_eq!(requests_to(&mock, "b2_cancel_large_file").len(), 1);
+    assert_eq!(mock.replies_left(), 0);
+}
+#[test]
+fn invalid_settings_are_rejected_before_starting() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    let data = vec![0u8; 25];
+    let send = |upload: UploadLargeFile| {
+        upload.send::<Value, _, _, _>(&auth, &mut &data[..], 25, &client, &mock).unwrap_err()
+    };
+    assert_eq!(send(UploadLargeFile::new("bucket", "big.bin").part_size(10).concurrency(0))
+               .field(), Some("concurrency"));
+    assert_eq!(send(UploadLargeFile::new("bucket", "big.bin").part_size(10)
+                    .large_file_sha1("not a sha1")).field(), Some("large_file_sha1"));
+    assert_eq!(send(UploadLargeFile::new("bucket", "big.bin").part_size(4)).field(),
+               Some("part_size"));
+    assert!(mock.requests().is_empty());
+}
@@ -0,0 +1,136 @@
+extern crate backblaze_b2;
+extern crate hyper;
+extern crate serde_json;
+
+mod mock;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::buckets::*;
+use backblaze_b2::raw::files::*;
+
+use mock::{file, hide};
+
+const DAY: u64 = 24 * 60 * 60 * 1000;
+
+fn rule(prefix: &str, hide: Option<u32>, delete: Option<u32>) -> LifecycleRule {
+    LifecycleRule {
+        file_name_prefix: prefix.to_owned(),
+        days_from_uploading_to_hiding: hide,
+        days_from_hiding_to_deleting: delete
+    }
+}
+// the actions of every rule as (file id, kind, day)
+fn preview(versions: &[FileVersion], rules: &[LifecycleRule], until_day: u64)
+    -> Vec<Vec<(String, LifecycleActionKind, u64)>>
+{
+    lifecycle_preview(versions.iter().cloned().map(Ok), rules, until_day * DAY).unwrap()
+        .into_iter()
+        .map(|preview| preview.actions.into_iter()
+             .map(|action| (action.file_id, action.kind, action.at / DAY))
+             .collect())
+        .collect()
+}
+fn action(id: &str, kind: LifecycleActionKind, day: u64) -> (String, LifecycleActionKind, u64) {
+    (id.to_owned(), kind, day)
+}
+
+#[test]
+fn overlapping_prefixes_use_the_longest_match() {
+    let versions = vec![file("a", "1", 0), file("ab", "2", 0), file("abc", "3", 0),
+                        file("abd", "4", 0), file("b", "5", 0)];
+    let rules = vec![rule("abc", Some(3), None), rule("a", Some(1), None),
+                     rule("ab", Some(2), None)];
+    let actions = preview(&versions, &rules, 100);
+    assert_eq!(actions[0], vec![action("3", LifecycleActionKind::Hide, 3)]);
+    assert_eq!(actions[1], vec![action("1", LifecycleActionKind::Hide, 1)]);
+    assert_eq!(actions[2], vec![action("2", LifecycleActionKind::Hide, 2),
+                                action("4", LifecycleActionKind::Hide, 2)]);
+}
+#[test]
+fn empty_prefix_is_a_catch_all() {
+    let versions = vec![file("a", "1", 0), file("logs/x", "2", 0), file("z", "3", 0)];
+    let rules = vec![rule("logs/", None, None), rule("", Some(1), None)];
+    let actions = preview(&versions, &rules, 100);
+    // the more specific rule wins even though it does nothing
+    assert_eq!(actions[0], vec![]);
+    assert_eq!(actions[1], vec![action("1", LifecycleActionKind::Hide, 1),
+                                action("3", LifecycleActionKind::Hide, 1)]);
+
+    // without a catch-all, unmatched files are left alone
+    let rules = vec![rule("logs/", Some(1), None)];
+    assert_eq!(preview(&versions, &rules, 100),
+               vec![vec![action("2", LifecycleActionKind::Hide, 1)]]);
+}
+#[test]
+fn newest_hide_marker_is_deleted() {
+    let versions = vec![hide("a", "h", 10 * DAY), file("a", "2", 5 * DAY), file("a", "1", 0)];
+    let rules = vec![rule("", Some(1), Some(2))];
+    // a hidden file is not hidden again, the hide marker and the older versions are deleted
+    assert_eq!(preview(&versions, &rules, 100),
+               vec![vec![action("1", LifecycleActionKind::Delete, 7),
+                         action("h", LifecycleActionKind::Delete, 12),
+                         action("2", LifecycleActionKind::Delete, 12)]]);
+    // without days_from_hiding_to_deleting nothing happens
+    let rules = vec![rule("", Some(1), None)];
+    assert_eq!(preview(&versions, &rules, 100), vec![vec![]]);
+}
+#[test]
+fn multiple_hide_markers() {
+    let versions = vec![hide("a", "h2", 30 * DAY), file("a", "2", 20 * DAY),
+                        hide("a", "h1", 10 * DAY), file("a", "1", 0)];
+    let rules = vec![rule("", None, Some(1))];
+    assert_eq!(preview(&versions, &rules, 100),
+               vec![vec![action("1", LifecycleActionKind::Delete, 11),
+                         action("h1", LifecycleActionKind::Delete, 21),
+                         action("h2", LifecycleActionKind::Delete, 31),
+                         action("2", LifecycleActionKind::Delete, 31)]]);
+}
+#[test]
+fn predicted_hides_are_followed_by_deletes() {
+    let versions = vec![file("a", "1", 0)];
+    let rules = vec![rule("", Some(5), Some(3))];
+    assert_eq!(preview(&versions, &rules, 100),
+               vec![vec![action("1", LifecycleActionKind::Hide, 5),
+                         action("1", LifecycleActionKind::Delete, 8)]]);
+    // actions at or after the horizon are left out
+    assert_eq!(preview(&versions, &rules, 8),
+               vec![vec![action("1", LifecycleActionKind::Hide, 5)]]);
+    assert_eq!(preview(&versions, &rules, 5), vec![vec![]]);
+}
+#[test]
+fn groups_do_not_leak_into_each_other() {
+    // the first version of "b" is its newest, even though "a" was uploaded later
+    let versions = vec![file("a", "a1", 50 * DAY), file("b", "b2", 10 * DAY), file("b", "b1", 0),
+                        file("c", "c1", 0)];
+    let rules = vec![rule("", None, Some(1))];
+    assert_eq!(preview(&versions, &rules, 100),
+               vec![vec![action("b1", LifecycleActionKind::Delete, 11)]]);
+}
+#[test]
+fn folders_and_unfinished_large_files_are_ignored() {
+    let versions = vec![
+        FileVersion::Folder(FolderInfo { file_name: "a/".to_owned() }),
+        FileVersion::UnfinishedLargeFile(UnfinishedLargeFileInfo {
+            file_id: "large".to_owned(),
+            file_name: "b".to_owned(),
+            bucket_id: None,
+            content_type: "application/octet-stream".to_owned(),
+            file_info: serde_json::Value::Null,
+            upload_timestamp: 0
+        }),
+        file("b", "1", 0)
+    ];
+    let rules = vec![rule("", Some(1), None)];
+    assert_eq!(preview(&versions, &rules, 100),
+               vec![vec![action("1", LifecycleActionKind::Hide, 1)]]);
+}
+#[test]
+fn errors_are_returned() {
+    let rules = vec![rule("", Some(1), None)];
+    let versions: Vec<Result<FileVersion, B2Error>> =
+        vec![Ok(file("a", "1", 0)), Err(B2Error::ApiInconsistency("broken".to_owned()))];
+    match lifecycle_preview(versions, &rules, 100 * DAY) {
+        Err(B2Error::ApiInconsistency(msg)) => assert_eq!(msg, "broken"),
+        res => panic!("unexpected result {:?}", res.map(|_| ()))
+    }
+}
@@ -0,0 +1,258 @@
+//! A connector serving scripted responses, such that api calls can be tested without a network.
+//!
+//! Every connection made through the connector takes the next scripted reply, and the bytes
+//! written to the connection are recorded, so the tests can inspect the requests afterwards.
+//! The responses close the connection, so hyper never reuses a connection for two requests.
+//!
+//! The module also has the fixtures shared by the tests, such as the authorizations pointing at
+//! the hosts served by the connector and the file versions returned by the listings.
+
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::io::{self, Cursor, Read, Write};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use hyper;
+use hyper::Client;
+use hyper::net::{NetworkConnector, NetworkStream};
+use serde_json::{self, Value};
+
+use backblaze_b2::raw::authorize::B2Authorization;
+use backblaze_b2::raw::download::DownloadAuthorization;
+use backblaze_b2::raw::files::{FileInfo, FileVersion, HideMarkerInfo};
+
+/// What happens when a connection is made.
+pub enum Reply {
+    /// The connection succeeds and `data` is sent as the response. If `error` is given, reading
+    /// fails with an io error of that kind after the data has been read.
+    Response { data: Vec<u8>, error: Option<io::ErrorKind> },
+    /// The connection fails with the given io error.
+    ConnectError(io::Error)
+}
+
+/// A request recorded by the connector.
+#[derive(Debug,Clone)]
+pub struct Request {
+    pub host: String,
+    pub port: u16,
+    pub data: Vec<u8>
+}
+impl Request {
+    /// Returns the request line, e.g. `POST /b2api/v1/b2_list_file_names HTTP/1.1`.
+    pub fn request_line(&self) -> String {
+        let text = String::from_utf8_lossy(&self.data).into_owned();
+        text.lines().next().unwrap_or("").to_owned()
+    }
+    /// Returns the value of the header, if the request contains it.
+    pub fn header(&self, name: &str) -> Option<String> {
+        let text = String::from_utf8_lossy(self.head()).into_owned();
+        for line in text.split("\r\n").skip(1) {
+            let mut parts = line.splitn(2, ':');
+            let key = parts.next().unwrap_or("");
+            if key.eq_ignore_ascii_case(name) {
+                return parts.next().map(|value| value.trim().to_owned());
+            }
+        }
+        None
+    }
+    /// Returns the body of the request.
+    pub fn body(&self) -> &[u8] {
+        let head = self.head().len();
+        if head + 4 <= self.data.len() { &self.data[head + 4..] } else { &[] }
+    }
+    /// Parses the body of the request as json.
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(self.body()).unwrap()
+    }
+    fn head(&self) -> &[u8] {
+        match self.data.windows(4).position(|w| w == b"\r\n\r\n") {
+            Some(end) => &self.data[..end],
+            None => &self.data
+        }
+    }
+}
+
+struct State {
+    replies: VecDeque<Reply>,
+    requests: Vec<Request>
+}
+
+/// The connector. Clones share the scripted replies and the recorded requests.
+#[derive(Clone)]
+pub struct MockConnector {
+    state: Arc<Mutex<State>>
+}
+impl MockConnector {
+    pub fn new() -> MockConnector {
+        MockConnector {
+            state: Arc::new(Mutex::new(State {
+                replies: VecDeque::new(),
+                requests: Vec::new()
+            }))
+        }
+    }
+    /// Returns a client making its connections through a clone of this connector.
+    pub fn client(&self) -> Client {
+        Client::with_connector(self.clone())
+    }
+    pub fn push(&self, reply: Reply) {
+        self.state.lock().unwrap().replies.push_back(reply);
+    }
+    /// Scripts a response with the given status, headers and body.
+    pub fn push_response(&self, status: &str, headers: &[(&str, &str)], body: &[u8]) {
+        self.push(Reply::Response { data: response(status, headers, body), error: None });
+    }
+    /// Scripts a json response with the given status.
+    pub fn push_json(&self, status: &str, body: &str) {
+        self.push_response(status, &[("Content-Type", "application/json")], body.as_bytes());
+    }
+    /// Scripts a response that sends the headers announcing `body.len() + missing` bytes, but
+    /// fails with `error` after sending `body`.
+    pub fn push_broken_response(&self, status: &str, headers: &[(&str, &str)], body: &[u8],
+                                missing: usize, error: io::ErrorKind) {
+        let mut data = head(status, headers, body.len() + missing);
+        data.extend_from_slice(body);
+        self.push(Reply::Response { data: data, error: Some(error) });
+    }
+    /// Scripts a connection failure.
+    pub fn push_connect_error(&self, kind: io::ErrorKind, message: &str) {
+        self.push(Reply::ConnectError(io::Error::new(kind, message.to_owned())));
+    }
+    /// Returns the requests made so far, including the failed connections.
+    pub fn requests(&self) -> Vec<Request> {
+        self.state.lock().unwrap().requests.clone()
+    }
+    /// Returns the amount of scripted replies that have not been used.
+    pub fn replies_left(&self) -> usize {
+        self.state.lock().unwrap().replies.len()
+    }
+}
+impl NetworkConnector for MockConnector {
+    type Stream = MockStream;
+    fn connect(&self, host: &str, port: u16, _scheme: &str) -> hyper::Result<MockStream> {
+        let mut state = self.state.lock().unwrap();
+        let index = state.requests.len();
+        state.requests.push(Request { host: host.to_owned(), port: port, data: Vec::new() });
+        match state.replies.pop_front() {
+            Some(Reply::Response { data, error }) => Ok(MockStream {
+                state: self.state.clone(),
+                index: index,
+                read: Cursor::new(data),
+                error: error
+            }),
+            Some(Reply::ConnectError(err)) => Err(hyper::Error::Io(err)),
+            None => panic!("no reply scripted for connection {} to {}", index + 1, host)
+        }
+    }
+}
+
+/// A connection made by the [`MockConnector`].
+pub struct MockStream {
+    state: Arc<Mutex<State>>,
+    index: usize,
+    read: Cursor<Vec<u8>>,
+    error: Option<io::ErrorKind>
+}
+impl Read for MockStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.read.read(buf)?;
+        if n == 0 && !buf.is_empty() {
+            if let Some(kind) = self.error.take() {
+                return Err(io::Error::new(kind, "mock connection failure"));
+            }
+        }
+        Ok(n)
+    }
+}
+impl Write for MockStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.state.lock().unwrap().requests[self.index].data.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+impl NetworkStream for MockStream {
+    fn peer_addr(&mut self) -> io::Result<SocketAddr> {
+        Ok("127.0.0.1:443".parse().unwrap())
+    }
+    fn set_read_timeout(&self, _dur: Option<Duration>) -> io::Result<()> {
+        Ok(())
+    }
+    fn set_write_timeout(&self, _dur: Option<Duration>) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn head(status: &str, headers: &[(&str, &str)], content_length: usize) -> Vec<u8> {
+    let mut head = format!("HTTP/1.1 {}\r\nConnection: close\r\nContent-Length: {}\r\n",
+                           status, content_length);
+    for &(name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+    head.into_bytes()
+}
+/// Returns the bytes of a response with the given status, headers and body.
+pub fn response(status: &str, headers: &[(&str, &str)], body: &[u8]) -> Vec<u8> {
+    let mut data = head(status, headers, body.len());
+    data.extend_from_slice(body);
+    data
+}
+
+/// An authorization for the api at `http://api.example`.
+pub fn auth() -> B2Authorization {
+    B2Authorization {
+        account_id: "account".to_owned(),
+        authorization_token: "token".to_owned(),
+        api_url: "http://api.example".to_owned(),
+        download_url: "http://download.example".to_owned(),
+        recommended_part_size: 100,
+        absolute_minimum_part_size: 5
+    }
+}
+/// A download authorization for every file at `http://download.example`.
+pub fn download_auth() -> DownloadAuthorization {
+    DownloadAuthorization {
+        authorization_token: "token".to_owned(),
+        bucket_id: None,
+        file_name_prefix: String::new(),
+        download_url: "http://download.example".to_owned()
+    }
+}
+/// An uploaded file containing one byte.
+pub fn file(name: &str, id: &str, timestamp: u64) -> FileVersion {
+    FileVersion::File(FileInfo {
+        file_id: id.to_owned(),
+        file_name: name.to_owned(),
+        content_length: 1,
+        content_type: "text/plain".to_owned(),
+        content_sha1: "da39a3ee5e6b4b0d3255bfef95601890afd80709".to_owned(),
+        file_info: Value::Object(serde_json::Map::new()),
+        upload_timestamp: timestamp
+    })
+}
+/// A hide marker.
+pub fn hide(name: &str, id: &str, timestamp: u64) -> FileVersion {
+    FileVersion::HideMarker(HideMarkerInfo {
+        file_id: id.to_owned(),
+        file_name: name.to_owned(),
+        upload_timestamp: timestamp
+    })
+}
+/// Returns the version as it appears in the responses from backblaze.
+pub fn version_json(version: &FileVersion) -> Value {
+    serde_json::to_value(version).unwrap()
+}
+/// The json of [`file`].
+pub fn file_json(name: &str, id: &str, timestamp: u64) -> Value {
+    version_json(&file(name, id, timestamp))
+}
+/// The json of [`hide`].
+pub fn hide_json(name: &str, id: &str, timestamp: u64) -> Value {
+    version_json(&hide(name, id, timestamp))
+}
@@ -0,0 +1,158 @@
+extern crate backblaze_b2;
+extern crate hyper;
+#[macro_use]
+extern crate serde_json;
+
+mod mock;
+
+use serde_json::Value;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::files::{DEFAULT_MAX_PAGES, FileInfo, FileVersion, UnfinishedLargeFileInfo};
+use backblaze_b2::raw::large_file::UploadedPart;
+
+use mock::{auth, file_json, MockConnector};
+
+fn part(number: u32) -> Value {
+    json!({
+        "fileId": "large", "partNumber": number, "contentLength": 5,
+        "contentSha1": "da39a3ee5e6b4b0d3255bfef95601890afd80709", "uploadTimestamp": 10
+    })
+}
+fn unfinished(name: &str) -> Value {
+    json!({
+        "fileId": format!("id of {}", name), "fileName": name, "contentType": "text/plain",
+        "fileInfo": {}, "uploadTimestamp": 10
+    })
+}
+fn push(mock: &MockConnector, body: Value) {
+    mock.push_json("200 OK", &body.to_string());
+}
+/// Collects the items until the first error, which must be `PaginationNotAdvancing`.
+fn until_error<T, I>(iter: I) -> (Vec<T>, B2Error)
+    where I: Iterator<Item=Result<T, B2Error>>
+{
+    let mut items = Vec::new();
+    for item in iter {
+        match item {
+            Ok(item) => items.push(item),
+            Err(err) => {
+                assert!(err.is_pagination_not_advancing(), "unexpected error {:?}", err);
+                return (items, err);
+            }
+        }
+    }
+    panic!("the iterator ended without an error");
+}
+
+#[test]
+fn file_names_repeating_cursor() {
+    let mock = MockConnector::new();
+    push(&mock, json!({
+        "files": [file_json("a", "1", 10), file_json("b", "2", 10)], "nextFileName": "c"
+    }));
+    push(&mock, json!({ "files": [file_json("c", "3", 10)], "nextFileName": "c" }));
+
+    let (files, err) = until_error(
+        auth().iter_file_names::<Value>("bucket", 2, None, &mock.client()));
+    let names: Vec<String> = files.into_iter().map(|file: FileInfo| file.file_name).collect();
+    assert_eq!(names, vec!["a", "b", "c"]);
+    match err {
+        B2Error::PaginationNotAdvancing { ref cursor, pages } => {
+            assert_eq!(cursor, "c");
+            assert_eq!(pages, 2);
+        },
+        ref err => panic!("unexpected error {:?}", err)
+    }
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn file_names_cursor_going_backwards() {
+    let mock = MockConnector::new();
+    push(&mock, json!({ "files": [file_json("b", "2", 10)], "nextFileName": "c" }));
+    // "C" is before "c" in byte order
+    push(&mock, json!({ "files": [file_json("c", "3", 10)], "nextFileName": "C" }));
+
+    let (files, _) = until_error(
+        auth().iter_file_names::<Value>("bucket", 1, None, &mock.client()));
+    assert_eq!(files.len(), 2);
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn file_names_page_limit() {
+    let mock = MockConnector::new();
+    push(&mock, json!({ "files": [file_json("a", "1", 10)], "nextFileName": "b" }));
+    push(&mock, json!({ "files": [file_json("b", "2", 10)], "nextFileName": "c" }));
+
+    let (auth, client) = (auth(), mock.client());
+    let (files, _) = until_error(
+        auth.iter_file_names::<Value>("bucket", 1, None, &client).max_pages(2));
+    assert_eq!(files.len(), 2);
+    assert_eq!(mock.requests().len(), 2);
+    assert_eq!(DEFAULT_MAX_PAGES, 100_000);
+}
+
+#[test]
+fn file_versions_repeating_cursor() {
+    let mock = MockConnector::new();
+    push(&mock, json!({
+        "files": [file_json("a", "1", 10)], "nextFileName": "b", "nextFileId": "2"
+    }));
+    push(&mock, json!({
+        "files": [file_json("b", "2", 10)], "nextFileName": "b", "nextFileId": "2"
+    }));
+
+    let (versions, _) = until_error(
+        auth().iter_file_versions::<Value>("bucket", 1, None, None, &mock.client()));
+    let names: Vec<String> = versions.iter()
+        .map(|version: &FileVersion| version.file_name().to_owned()).collect();
+    assert_eq!(names, vec!["a", "b"]);
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn file_versions_of_the_same_name_advance() {
+    let mock = MockConnector::new();
+    push(&mock, json!({
+        "files": [file_json("a", "1", 10)], "nextFileName": "a", "nextFileId": "2"
+    }));
+    push(&mock, json!({
+        "files": [file_json("a", "2", 10)], "nextFileName": "a", "nextFileId": "3"
+    }));
+    push(&mock, json!({
+        "files": [file_json("a", "3", 10)], "nextFileName": null, "nextFileId": null
+    }));
+
+    let versions: Vec<FileVersion> =
+        auth().iter_file_versions("bucket", 1, None, None, &mock.client())
+        .collect::<Result<_, _>>().unwrap();
+    assert_eq!(versions.len(), 3);
+}
+
+#[test]
+fn parts_repeating_cursor() {
+    let mock = MockConnector::new();
+    push(&mock, json!({ "parts": [part(1), part(2)], "nextPartNumber": 3 }));
+    push(&mock, json!({ "parts": [part(3)], "nextPartNumber": 3 }));
+
+    let (parts, _) = until_error(auth().iter_parts("large", 2, &mock.client()));
+    let numbers: Vec<u32> = parts.iter().map(|part: &UploadedPart| part.part_number).collect();
+    assert_eq!(numbers, vec![1, 2, 3]);
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn unfinished_large_files_repeating_cursor() {
+    let mock = MockConnector::new();
+    push(&mock, json!({ "files": [unfinished("a")], "nextFileId": "id of b" }));
+    push(&mock, json!({ "files": [unfinished("b")], "nextFileId": "id of b" }));
+
+    let (files, _) = until_error(
+        auth().iter_unfinished_large_files::<Value>("bucket", None, 1, &mock.client()));
+    let names: Vec<String> = files.into_iter()
+        .map(|file: UnfinishedLargeFileInfo| file.file_name).collect();
+    assert_eq!(names, vec!["a", "b"]);
+    assert_eq!(mock.requests().len(), 2);
+}
@@ -0,0 +1,92 @@
+extern crate backblaze_b2;
+
+use std::cell::RefCell;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::publish::*;
+
+fn file(name: &str, data: &str) -> PublishFile {
+    PublishFile {
+        name: name.to_owned(),
+        content_type: None,
+        data: data.as_bytes().to_vec()
+    }
+}
+fn files() -> Vec<PublishFile> {
+    vec![file("a.css", "a"), file("b.js", "b"), file("c.png", "c")]
+}
+fn failure() -> B2Error {
+    B2Error::ApiInconsistency("upload failed".to_owned())
+}
+
+#[test]
+fn publishes_manifest_last() {
+    let uploads = RefCell::new(Vec::new());
+    let report = publish_with(files(), "index.html", |files| {
+        assert_eq!(files.len(), 3);
+        b"manifest".to_vec()
+    }, Naming::ContentAddressed, |name, _, sha1| {
+        uploads.borrow_mut().push(name.to_owned());
+        Ok((format!("id of {}", name), sha1.to_owned()))
+    }, |_| panic!("nothing should be deleted"));
+    assert!(report.is_published());
+    assert!(!report.rolled_back);
+    let uploads = uploads.into_inner();
+    assert_eq!(uploads.len(), 4);
+    assert_eq!(uploads[0], "86f7e437faa5a7fce15d1ddcb9eaeaea377667b8/a.css");
+    assert_eq!(uploads[3], "index.html");
+}
+#[test]
+fn failure_before_manifest_rolls_back() {
+    let deleted = RefCell::new(Vec::new());
+    let report = publish_with(files(), "index.html", |_| panic!("manifest must not be created"),
+                              Naming::Final, |name, _, sha1| {
+        if name == "c.png" {
+            Err(failure())
+        } else {
+            Ok((format!("id of {}", name), sha1.to_owned()))
+        }
+    }, |file| {
+        deleted.borrow_mut().push(file.file_id.clone());
+        if file.name == "b.js" { Err(failure()) } else { Ok(()) }
+    });
+    assert!(!report.is_published());
+    assert!(report.rolled_back);
+    assert!(report.error.is_some());
+    assert_eq!(deleted.into_inner(), vec!["id of a.css".to_owned(), "id of b.js".to_owned()]);
+    assert_eq!(report.rollback_failures.len(), 1);
+    assert_eq!(report.rollback_failures[0].0.name, "b.js");
+}
+#[test]
+fn sha1_mismatch_rolls_back() {
+    let deleted = RefCell::new(Vec::new());
+    let report = publish_with(files(), "index.html", |_| Vec::new(), Naming::Final,
+                              |name, _, sha1| {
+        let sha1 = if name == "b.js" { "0000".to_owned() } else { sha1.to_owned() };
+        Ok((format!("id of {}", name), sha1))
+    }, |file| {
+        deleted.borrow_mut().push(file.name.clone());
+        Ok(())
+    });
+    assert!(report.rolled_back);
+    assert!(report.rollback_failures.is_empty());
+    assert_eq!(deleted.into_inner(), vec!["a.css".to_owned(), "b.js".to_owned()]);
+}
+#[test]
+fn manifest_failure_rolls_back() {
+    let deleted = RefCell::new(Vec::new());
+    let report = publish_with(files(), "index.html", |_| Vec::new(), Naming::Final,
+                              |name, _, sha1| {
+        if name == "index.html" {
+            Err(failure())
+        } else {
+            Ok((format!("id of {}", name), sha1.to_owned()))
+        }
+    }, |file| {
+        deleted.borrow_mut().push(file.name.clone());
+        Ok(())
+    });
+    assert!(!report.is_published());
+    assert!(report.rolled_back);
+    assert_eq!(deleted.into_inner().len(), 3);
+}
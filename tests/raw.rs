@@ -18,7 +18,10 @@ use rand::Rng;
 
 use backblaze_b2::raw::authorize::*;
 use backblaze_b2::raw::buckets::*;
+use backblaze_b2::raw::download::ByteRange;
 use backblaze_b2::raw::files::*;
+use backblaze_b2::raw::large_file::UploadLargeFile;
+use backblaze_b2::transfer::*;
 
 use serde_json::value::Value;
 
@@ -47,7 +50,7 @@ fn list_all_files() {
     let cred: B2Credentials = serde_json::from_reader(cred_file).unwrap();
     let auth: B2Authorization = cred.authorize(&client).unwrap();
     let new_bucket_name = format!("rust-b2-test-{}", rand_string(16));
-    let bucket = auth.create_bucket_no_info(&new_bucket_name, BucketType::Private,
+    let bucket = auth.create_bucket_no_info(&new_bucket_name, CreatableBucketType::Private,
                                             Vec::new(), &client).unwrap();
     let mut files = Vec::new();
     let upload_auth = auth.get_upload_url(&bucket.bucket_id, &client).unwrap();
@@ -89,6 +92,17 @@ fn list_all_files() {
         };
         files.push(file);
     }
+    let names: Vec<String> = auth.iter_file_names::<Value>(&bucket.bucket_id, 7, None, &client)
+        .map(|file| file.unwrap().file_name)
+        .collect();
+    let mut sorted: Vec<String> = (0..30).map(|i| format!("{}", i)).collect();
+    sorted.sort_by(|a, b| file_name_cmp(a, b));
+    assert_eq!(names, sorted);
+    let versions: Vec<String> = auth.iter_file_versions::<Value>(&bucket.bucket_id, 7, None, None,
+                                                                &client)
+        .map(|version| version.unwrap().file_name().to_owned())
+        .collect();
+    assert_eq!(versions, sorted);
     let listing = auth.list_all_file_names::<Value>(&bucket.bucket_id, 12, None, None, &client).unwrap();
     assert_eq!(listing.folders.len(), 0);
     for file in listing.files {
@@ -99,6 +113,115 @@ fn list_all_files() {
     auth.delete_bucket(&bucket, &client).unwrap();
 }
 #[test]
+fn upload_large_file() {
+    let client = make_client();
+    let connector = make_connector();
+    let cred_file = match File::open("credentials.txt") {
+        Ok(f) => f,
+        Err(_) =>
+            panic!("The test requires the credentials for b2 to be placed in the file \'credentials.txt\' which contains a json object with the properties \"id\" and \"key\".")
+    };
+    let cred: B2Credentials = serde_json::from_reader(cred_file).unwrap();
+    let auth: B2Authorization = cred.authorize(&client).unwrap();
+    let new_bucket_name = format!("rust-b2-test-{}", rand_string(16));
+    let bucket = auth.create_bucket_no_info(&new_bucket_name, CreatableBucketType::Private,
+                                            Vec::new(), &client).unwrap();
+
+    let part_size = auth.absolute_minimum_part_size;
+    let mut file_data = vec![0u8; 2 * part_size + 10];
+    rand::thread_rng().fill_bytes(&mut file_data);
+    let mut m = sha1::Sha1::new();
+    m.update(&file_data);
+    let sha1 = m.digest().to_string();
+
+    let file: MoreFileInfo = auth.upload_large_file(
+        &bucket.bucket_id, "large_file", None, &mut &file_data[..], file_data.len() as u64,
+        Some(part_size as u64), &client, &connector).unwrap();
+    assert_eq!(file.content_length, file_data.len() as u64);
+    assert_eq!(file.content_sha1, "none");
+
+    let download_auth = auth.to_download_authorization();
+    let (mut resp, _): (_, Option<FileInfo>) =
+        download_auth.download_file_by_id(&file.file_id, &client).unwrap();
+    let mut downloaded = Vec::new();
+    resp.read_to_end(&mut downloaded).unwrap();
+    let mut m = sha1::Sha1::new();
+    m.update(&downloaded);
+    assert_eq!(m.digest().to_string(), sha1);
+
+    // reassemble the file on the server from two ranges of the uploaded file
+    let large: UnfinishedLargeFileInfo = auth.start_large_file(
+        &bucket.bucket_id, "copied_large_file", None, json!({}), &client).unwrap();
+    let split = part_size as u64 + 5;
+    let first = auth.copy_part(&file.file_id, &large.file_id, 1,
+                               Some(ByteRange::Inclusive(0, split - 1)), &client).unwrap();
+    let second = auth.copy_part(&file.file_id, &large.file_id, 2,
+                                Some(ByteRange::From(split)), &client).unwrap();
+    let parts: Vec<u32> = auth.iter_parts(&large.file_id, 1, &client)
+        .map(|part| part.unwrap().part_number)
+        .collect();
+    assert_eq!(parts, vec![1, 2]);
+    assert_eq!(first.content_length + second.content_length, file_data.len() as u64);
+    let copied: MoreFileInfo = auth.finish_large_file(
+        &large.file_id, &[first.content_sha1, second.content_sha1], &client).unwrap();
+    assert_eq!(copied.content_length, file_data.len() as u64);
+
+    auth.delete_file_version(&copied.file_name, &copied.file_id, &client).unwrap();
+    auth.delete_file_version(&file.file_name, &file.file_id, &client).unwrap();
+    auth.delete_bucket(&bucket, &client).unwrap();
+}
+#[test]
+fn copy_large_file_between_accounts() {
+    let client = make_client();
+    let connector = make_connector();
+    let cred_file = match File::open("credentials.txt") {
+        Ok(f) => f,
+        Err(_) =>
+            panic!("The test requires the credentials for b2 to be placed in the file \'credentials.txt\' which contains a json object with the properties \"id\" and \"key\".")
+    };
+    let cred: B2Credentials = serde_json::from_reader(cred_file).unwrap();
+    let auth: B2Authorization = cred.authorize(&client).unwrap();
+    let new_bucket_name = format!("rust-b2-test-{}", rand_string(16));
+    let bucket = auth.create_bucket_no_info(&new_bucket_name, CreatableBucketType::Private,
+                                            Vec::new(), &client).unwrap();
+
+    let part_size = auth.absolute_minimum_part_size;
+    let mut file_data = vec![0u8; 2 * part_size + 10];
+    rand::thread_rng().fill_bytes(&mut file_data);
+    let mut m = sha1::Sha1::new();
+    m.update(&file_data);
+    let sha1 = m.digest().to_string();
+    let file: MoreFileInfo = UploadLargeFile::new(&bucket.bucket_id, "large_file")
+        .part_size(part_size as u64)
+        .concurrency(2)
+        .large_file_sha1(&sha1)
+        .send(&auth, &mut &file_data[..], file_data.len() as u64, &client, &connector).unwrap();
+    assert_eq!(file.file_info["large_file_sha1"], sha1.as_str());
+
+    // the same account is used as both source and destination, since the test has a single
+    // set of credentials
+    let options = TransferOptions {
+        part_size: Some(part_size as u64),
+        file_info_keys: Vec::new()
+    };
+    let report: TransferReport = copy_between_accounts(
+        &auth.to_download_authorization(), (new_bucket_name.as_str(), "large_file"), &auth,
+        &bucket.bucket_id, "copied_large_file", &options, &client, &connector).unwrap();
+    assert_eq!(report.bytes_transferred, file_data.len() as u64);
+    assert_eq!(report.file.content_length, file_data.len() as u64);
+    assert_eq!(report.file.file_info["large_file_sha1"], sha1.as_str());
+
+    let (mut resp, _): (_, Option<FileInfo>) = auth.to_download_authorization()
+        .download_file_by_id(&report.file.file_id, &client).unwrap();
+    let mut downloaded = Vec::new();
+    resp.read_to_end(&mut downloaded).unwrap();
+    assert_eq!(downloaded, file_data);
+
+    auth.delete_file_version(&report.file.file_name, &report.file.file_id, &client).unwrap();
+    auth.delete_file_version(&file.file_name, &file.file_id, &client).unwrap();
+    auth.delete_bucket(&bucket, &client).unwrap();
+}
+#[test]
 #[allow(unused_variables)]
 fn main_test() {
     let client = make_client();
@@ -116,7 +239,7 @@ fn main_test() {
 
     {
         let bucket_info = json!({"abc": "test", "json": "data"});
-        let bucket = auth.create_bucket(&new_bucket_name, BucketType::Private,
+        let bucket = auth.create_bucket(&new_bucket_name, CreatableBucketType::Private,
                                         bucket_info.clone(), Vec::new(), &client).unwrap();
         assert_eq!(bucket.bucket_name, new_bucket_name);
         assert_eq!(bucket.bucket_type, BucketType::Private);
@@ -124,7 +247,7 @@ fn main_test() {
         assert_eq!(bucket.account_id, auth.account_id);
         auth.delete_bucket_id::<Value>(&bucket.bucket_id, &client).unwrap();
     }
-    let bucket = auth.create_bucket_no_info(&new_bucket_name, BucketType::Private,
+    let bucket = auth.create_bucket_no_info(&new_bucket_name, CreatableBucketType::Private,
                                             Vec::new(), &client).unwrap();
     assert_eq!(bucket.bucket_name, new_bucket_name);
     assert_eq!(bucket.bucket_type, BucketType::Private);
@@ -247,6 +370,21 @@ fn main_test() {
         assert_eq!(file2.content_length, 3);
         assert_eq!(file2.content_type, "image/png");
     }
+    {
+        let copy: MoreFileInfo = auth.copy_file(&file.file_id, "copy.png", None,
+                                                Some(ByteRange::Inclusive(1, 3)),
+                                                MetadataDirective::Copy, &client).unwrap();
+        assert_eq!(copy.content_length, 3);
+        assert_eq!(copy.content_type, "image/png");
+        let copy: MoreFileInfo = auth.copy_file(&file.file_id, "copy.bin", None, None,
+            MetadataDirective::Replace {
+                content_type: "application/octet-stream".parse().unwrap(),
+                file_info: json!({"copied": "yes"})
+            }, &client).unwrap();
+        assert_eq!(copy.content_sha1, sha1);
+        assert_eq!(copy.content_type, "application/octet-stream");
+        assert_eq!(copy.file_info["copied"], "yes");
+    }
 
     auth.hide_file(&file.file_name, &bucket.bucket_id, &client).unwrap();
     if let Ok((fvl, None, None)) =
@@ -0,0 +1,212 @@
+extern crate backblaze_b2;
+extern crate hyper;
+extern crate serde_json;
+
+mod mock;
+
+use std::io::{self, Read};
+use std::time::Duration;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::download::ByteRange;
+use backblaze_b2::raw::files::FileInfo;
+use backblaze_b2::retry::RetryPolicy;
+
+use mock::{download_auth, MockConnector, Reply, Request};
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 1,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(2),
+        jitter: false
+    }
+}
+fn data() -> Vec<u8> {
+    (0..30).collect()
+}
+fn headers<'a>(file_id: &'a str, range: &'a str) -> Vec<(&'a str, &'a str)> {
+    let mut headers = vec![
+        ("X-Bz-File-Id", file_id),
+        ("X-Bz-File-Name", "file.bin"),
+        ("Content-Type", "application/octet-stream"),
+        ("X-Bz-Content-Sha1", "none"),
+        ("X-Bz-Upload-Timestamp", "1500000000000"),
+    ];
+    if !range.is_empty() {
+        headers.push(("Content-Range", range));
+    }
+    headers
+}
+/// Scripts the response to a range request for `data()[start..end]`.
+fn push_range(mock: &MockConnector, file_id: &str, start: usize, end: usize) {
+    let range = format!("bytes {}-{}/30", start, end - 1);
+    mock.push_response("206 Partial Content", &headers(file_id, &range), &data()[start..end]);
+}
+fn ranges(mock: &MockConnector) -> Vec<Option<String>> {
+    mock.requests().iter().map(|req: &Request| req.header("Range")).collect()
+}
+fn read_all<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    reader.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+#[test]
+fn resumes_after_the_connection_drops_mid_body() {
+    let mock = MockConnector::new();
+    mock.push_broken_response("200 OK", &headers("file", ""), &data()[..12], 18,
+                              io::ErrorKind::ConnectionReset);
+    push_range(&mock, "file", 12, 30);
+
+    let (auth, client) = (download_auth(), mock.client());
+    let (reader, _): (_, Option<FileInfo>) =
+        auth.download_resumable("file", None, 3, &client).unwrap();
+    let mut reader = reader.retry_policy(policy());
+    assert_eq!(read_all(&mut reader).unwrap(), data());
+    assert_eq!(reader.position(), 30);
+    assert_eq!(reader.resumes_left(), 2);
+    assert_eq!(ranges(&mock), vec![None, Some("bytes=12-29".to_owned())]);
+}
+
+#[test]
+fn resumes_when_the_body_ends_early() {
+    let mock = MockConnector::new();
+    // the response announces 30 bytes, but the connection is closed after 10
+    let mut response = mock::response("200 OK", &headers("file", ""), &data());
+    let len = response.len();
+    response.truncate(len - 20);
+    mock.push(Reply::Response { data: response, error: None });
+    push_range(&mock, "file", 10, 30);
+
+    let (auth, client) = (download_auth(), mock.client());
+    let (reader, _): (_, Option<FileInfo>) =
+        auth.download_resumable("file", None, 1, &client).unwrap();
+    let mut reader = reader.retry_policy(policy());
+    assert_eq!(read_all(&mut reader).unwrap(), data());
+    assert_eq!(ranges(&mock), vec![None, Some("bytes=10-29".to_owned())]);
+}
+
+#[test]
+fn resumes_within_the_requested_range() {
+    let mock = MockConnector::new();
+    mock.push_broken_response("206 Partial Content", &headers("file", "bytes 10-19/30"),
+                              &data()[10..13], 7, io::ErrorKind::ConnectionAborted);
+    push_range(&mock, "file", 13, 20);
+
+    let (auth, client) = (download_auth(), mock.client());
+    let (reader, _): (_, Option<FileInfo>) =
+        auth.download_resumable("file", Some(ByteRange::Inclusive(10, 19)), 1, &client).unwrap();
+    let mut reader = reader.retry_policy(policy());
+    assert_eq!(read_all(&mut reader).unwrap(), &data()[10..20]);
+    assert_eq!(reader.position(), 20);
+    assert_eq!(ranges(&mock), vec![Some("bytes=10-19".to_owned()),
+                                   Some("bytes=13-19".to_owned())]);
+}
+
+#[test]
+fn suffix_ranges_resume_from_the_reported_offset() {
+    let mock = MockConnector::new();
+    // the start of a suffix range is only known from the Content-Range header
+    mock.push_broken_response("206 Partial Content", &headers("file", "bytes 20-29/30"),
+                              &data()[20..24], 6, io::ErrorKind::ConnectionReset);
+    push_range(&mock, "file", 24, 30);
+
+    let (auth, client) = (download_auth(), mock.client());
+    let (reader, _): (_, Option<FileInfo>) =
+        auth.download_resumable("file", Some(ByteRange::Suffix(10)), 1, &client).unwrap();
+    let mut reader = reader.retry_policy(policy());
+    assert_eq!(read_all(&mut reader).unwrap(), &data()[20..30]);
+    assert_eq!(reader.position(), 30);
+    assert_eq!(ranges(&mock), vec![Some("bytes=-10".to_owned()),
+                                   Some("bytes=24-29".to_owned())]);
+}
+
+#[test]
+fn range_out_of_bounds_returns_the_original_error() {
+    let mock = MockConnector::new();
+    mock.push_broken_response("200 OK", &headers("file", ""), &data()[..12], 18,
+                              io::ErrorKind::ConnectionReset);
+    mock.push_json("416 Range Not Satisfiable", r#"{"status":416,"code":"range_not_satisfiable",
+                                                    "message":"The range is not satisfiable"}"#);
+
+    let (auth, client) = (download_auth(), mock.client());
+    let (reader, _): (_, Option<FileInfo>) =
+        auth.download_resumable("file", None, 3, &client).unwrap();
+    let mut reader = reader.retry_policy(policy());
+    let err = read_all(&mut reader).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+    assert_eq!(mock.requests().len(), 2);
+}
+
+#[test]
+fn backs_off_and_retries_the_resumed_request() {
+    let mock = MockConnector::new();
+    mock.push_broken_response("200 OK", &headers("file", ""), &data()[..12], 18,
+                              io::ErrorKind::ConnectionReset);
+    mock.push_response("503 Service Unavailable", &[("Retry-After", "0")], b"busy");
+    mock.push_connect_error(io::ErrorKind::ConnectionReset, "reset");
+    push_range(&mock, "file", 12, 30);
+
+    let (auth, client) = (download_auth(), mock.client());
+    let (reader, _): (_, Option<FileInfo>) =
+        auth.download_resumable("file", None, 3, &client).unwrap();
+    let mut reader = reader.retry_policy(policy());
+    assert_eq!(read_all(&mut reader).unwrap(), data());
+    assert_eq!(reader.resumes_left(), 0);
+    assert_eq!(mock.replies_left(), 0);
+}
+
+#[test]
+fn errors_that_are_not_transient_are_not_resumed() {
+    let mock = MockConnector::new();
+    mock.push_broken_response("200 OK", &headers("file", ""), &data()[..12], 18,
+                              io::ErrorKind::PermissionDenied);
+
+    let (auth, client) = (download_auth(), mock.client());
+    let (reader, _): (_, Option<FileInfo>) =
+        auth.download_resumable("file", None, 3, &client).unwrap();
+    let mut reader = reader.retry_policy(policy());
+    let err = read_all(&mut reader).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    assert_eq!(reader.resumes_left(), 3);
+
+    // a failed resumed request that should not be retried is returned right away
+    mock.push_broken_response("200 OK", &headers("file", ""), &data()[..12], 18,
+                              io::ErrorKind::ConnectionReset);
+    mock.push_json("403 Forbidden", r#"{"status":403,"code":"access_denied","message":"no"}"#);
+    let (reader, _): (_, Option<FileInfo>) =
+        auth.download_resumable("file", None, 3, &client).unwrap();
+    let mut reader = reader.retry_policy(policy());
+    assert!(read_all(&mut reader).is_err());
+    assert_eq!(reader.resumes_left(), 2);
+}
+
+#[test]
+fn replaced_file_is_detected() {
+    let mock = MockConnector::new();
+    mock.push_broken_response("200 OK", &headers("file", ""), &data()[..12], 18,
+                              io::ErrorKind::ConnectionReset);
+    push_range(&mock, "replacement", 12, 30);
+
+    let (auth, client) = (download_auth(), mock.client());
+    let (reader, _): (_, Option<FileInfo>) =
+        auth.download_resumable(("bucket", "file.bin"), None, 3, &client).unwrap();
+    let mut reader = reader.retry_policy(policy());
+    let err = read_all(&mut reader).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn missing_file_id_is_an_error() {
+    let mock = MockConnector::new();
+    mock.push_response("200 OK", &[("Content-Type", "application/octet-stream")], &data());
+
+    let (auth, client) = (download_auth(), mock.client());
+    let result = auth.download_resumable::<_, serde_json::Value>("file", None, 3, &client);
+    match result {
+        Err(B2Error::ApiInconsistency(_)) => {},
+        Err(err) => panic!("unexpected error {:?}", err),
+        Ok(_) => panic!("the download started without a file id")
+    }
+}
@@ -0,0 +1,222 @@
+extern crate backblaze_b2;
+extern crate hyper;
+extern crate serde_json;
+
+mod mock;
+
+use std::cell::Cell;
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+use std::time::Duration;
+
+use hyper::status::StatusCode;
+
+use backblaze_b2::{B2Error, B2ErrorMessage};
+use backblaze_b2::raw::authorize::B2Authorization;
+use backblaze_b2::retry::*;
+
+use mock::MockConnector;
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(2),
+        jitter: false
+    }
+}
+
+#[test]
+fn transient_errors_are_retried() {
+    let attempts = Cell::new(0);
+    let result = retry(&policy(), || {
+        attempts.set(attempts.get() + 1);
+        if attempts.get() < 3 {
+            Err(B2Error::from(io::Error::new(io::ErrorKind::ConnectionReset, "reset")))
+        } else {
+            Ok(attempts.get())
+        }
+    });
+    assert_eq!(result.unwrap(), 3);
+}
+#[test]
+fn gives_up_after_max_attempts() {
+    let attempts = Cell::new(0);
+    let result: Result<(), B2Error> = retry(&policy(), || {
+        attempts.set(attempts.get() + 1);
+        Err(B2Error::from(io::Error::new(io::ErrorKind::TimedOut, "timeout")))
+    });
+    assert!(result.unwrap_err().is_transient_io_error());
+    assert_eq!(attempts.get(), 3);
+}
+#[test]
+fn other_errors_are_not_retried() {
+    let attempts = Cell::new(0);
+    let result: Result<(), B2Error> = retry(&policy(), || {
+        attempts.set(attempts.get() + 1);
+        Err(B2Error::InvalidRequest { field: "file_name", reason: "empty".to_owned() })
+    });
+    assert!(result.is_err());
+    assert_eq!(attempts.get(), 1);
+}
+/// Reads part of the body, like an upload that fails halfway.
+fn read_some<R: Read>(body: &mut R) -> Vec<u8> {
+    let mut buf = [0; 4];
+    let n = body.read(&mut buf).unwrap();
+    buf[..n].to_vec()
+}
+#[test]
+fn uploads_are_retried_from_the_start_of_the_body() {
+    let mut body = Cursor::new(b"xxhello".to_vec());
+    body.set_position(2);
+    let attempts = Cell::new(0);
+    let result = retry_upload(&policy(), &mut body, |body| {
+        attempts.set(attempts.get() + 1);
+        let data = read_some(body);
+        if attempts.get() < 3 {
+            Err(B2Error::from(io::Error::new(io::ErrorKind::ConnectionReset, "reset")))
+        } else {
+            Ok(data)
+        }
+    });
+    assert_eq!(result.unwrap(), b"hell");
+    assert_eq!(attempts.get(), 3);
+}
+/// A body that can't be rewound, such as a pipe.
+struct Pipe(Cursor<Vec<u8>>);
+impl Read for Pipe {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+impl Seek for Pipe {
+    fn seek(&mut self, _pos: SeekFrom) -> io::Result<u64> {
+        Err(io::Error::new(io::ErrorKind::Other, "illegal seek"))
+    }
+}
+#[test]
+fn uploads_that_cannot_be_rewound_are_not_retried() {
+    let mut body = Pipe(Cursor::new(b"hello".to_vec()));
+    let attempts = Cell::new(0);
+    let result: Result<(), B2Error> = retry_upload(&policy(), &mut body, |body| {
+        attempts.set(attempts.get() + 1);
+        read_some(body);
+        Err(B2Error::from(io::Error::new(io::ErrorKind::ConnectionReset, "reset")))
+    });
+    let err = result.unwrap_err();
+    assert!(err.is_upload_not_replayable());
+    match err {
+        B2Error::UploadNotReplayable(ref err) => assert!(err.is_transient_io_error()),
+        ref other => panic!("unexpected error {:?}", other)
+    }
+    assert_eq!(attempts.get(), 1);
+
+    // errors that would not be retried anyway are returned as they are
+    let result: Result<(), B2Error> = retry_upload(&policy(), &mut body, |_| {
+        Err(B2Error::InvalidRequest { field: "file_name", reason: "empty".to_owned() })
+    });
+    assert_eq!(result.unwrap_err().field(), Some("file_name"));
+}
+#[test]
+fn jitter_is_below_delay() {
+    let policy = RetryPolicy { jitter: true, ..policy() };
+    for attempt in 1..10 {
+        assert!(policy.delay(attempt) <= Duration::from_millis(2));
+    }
+}
+
+fn b2_error(status: u16, code: &str) -> B2Error {
+    let message: B2ErrorMessage = serde_json::from_str(&format!(
+            r#"{{"status": {}, "code": "{}", "message": ""}}"#, status, code)).unwrap();
+    B2Error::B2Error(StatusCode::from_u16(status), message)
+}
+#[test]
+fn upload_recovery_table() {
+    use backblaze_b2::retry::RecoveryAction::*;
+    // (status, code, action for GetUploadUrl, action for Upload)
+    let table = [
+        (400, "bad_request", Fail, Fail),
+        (401, "unauthorized", Fail, Fail),
+        (401, "bad_auth_token", Reauthorize, NewUploadUrl),
+        (401, "expired_auth_token", Reauthorize, NewUploadUrl),
+        (402, "payment_required", Fail, Fail),
+        (403, "cap_exceeded", Fail, Fail),
+        (403, "access_denied", Fail, Fail),
+        (404, "not_found", Fail, Fail),
+        (408, "request_timeout", BackOff, BackOffAndNewUploadUrl),
+        (429, "too_many_requests", BackOff, BackOff),
+        (500, "internal_error", BackOff, NewUploadUrl),
+        (503, "service_unavailable", BackOff, BackOffAndNewUploadUrl),
+    ];
+    for &(status, code, get_upload_url, upload) in &table {
+        let err = b2_error(status, code);
+        assert_eq!(upload_recovery_action(&err, UploadContext::GetUploadUrl), get_upload_url,
+                   "{} {} while getting an upload url", status, code);
+        assert_eq!(upload_recovery_action(&err, UploadContext::Upload), upload,
+                   "{} {} while uploading", status, code);
+    }
+}
+#[test]
+fn upload_recovery_io_errors() {
+    let kinds = [
+        (io::ErrorKind::ConnectionReset, RecoveryAction::NewUploadUrl),
+        (io::ErrorKind::ConnectionRefused, RecoveryAction::NewUploadUrl),
+        (io::ErrorKind::BrokenPipe, RecoveryAction::NewUploadUrl),
+        (io::ErrorKind::TimedOut, RecoveryAction::NewUploadUrl),
+        (io::ErrorKind::NotFound, RecoveryAction::Fail),
+    ];
+    for &(kind, upload) in &kinds {
+        let err = B2Error::from(io::Error::new(kind, "io error"));
+        assert_eq!(upload_recovery_action(&err, UploadContext::Upload), upload);
+        let get_upload_url = if upload == RecoveryAction::Fail {
+            RecoveryAction::Fail
+        } else {
+            RecoveryAction::BackOff
+        };
+        assert_eq!(upload_recovery_action(&err, UploadContext::GetUploadUrl), get_upload_url);
+    }
+    let err = B2Error::InvalidRequest { field: "file_name", reason: "empty".to_owned() };
+    assert_eq!(upload_recovery_action(&err, UploadContext::Upload), RecoveryAction::Fail);
+}
+#[test]
+fn upload_recovery_unexpected_responses() {
+    use backblaze_b2::retry::RecoveryAction::*;
+    let html = &b"<html><body>Bad Gateway</body></html>"[..];
+    // (status, action for GetUploadUrl, action for Upload)
+    let table = [
+        (StatusCode::Unauthorized, Fail, Fail),
+        (StatusCode::NotFound, Fail, Fail),
+        (StatusCode::RequestTimeout, BackOff, BackOffAndNewUploadUrl),
+        (StatusCode::TooManyRequests, BackOff, BackOff),
+        (StatusCode::BadGateway, BackOff, NewUploadUrl),
+        (StatusCode::ServiceUnavailable, BackOff, BackOffAndNewUploadUrl),
+    ];
+    for &(status, get_upload_url, upload) in &table {
+        let err = B2Error::from_error_body(status, html);
+        assert_eq!(upload_recovery_action(&err, UploadContext::GetUploadUrl), get_upload_url,
+                   "{} while getting an upload url", status);
+        assert_eq!(upload_recovery_action(&err, UploadContext::Upload), upload,
+                   "{} while uploading", status);
+    }
+}
+#[test]
+fn unexpected_response_keeps_retry_after() {
+    let mock = MockConnector::new();
+    mock.push_response("503 Service Unavailable",
+                       &[("Content-Type", "text/html"), ("Retry-After", "7")],
+                       b"<html><body>Service Unavailable</body></html>");
+    let auth = B2Authorization {
+        account_id: "account".to_owned(),
+        authorization_token: "token".to_owned(),
+        api_url: "http://api.example".to_owned(),
+        download_url: "http://download.example".to_owned(),
+        recommended_part_size: 100,
+        absolute_minimum_part_size: 5
+    };
+    let err = auth.get_upload_url("bucket", &mock.client()).unwrap_err();
+    match err {
+        B2Error::UnexpectedResponse { retry_after, .. } => assert_eq!(retry_after, Some(7)),
+        ref other => panic!("expected an unexpected response, got {:?}", other)
+    }
+    assert_eq!(err.retry_after(), Some(Duration::from_secs(7)));
+    assert!(policy().should_retry(&err));
+}
@@ -0,0 +1,48 @@
+extern crate backblaze_b2;
+extern crate hyper;
+
+use std::io::{ErrorKind, Read};
+
+use hyper::header::Headers;
+
+use backblaze_b2::raw::download::{DownloadedFileInfo, VerifyingReader};
+use backblaze_b2::raw::files::{effective_sha1, Sha1Availability};
+
+// the sha1 of "abc"
+const SHA1: &str = "a9993e364706816aba3e25717850c26c9cd0d89d";
+
+#[test]
+fn small_file() {
+    assert_eq!(effective_sha1(SHA1, None), Sha1Availability::Exact(SHA1.to_owned()));
+    assert_eq!(effective_sha1(&SHA1.to_uppercase(), None),
+               Sha1Availability::Exact(SHA1.to_owned()));
+    assert_eq!(effective_sha1(&format!("unverified:{}", SHA1), None),
+               Sha1Availability::Exact(SHA1.to_owned()));
+}
+#[test]
+fn large_file() {
+    assert_eq!(effective_sha1("none", Some(SHA1)),
+               Sha1Availability::LargeFileDeclared(SHA1.to_owned()));
+    assert_eq!(effective_sha1("none", None), Sha1Availability::Unavailable);
+    assert_eq!(effective_sha1("none", None).sha1(), None);
+}
+#[test]
+fn malformed() {
+    assert_eq!(effective_sha1(&SHA1[1..], None), Sha1Availability::Unavailable);
+    assert_eq!(effective_sha1("none", Some(&SHA1[1..])), Sha1Availability::Unavailable);
+    assert_eq!(effective_sha1("", None), Sha1Availability::Unavailable);
+}
+#[test]
+fn download_uses_declared_sha1() {
+    let mut headers = Headers::new();
+    headers.set_raw("X-Bz-Content-Sha1", vec![b"none".to_vec()]);
+    headers.set_raw("X-Bz-Info-large_file_sha1", vec![SHA1.as_bytes().to_vec()]);
+    let sha1 = DownloadedFileInfo::from_headers(&headers).unwrap().effective_sha1();
+    assert_eq!(sha1, Sha1Availability::LargeFileDeclared(SHA1.to_owned()));
+
+    let mut buf = Vec::new();
+    let mut reader = VerifyingReader::new(&b"abc"[..], sha1.sha1().unwrap());
+    reader.read_to_end(&mut buf).unwrap();
+    let mut reader = VerifyingReader::new(&b"abd"[..], sha1.sha1().unwrap());
+    assert_eq!(reader.read_to_end(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+}
@@ -0,0 +1,46 @@
+extern crate backblaze_b2;
+extern crate hyper;
+#[macro_use]
+extern crate serde_json;
+
+mod mock;
+
+use backblaze_b2::raw::buckets::{BucketType, CreatableBucketType};
+
+use mock::{auth, MockConnector};
+
+fn push_deleted(mock: &MockConnector) {
+    mock.push_json("200 OK", &json!({ "fileId": "id", "fileName": "cat.png" }).to_string());
+}
+
+#[test]
+fn delete_allow_snapshot_sends_the_header() {
+    let mock = MockConnector::new();
+    push_deleted(&mock);
+    let (auth, client) = (auth(), mock.client());
+    auth.delete_file_version_allow_snapshot("cat.png", "id", &client).unwrap();
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].request_line().contains("/b2api/v1/b2_delete_file_version"));
+    assert_eq!(requests[0].header("X-Bz-Allow-Snapshot"), Some("true".to_owned()));
+    assert_eq!(requests[0].json(), json!({ "fileName": "cat.png", "fileId": "id" }));
+}
+#[test]
+fn delete_does_not_send_the_header() {
+    let mock = MockConnector::new();
+    push_deleted(&mock);
+    let (auth, client) = (auth(), mock.client());
+    auth.delete_file_version("cat.png", "id", &client).unwrap();
+
+    assert_eq!(mock.requests()[0].header("X-Bz-Allow-Snapshot"), None);
+}
+#[test]
+fn snapshot_buckets_are_not_creatable() {
+    assert_eq!(CreatableBucketType::from_bucket_type(BucketType::Snapshot), None);
+    for &bucket_type in &[CreatableBucketType::Public, CreatableBucketType::Private] {
+        let converted = BucketType::from(bucket_type);
+        assert_eq!(converted.as_str(), bucket_type.as_str());
+        assert_eq!(CreatableBucketType::from_bucket_type(converted), Some(bucket_type));
+    }
+}
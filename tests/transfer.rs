@@ -0,0 +1,293 @@
+extern crate backblaze_b2;
+extern crate hyper;
+extern crate serde_json;
+extern crate sha1;
+
+mod mock;
+
+use std::io;
+use std::time::{Duration, Instant};
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::authorize::B2Authorization;
+use backblaze_b2::raw::download::DownloadAuthorization;
+use backblaze_b2::transfer::{copy_between_accounts, TransferOptions, TransferReport};
+
+use mock::{MockConnector, Request};
+
+const PART_SIZE: u64 = 10;
+
+fn src() -> DownloadAuthorization {
+    DownloadAuthorization {
+        authorization_token: "src token".to_owned(),
+        bucket_id: None,
+        file_name_prefix: String::new(),
+        download_url: "http://src.example".to_owned()
+    }
+}
+fn dst() -> B2Authorization {
+    B2Authorization {
+        account_id: "dst".to_owned(),
+        authorization_token: "dst token".to_owned(),
+        api_url: "http://dst.example".to_owned(),
+        download_url: "http://dst-download.example".to_owned(),
+        recommended_part_size: 100,
+        absolute_minimum_part_size: 5
+    }
+}
+fn options() -> TransferOptions {
+    TransferOptions {
+        part_size: Some(PART_SIZE),
+        file_info_keys: vec!["author".to_owned(), "missing".to_owned()]
+    }
+}
+fn sha1_of(data: &[u8]) -> String {
+    let mut sha1 = sha1::Sha1::new();
+    sha1.update(data);
+    sha1.digest().to_string()
+}
+
+/// Scripts the response to a range request for `data[start..end]`.
+fn push_range(mock: &MockConnector, file_id: &str, data: &[u8], start: usize, end: usize,
+              content_sha1: &str, large_file_sha1: Option<&str>) {
+    let range = format!("bytes {}-{}/{}", start, end - 1, data.len());
+    let mut headers = vec![
+        ("X-Bz-File-Id", file_id),
+        ("X-Bz-File-Name", "source.bin"),
+        ("Content-Type", "application/octet-stream"),
+        ("X-Bz-Content-Sha1", content_sha1),
+        ("X-Bz-Upload-Timestamp", "1500000000000"),
+        ("Content-Range", &range),
+        ("X-Bz-Info-author", "J%C3%B8rgen"),
+        ("X-Bz-Info-other", "not copied"),
+    ];
+    if let Some(sha1) = large_file_sha1 {
+        headers.push(("X-Bz-Info-large_file_sha1", sha1));
+    }
+    mock.push_response("206 Partial Content", &headers, &data[start..end]);
+}
+fn file_json(file_id: &str, length: usize) -> String {
+    format!(r#"{{"fileId":"{}","fileName":"copy.bin","accountId":"dst","contentSha1":"none",
+                "bucketId":"bucket","contentLength":{},"contentType":"application/octet-stream",
+                "fileInfo":{{}},"action":"upload","uploadTimestamp":1500000000001}}"#,
+            file_id, length)
+}
+fn push_start_large_file(mock: &MockConnector) {
+    mock.push_json("200 OK", r#"{"fileId":"large","fileName":"copy.bin","bucketId":"bucket",
+                                 "contentType":"application/octet-stream","fileInfo":{},
+                                 "uploadTimestamp":1500000000001}"#);
+    mock.push_json("200 OK", r#"{"fileId":"large","uploadUrl":"http://upload.example/part",
+                                 "authorizationToken":"part token"}"#);
+}
+fn push_part(mock: &MockConnector, number: u32, part: &[u8]) {
+    mock.push_json("200 OK", &format!(
+        r#"{{"fileId":"large","partNumber":{},"contentLength":{},"contentSha1":"{}"}}"#,
+        number, part.len(), sha1_of(part)));
+}
+fn requests_to(mock: &MockConnector, path: &str) -> Vec<Request> {
+    mock.requests().into_iter().filter(|req| req.request_line().contains(path)).collect()
+}
+
+#[test]
+fn large_copy_buffers_at_most_one_part() {
+    let data: Vec<u8> = (0..25).collect();
+    let sha1 = sha1_of(&data);
+    let mock = MockConnector::new();
+    push_range(&mock, "source", &data, 0, 10, "none", Some(&sha1));
+    push_start_large_file(&mock);
+    push_part(&mock, 1, &data[0..10]);
+    push_range(&mock, "source", &data, 10, 20, "none", Some(&sha1));
+    push_part(&mock, 2, &data[10..20]);
+    push_range(&mock, "source", &data, 20, 25, "none", Some(&sha1));
+    push_part(&mock, 3, &data[20..25]);
+    mock.push_json("200 OK", &file_json("copy", 25));
+
+    let report: TransferReport = copy_between_accounts(
+        &src(), ("source-bucket", "source.bin"), &dst(), "bucket", "copy.bin", &options(),
+        &mock.client(), &mock).unwrap();
+    assert_eq!(report.bytes_transferred, 25);
+    assert_eq!(report.file.file_id, "copy");
+    assert_eq!(mock.replies_left(), 0);
+
+    let ranges: Vec<_> = requests_to(&mock, "/file/source-bucket/source.bin").iter()
+        .map(|req| req.header("Range").unwrap()).collect();
+    assert_eq!(ranges, vec!["bytes=0-9", "bytes=10-19", "bytes=20-24"]);
+
+    let parts = requests_to(&mock, "/part");
+    assert_eq!(parts.len(), 3);
+    let mut uploaded = Vec::new();
+    for (i, part) in parts.iter().enumerate() {
+        let length: u64 = part.header("Content-Length").unwrap().parse().unwrap();
+        assert!(length <= PART_SIZE);
+        assert_eq!(part.header("X-Bz-Part-Number"), Some(format!("{}", i + 1)));
+        assert_eq!(part.header("X-Bz-Content-Sha1"), Some(sha1_of(part.body())));
+        uploaded.extend_from_slice(part.body());
+    }
+    assert_eq!(uploaded, data);
+
+    let start = &requests_to(&mock, "b2_start_large_file")[0];
+    let body = start.json();
+    assert_eq!(body["contentType"], "application/octet-stream");
+    assert_eq!(body["fileInfo"]["author"], "Jørgen");
+    assert_eq!(body["fileInfo"]["large_file_sha1"], sha1.as_str());
+    assert!(body["fileInfo"].get("other").is_none());
+
+    let finish = &requests_to(&mock, "b2_finish_large_file")[0];
+    let expected: Vec<String> = data.chunks(10).map(sha1_of).collect();
+    assert_eq!(finish.json()["partSha1Array"], serde_json::to_value(&expected).unwrap());
+}
+
+#[test]
+fn small_copy_uses_a_single_upload() {
+    let data = b"hello b2".to_vec();
+    let sha1 = sha1_of(&data);
+    let mock = MockConnector::new();
+    push_range(&mock, "source", &data, 0, data.len(), &format!("unverified:{}", sha1), None);
+    mock.push_json("200 OK", r#"{"bucketId":"bucket","uploadUrl":"http://upload.example/file",
+                                 "authorizationToken":"upload token"}"#);
+    mock.push_json("200 OK", &file_json("copy", data.len()));
+
+    let report: TransferReport = copy_between_accounts(
+        &src(), "source", &dst(), "bucket", "copy.bin", &options(), &mock.client(),
+        &mock).unwrap();
+    assert_eq!(report.bytes_transferred, data.len() as u64);
+    assert_eq!(mock.replies_left(), 0);
+
+    let download = &requests_to(&mock, "b2_download_file_by_id")[0];
+    assert_eq!(download.header("Range"), Some("bytes=0-9".to_owned()));
+    let upload = &requests_to(&mock, "POST /file ")[0];
+    assert_eq!(upload.body(), &data[..]);
+    // the unverified: prefix is not a valid value for the upload header
+    assert_eq!(upload.header("X-Bz-Content-Sha1"), Some(sha1));
+    assert_eq!(upload.header("Content-Type"), Some("application/octet-stream".to_owned()));
+    assert_eq!(upload.header("X-Bz-Info-author"), Some("J%C3%B8rgen".to_owned()));
+    assert_eq!(upload.header("X-Bz-Info-other"), None);
+}
+
+#[test]
+fn sha1_mismatch_cancels_the_large_file() {
+    let data: Vec<u8> = (0..15).collect();
+    let wrong = sha1_of(b"something else");
+    let mock = MockConnector::new();
+    push_range(&mock, "source", &data, 0, 10, "none", Some(&wrong));
+    push_start_large_file(&mock);
+    push_part(&mock, 1, &data[0..10]);
+    push_range(&mock, "source", &data, 10, 15, "none", Some(&wrong));
+    push_part(&mock, 2, &data[10..15]);
+    mock.push_json("200 OK", r#"{"fileId":"large","accountId":"dst","bucketId":"bucket",
+                                 "fileName":"copy.bin"}"#);
+
+    let err = copy_between_accounts::<_, serde_json::Value, _, _>(
+        &src(), "source", &dst(), "bucket", "copy.bin", &options(), &mock.client(),
+        &mock).unwrap_err();
+    match err {
+        B2Error::IOError(ref err) if err.kind() == io::ErrorKind::InvalidData => {},
+        err => panic!("unexpected error {:?}", err)
+    }
+    assert_eq!(requests_to(&mock, "b2_cancel_large_file").len(), 1);
+    assert!(requests_to(&mock, "b2_finish_large_file").is_empty());
+}
+
+#[test]
+fn replaced_source_cancels_the_large_file() {
+    let data: Vec<u8> = (0..15).collect();
+    let sha1 = sha1_of(&data);
+    let mock = MockConnector::new();
+    push_range(&mock, "source", &data, 0, 10, "none", Some(&sha1));
+    push_start_large_file(&mock);
+    push_part(&mock, 1, &data[0..10]);
+    push_range(&mock, "replacement", &data, 10, 15, "none", Some(&sha1));
+    mock.push_json("200 OK", r#"{"fileId":"large","accountId":"dst","bucketId":"bucket",
+                                 "fileName":"copy.bin"}"#);
+
+    let err = copy_between_accounts::<_, serde_json::Value, _, _>(
+        &src(), ("source-bucket", "source.bin"), &dst(), "bucket", "copy.bin", &options(),
+        &mock.client(), &mock).unwrap_err();
+    assert!(err.is_conflict(), "unexpected error {:?}", err);
+    match err {
+        B2Error::Conflict { ref file_name, .. } => assert_eq!(file_name, "source.bin"),
+        err => panic!("unexpected error {:?}", err)
+    }
+    assert_eq!(requests_to(&mock, "b2_cancel_large_file").len(), 1);
+    assert_eq!(mock.replies_left(), 0);
+}
+#[test]
+fn file_info_limit_includes_the_large_file_sha1() {
+    let names: Vec<String> = (0..10).map(|i| format!("key{}", i)).collect();
+    let options = TransferOptions { part_size: Some(PART_SIZE), file_info_keys: names.clone() };
+    for &(length, ok) in &[(8, true), (15, false)] {
+        let data: Vec<u8> = (0..length).collect();
+        let sent = data.len().min(PART_SIZE as usize);
+        let range = format!("bytes 0-{}/{}", sent - 1, data.len());
+        let sha1 = sha1_of(&data);
+        let info: Vec<(String, &str)> = names.iter()
+            .map(|name| (format!("X-Bz-Info-{}", name), "value")).collect();
+        let mut headers = vec![
+            ("X-Bz-File-Id", "source"),
+            ("X-Bz-File-Name", "source.bin"),
+            ("Content-Type", "application/octet-stream"),
+            ("X-Bz-Content-Sha1", "none"),
+            ("X-Bz-Upload-Timestamp", "1500000000000"),
+            ("X-Bz-Info-large_file_sha1", &sha1),
+            ("Content-Range", &range),
+        ];
+        headers.extend(info.iter().map(|&(ref name, value)| (name.as_str(), value)));
+        let mock = MockConnector::new();
+        mock.push_response("206 Partial Content", &headers, &data[..sent]);
+        if ok {
+            mock.push_json("200 OK", r#"{"bucketId":"bucket","uploadUrl":"http://upload.example/file",
+                                         "authorizationToken":"upload token"}"#);
+            mock.push_json("200 OK", &file_json("copy", data.len()));
+        }
+
+        let res = copy_between_accounts::<_, serde_json::Value, _, _>(
+            &src(), "source", &dst(), "bucket", "copy.bin", &options, &mock.client(), &mock);
+        if ok {
+            // ten entries fit in the headers of a small copy
+            res.unwrap();
+        } else {
+            // the large_file_sha1 would be the eleventh entry, so the copy is not started
+            assert_eq!(res.unwrap_err().field(), Some("file_info"));
+            assert_eq!(mock.requests().len(), 1);
+        }
+        assert_eq!(mock.replies_left(), 0);
+    }
+}
+#[test]
+fn part_retries_wait_for_retry_after() {
+    let data: Vec<u8> = (0..15).collect();
+    let sha1 = sha1_of(&data);
+    let mock = MockConnector::new();
+    push_range(&mock, "source", &data, 0, 10, "none", Some(&sha1));
+    push_start_large_file(&mock);
+    mock.push_response("429 Too Many Requests",
+                       &[("Content-Type", "application/json"), ("Retry-After", "1")],
+                       br#"{"status":429,"code":"too_many_requests","message":"slow down"}"#);
+    push_part(&mock, 1, &data[0..10]);
+    push_range(&mock, "source", &data, 10, 15, "none", Some(&sha1));
+    push_part(&mock, 2, &data[10..15]);
+    mock.push_json("200 OK", &file_json("copy", 15));
+
+    let start = Instant::now();
+    let _: TransferReport = copy_between_accounts(
+        &src(), "source", &dst(), "bucket", "copy.bin", &options(), &mock.client(),
+        &mock).unwrap();
+    let elapsed = start.elapsed();
+    // the delay of the header is used instead of the delay of the retry policy
+    assert!(elapsed >= Duration::from_secs(1), "retried after {:?}", elapsed);
+    assert!(elapsed < Duration::from_secs(2), "retried after {:?}", elapsed);
+    assert_eq!(requests_to(&mock, "/part").len(), 3);
+    // a 429 is retried with the same upload url
+    assert_eq!(requests_to(&mock, "b2_get_upload_part_url").len(), 1);
+    assert_eq!(mock.replies_left(), 0);
+}
+#[test]
+fn part_size_below_minimum_is_rejected_before_downloading() {
+    let mock = MockConnector::new();
+    let options = TransferOptions { part_size: Some(4), file_info_keys: Vec::new() };
+    let err = copy_between_accounts::<_, serde_json::Value, _, _>(
+        &src(), "source", &dst(), "bucket", "copy.bin", &options, &mock.client(),
+        &mock).unwrap_err();
+    assert_eq!(err.field(), Some("part_size"));
+    assert!(mock.requests().is_empty());
+}
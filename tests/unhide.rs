@@ -0,0 +1,160 @@
+extern crate backblaze_b2;
+extern crate hyper;
+#[macro_use]
+extern crate serde_json;
+
+mod mock;
+
+use serde_json::Value;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::files::{UnhiddenFile, UnhideStrategy};
+
+use mock::{auth, file_json, hide_json, MockConnector};
+
+fn push_listing(mock: &MockConnector, files: Vec<Value>, next_file_name: Option<&str>) {
+    let body = json!({
+        "files": files,
+        "nextFileName": next_file_name,
+        "nextFileId": next_file_name.map(|_| "next id")
+    });
+    mock.push_json("200 OK", &body.to_string());
+}
+fn push_deleted(mock: &MockConnector, id: &str) {
+    mock.push_json("200 OK", &json!({ "fileId": id, "fileName": "cat.png" }).to_string());
+}
+fn push_not_present(mock: &MockConnector, id: &str) {
+    mock.push_json("400 Bad Request", &json!({
+        "status": 400, "code": "file_not_present", "message": format!("File not present: {}", id)
+    }).to_string());
+}
+fn count(mock: &MockConnector, path: &str) -> usize {
+    mock.requests().iter().filter(|req| req.request_line().contains(path)).count()
+}
+
+#[test]
+fn deletes_hide_markers_newer_than_the_newest_upload() {
+    let mock = MockConnector::new();
+    push_listing(&mock, vec![hide_json("cat.png", "h2", 40), hide_json("cat.png", "h1", 30),
+                             file_json("cat.png", "f2", 20), hide_json("cat.png", "h0", 15),
+                             file_json("cat.png", "f1", 10)], None);
+    push_deleted(&mock, "h2");
+    push_deleted(&mock, "h1");
+
+    let unhidden: UnhiddenFile = auth().unhide_file("cat.png", "bucket", &mock.client()).unwrap();
+    assert_eq!(unhidden.strategy, UnhideStrategy::DeletedHideMarkers);
+    assert_eq!(unhidden.file.file_id, "f2");
+    let deleted: Vec<_> = unhidden.deleted_hide_markers.iter()
+        .map(|hide| hide.file_id.as_str()).collect();
+    assert_eq!(deleted, vec!["h2", "h1"]);
+    assert_eq!(mock.replies_left(), 0);
+}
+
+#[test]
+fn visible_file_is_left_alone() {
+    let mock = MockConnector::new();
+    push_listing(&mock, vec![file_json("cat.png", "f2", 20), hide_json("cat.png", "h1", 15)], None);
+
+    let unhidden: UnhiddenFile = auth().unhide_file("cat.png", "bucket", &mock.client()).unwrap();
+    assert_eq!(unhidden.strategy, UnhideStrategy::NotHidden);
+    assert_eq!(unhidden.file.file_id, "f2");
+    assert!(unhidden.deleted_hide_markers.is_empty());
+    assert_eq!(count(&mock, "b2_delete_file_version"), 0);
+}
+
+#[test]
+fn listing_stops_at_the_first_other_name() {
+    let mock = MockConnector::new();
+    // the listing would continue, but every later name is different too
+    push_listing(&mock, vec![file_json("cat.png.bak", "f1", 10)], Some("cat.png.bak2"));
+
+    let err = auth().unhide_file::<Value>("cat.png", "bucket", &mock.client()).unwrap_err();
+    assert!(err.is_file_not_found());
+    match err {
+        B2Error::FileNotFound { ref file_name, .. } => assert_eq!(file_name, "cat.png"),
+        ref err => panic!("unexpected error {:?}", err)
+    }
+    assert_eq!(count(&mock, "b2_list_file_versions"), 1);
+}
+
+#[test]
+fn only_hide_markers_is_not_found() {
+    let mock = MockConnector::new();
+    push_listing(&mock, vec![hide_json("cat.png", "h1", 30), file_json("cat.png.bak", "f1", 10)],
+                 None);
+
+    let err = auth().unhide_file::<Value>("cat.png", "bucket", &mock.client()).unwrap_err();
+    assert!(err.is_file_not_found());
+    assert_eq!(count(&mock, "b2_delete_file_version"), 0);
+}
+
+#[test]
+fn reinspects_once_and_keeps_deleted_hide_markers() {
+    let mock = MockConnector::new();
+    push_listing(&mock, vec![hide_json("cat.png", "h2", 40), hide_json("cat.png", "h1", 30),
+                             file_json("cat.png", "f1", 10)], None);
+    push_deleted(&mock, "h2");
+    // someone else deleted h1 after the listing
+    push_not_present(&mock, "h1");
+    push_listing(&mock, vec![file_json("cat.png", "f1", 10)], None);
+
+    let unhidden: UnhiddenFile = auth().unhide_file("cat.png", "bucket", &mock.client()).unwrap();
+    assert_eq!(unhidden.strategy, UnhideStrategy::DeletedHideMarkers);
+    assert_eq!(unhidden.deleted_hide_markers.len(), 1);
+    assert_eq!(unhidden.deleted_hide_markers[0].file_id, "h2");
+    assert_eq!(mock.replies_left(), 0);
+}
+
+#[test]
+fn second_lost_race_is_a_conflict() {
+    let mock = MockConnector::new();
+    push_listing(&mock, vec![hide_json("cat.png", "h1", 30), file_json("cat.png", "f1", 10)], None);
+    push_not_present(&mock, "h1");
+    push_listing(&mock, vec![hide_json("cat.png", "h2", 50), file_json("cat.png", "f1", 10)], None);
+    push_not_present(&mock, "h2");
+
+    let err = auth().unhide_file::<Value>("cat.png", "bucket", &mock.client()).unwrap_err();
+    assert!(err.is_conflict());
+    assert!(!err.is_file_not_found());
+    assert_eq!(count(&mock, "b2_list_file_versions"), 2);
+}
+
+#[test]
+fn copies_the_newest_version_when_hide_markers_cannot_be_deleted() {
+    let mock = MockConnector::new();
+    push_listing(&mock, vec![hide_json("cat.png", "h1", 30), file_json("cat.png", "f1", 10)], None);
+    mock.push_json("401 Unauthorized", &json!({
+        "status": 401, "code": "unauthorized", "message": "not entitled"
+    }).to_string());
+    let mut copy = file_json("cat.png", "f2", 50);
+    copy["accountId"] = json!("account");
+    copy["bucketId"] = json!("bucket");
+    mock.push_json("200 OK", &copy.to_string());
+
+    let unhidden: UnhiddenFile = auth().unhide_file("cat.png", "bucket", &mock.client()).unwrap();
+    assert_eq!(unhidden.strategy, UnhideStrategy::CopiedNewestVersion);
+    assert_eq!(unhidden.file.file_id, "f2");
+    assert!(unhidden.deleted_hide_markers.is_empty());
+
+    let copy_request = mock.requests().into_iter()
+        .find(|req| req.request_line().contains("b2_copy_file")).unwrap();
+    assert_eq!(copy_request.json()["sourceFileId"], "f1");
+    assert_eq!(copy_request.json()["fileName"], "cat.png");
+}
+#[test]
+fn other_authorization_errors_are_returned() {
+    for &(code, message) in &[("bad_auth_token", "Invalid authorization token"),
+                              ("unauthorized", "Not authorized")] {
+        let mock = MockConnector::new();
+        push_listing(&mock, vec![hide_json("cat.png", "h1", 30), file_json("cat.png", "f1", 10)],
+                     None);
+        mock.push_json("401 Unauthorized", &json!({
+            "status": 401, "code": code, "message": message
+        }).to_string());
+
+        let err = auth().unhide_file::<Value>("cat.png", "bucket", &mock.client()).unwrap_err();
+        assert!(err.is_authorization_issue());
+        assert!(!err.is_missing_capability());
+        assert_eq!(count(&mock, "b2_copy_file"), 0);
+    }
+}
@@ -0,0 +1,107 @@
+extern crate backblaze_b2;
+extern crate hyper;
+
+use hyper::header::Headers;
+use hyper::net::HttpConnector;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::download::DownloadedFileInfo;
+use backblaze_b2::raw::encryption::ServerSideEncryption;
+use backblaze_b2::raw::large_file::UploadPartAuthorization;
+use backblaze_b2::raw::upload::{UploadAuthorization, MAX_SIMPLE_UPLOAD_SIZE,
+                                set_file_info_headers};
+
+fn format_headers(title: &str, headers: &Headers) -> String {
+    let mut lines: Vec<String> = headers.iter()
+        .map(|header| format!("{}: {}", header.name(), header.value_string()))
+        .collect();
+    lines.sort();
+    format!("# {}\n{}\n\n", title, lines.join("\n"))
+}
+
+fn upload_auth() -> UploadAuthorization {
+    UploadAuthorization {
+        bucket_id: "4a48fe8875c6214145260818".to_owned(),
+        upload_url: "https://pod-000-1005-03.backblaze.com/b2api/v1/b2_upload_file".to_owned(),
+        authorization_token: "upload_token".to_owned()
+    }
+}
+
+fn part_auth() -> UploadPartAuthorization {
+    UploadPartAuthorization {
+        file_id: "4_za71f544e781e6891531b001a_f200ec353a2184825".to_owned(),
+        upload_url: "https://pod-000-1016-09.backblaze.com/b2api/v1/b2_upload_part/4_za71f544e781e6891531b001a_f200ec353a2184825/0037"
+            .to_owned(),
+        authorization_token: "part_token".to_owned()
+    }
+}
+
+fn encryptions() -> Vec<(&'static str, ServerSideEncryption)> {
+    vec![
+        ("no encryption", ServerSideEncryption::None),
+        ("SSE-B2", ServerSideEncryption::SseB2),
+        ("SSE-C", ServerSideEncryption::SseC {
+            algorithm: "AES256".to_owned(),
+            key: "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=".to_owned(),
+            key_md5: "XehQtXR7Cj6JOdkX9ybHzA==".to_owned()
+        }),
+    ]
+}
+
+/// Compares the headers of upload requests with the snapshot in upload_headers.txt. If the
+/// headers are changed on purpose, the snapshot must be updated.
+#[test]
+fn upload_header_snapshot() {
+    let auth = upload_auth();
+    let mut snapshot = String::new();
+    snapshot.push_str(&format_headers("hello.txt", &auth.upload_file_headers(
+                "hello.txt", None, 11, "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed")));
+    snapshot.push_str(&format_headers("ø dir/🐱.png", &auth.upload_file_headers(
+                "ø dir/🐱.png", Some("image/png".parse().unwrap()), 3, "do_not_verify")));
+    snapshot.push_str(&format_headers("stream.bin (sha1 at end)",
+                &auth.upload_file_headers_sha1_at_end("stream.bin", None, 100)));
+    for &(title, ref encryption) in &encryptions() {
+        let mut headers = auth.upload_file_headers("secret.txt", None, 11,
+                                                   "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed");
+        encryption.set_upload_headers(&mut headers);
+        snapshot.push_str(&format_headers(&format!("secret.txt ({})", title), &headers));
+    }
+    let part_auth = part_auth();
+    for &(title, ref encryption) in &encryptions() {
+        snapshot.push_str(&format_headers(&format!("part 2 ({})", title),
+                &part_auth.upload_part_headers(2, 11, "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed",
+                                               encryption)));
+    }
+    assert_eq!(snapshot, include_str!("upload_headers.txt"));
+}
+/// Uploads above the limit must be rejected before a connection is made.
+#[test]
+fn simple_upload_size_limit() {
+    let auth = upload_auth();
+    let err = auth.create_upload_file_request("huge.bin".to_owned(), None,
+                                              MAX_SIMPLE_UPLOAD_SIZE + 1,
+                                              "do_not_verify".to_owned(),
+                                              &HttpConnector).err().unwrap();
+    assert!(err.is_upload_too_large());
+    match err {
+        B2Error::UploadTooLarge { size, limit } => {
+            assert_eq!(size, MAX_SIMPLE_UPLOAD_SIZE + 1);
+            assert_eq!(limit, MAX_SIMPLE_UPLOAD_SIZE);
+        },
+        err => panic!("unexpected error {:?}", err)
+    }
+    let err = auth.create_upload_file_request_sha1_at_end("huge.bin".to_owned(), None,
+                                                          MAX_SIMPLE_UPLOAD_SIZE + 1,
+                                                          &HttpConnector).err().unwrap();
+    assert!(err.is_upload_too_large());
+    assert_eq!(err.field(), None);
+}
+/// File info values are percent-encoded on upload and decoded again on download.
+#[test]
+fn file_info_roundtrip() {
+    let mut headers = Headers::new();
+    set_file_info_headers(&mut headers, vec![("author", "ø hello, world"), ("n", "1")]).unwrap();
+    let info = DownloadedFileInfo::from_headers(&headers).unwrap();
+    assert_eq!(info.file_info["author"], "ø hello, world");
+    assert_eq!(info.file_info["n"], "1");
+}
@@ -0,0 +1,134 @@
+//! Every request rejected before it is sent reports the argument at fault in `field()`.
+
+extern crate backblaze_b2;
+extern crate hyper;
+#[macro_use]
+extern crate serde_json;
+
+mod mock;
+
+use hyper::header::Headers;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::authorize::{B2Authorization, B2Credentials};
+use backblaze_b2::raw::buckets::set_cache_control;
+use backblaze_b2::raw::download::{DownloadAuthorization, FileSelector};
+use backblaze_b2::raw::large_file::UploadPartAuthorization;
+use backblaze_b2::raw::upload::{set_file_info_headers, UploadAuthorization, MAX_FILE_INFO_ENTRIES};
+
+use mock::{auth, MockConnector};
+
+#[test]
+fn credentials() {
+    let err = B2Credentials::new(String::new(), "key".to_owned()).err().unwrap();
+    assert_eq!(err.field(), Some("id"));
+    let err = B2Credentials::new("id".to_owned(), "k\ney".to_owned()).err().unwrap();
+    assert_eq!(err.field(), Some("key"));
+}
+
+#[test]
+fn authorization() {
+    assert!(auth().validate().is_ok());
+    let cases: [(&str, fn(&mut B2Authorization)); 3] = [
+        ("authorization_token", |auth| auth.authorization_token = String::new()),
+        ("api_url", |auth| auth.api_url = "api.example".to_owned()),
+        ("download_url", |auth| auth.download_url = "http://a b".to_owned()),
+    ];
+    for &(field, corrupt) in &cases {
+        let mut auth = auth();
+        corrupt(&mut auth);
+        assert_eq!(auth.validate().unwrap_err().field(), Some(field));
+    }
+}
+
+#[test]
+fn corrupted_persisted_authorizations() {
+    let stored = serde_json::to_string(&auth()).unwrap();
+    let auth: B2Authorization = serde_json::from_str(&stored).unwrap();
+    assert!(auth.validate().is_ok());
+
+    let auth: B2Authorization = serde_json::from_str(&stored.replace("token", "to\\u0000ken"))
+        .unwrap();
+    assert_eq!(auth.validate().unwrap_err().field(), Some("authorization_token"));
+
+    let auth: DownloadAuthorization = serde_json::from_str(r#"{
+        "authorizationToken": "token", "bucketId": null, "fileNamePrefix": "",
+        "downloadUrl": "f001.backblazeb2.com"
+    }"#).unwrap();
+    assert_eq!(auth.validate().unwrap_err().field(), Some("download_url"));
+
+    let auth: UploadAuthorization = serde_json::from_str(r#"{
+        "bucketId": "bucket", "uploadUrl": "https://pod-000-1005-03.backblaze.com/b2api/v1",
+        "authorizationToken": "t\u00f8ken"
+    }"#).unwrap();
+    assert_eq!(auth.validate().unwrap_err().field(), Some("authorization_token"));
+
+    let auth: UploadPartAuthorization = serde_json::from_str(r#"{
+        "fileId": "large", "uploadUrl": "", "authorizationToken": "token"
+    }"#).unwrap();
+    assert_eq!(auth.validate().unwrap_err().field(), Some("upload_url"));
+}
+
+#[test]
+fn invalid_responses_are_api_inconsistencies() {
+    let mock = MockConnector::new();
+    mock.push_json("200 OK", r#"{"bucketId":"bucket","uploadUrl":"http://upload.example",
+                                 "authorizationToken":"to\nken"}"#);
+    let (auth, client) = (auth(), mock.client());
+    match auth.get_upload_url("bucket", &client).unwrap_err() {
+        B2Error::ApiInconsistency(ref message) => {
+            assert!(message.contains("authorization_token"));
+            assert!(message.contains("b2_get_upload_url"));
+        },
+        ref other => panic!("unexpected error {:?}", other)
+    }
+}
+
+#[test]
+fn file_info_headers() {
+    let mut headers = Headers::new();
+    let err = set_file_info_headers(&mut headers, vec![("not a name", "value")]).unwrap_err();
+    assert_eq!(err.field(), Some("file_info"));
+    let names: Vec<String> = (0..MAX_FILE_INFO_ENTRIES + 1).map(|i| format!("name{}", i)).collect();
+    let err = set_file_info_headers(&mut headers, names.iter().map(|name| (name.as_str(), "v")))
+        .unwrap_err();
+    assert_eq!(err.field(), Some("file_info"));
+    assert_eq!(headers.len(), 0);
+}
+
+#[test]
+fn cache_control() {
+    let mut info = json!({});
+    let err = set_cache_control(&mut info, "max-age").unwrap_err();
+    assert_eq!(err.field(), Some("cache_control"));
+    let err = set_cache_control(&mut json!([]), "no-cache").unwrap_err();
+    assert_eq!(err.field(), Some("bucket_info"));
+    assert_eq!(info, json!({}));
+}
+
+#[test]
+fn large_file_upload() {
+    let mock = MockConnector::new();
+    let (auth, client) = (auth(), mock.client());
+    let data = vec![0u8; 20];
+
+    let err = auth.upload_large_file::<serde_json::Value, _, _, _>(
+        "bucket", "large.bin", None, &mut &data[..], 20, Some(4), &client, &mock).unwrap_err();
+    assert_eq!(err.field(), Some("part_size"));
+    // a file of a single part must be uploaded with b2_upload_file
+    let err = auth.upload_large_file::<serde_json::Value, _, _, _>(
+        "bucket", "large.bin", None, &mut &data[..], 20, Some(20), &client, &mock).unwrap_err();
+    assert_eq!(err.field(), Some("content_length"));
+    assert!(mock.requests().is_empty());
+}
+
+#[test]
+fn file_selector() {
+    let err = FileSelector::from(("bucket", "file.bin")).file_id().unwrap_err();
+    assert!(err.is_unsupported_file_selector());
+    assert_eq!(err.field(), None);
+    match err {
+        B2Error::UnsupportedFileSelector { required } => assert_eq!(required, "a file id"),
+        ref other => panic!("unexpected error {:?}", other)
+    }
+}
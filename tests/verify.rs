@@ -0,0 +1,154 @@
+extern crate backblaze_b2;
+extern crate serde_json;
+
+use std::time::Instant;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::files::FileInfo;
+use backblaze_b2::verify::*;
+
+/// Expands two digit tags like "aa" to a valid sha1, such that the tests stay readable.
+fn sha1(tag: &str) -> String {
+    if tag.len() == 2 { tag.repeat(20) } else { tag.to_owned() }
+}
+fn file(name: &str, size: u64, sha1: &str) -> FileInfo {
+    let sha1 = ::sha1(sha1);
+    FileInfo {
+        file_id: format!("id of {}", name),
+        file_name: name.to_owned(),
+        content_length: size,
+        content_type: "application/octet-stream".to_owned(),
+        content_sha1: sha1,
+        file_info: serde_json::Value::Null,
+        upload_timestamp: 0
+    }
+}
+fn entry(name: &str, size: u64, sha1: &str) -> ManifestEntry {
+    ManifestEntry {
+        name: name.to_owned(),
+        size: size,
+        sha1: ::sha1(sha1)
+    }
+}
+fn compare(manifest: Vec<ManifestEntry>, listing: Vec<FileInfo>) -> VerifyReport {
+    compare_manifest(manifest, listing.into_iter().map(Ok), None, |_| Ok(None)).unwrap()
+}
+
+#[test]
+fn identical() {
+    let report = compare(vec![entry("a", 1, "aa"), entry("b", 2, "bb")],
+                         vec![file("a", 1, "aa"), file("b", 2, "BB")]);
+    assert!(report.is_ok());
+    assert_eq!(report.checked, 2);
+}
+#[test]
+fn empty() {
+    assert!(compare(Vec::new(), Vec::new()).is_ok());
+    let report = compare(Vec::new(), vec![file("a", 1, "aa")]);
+    assert_eq!(report.discrepancies.len(), 1);
+    let report = compare(vec![entry("a", 1, "aa")], Vec::new());
+    assert_eq!(report.discrepancies.len(), 1);
+}
+#[test]
+fn mismatches() {
+    let report = compare(vec![entry("a", 1, "aa"), entry("b", 2, "bb"), entry("d", 4, "dd")],
+                         vec![file("a", 1, "ab"), file("b", 3, "bb"), file("c", 3, "cc")]);
+    assert_eq!(report.checked, 2);
+    let kinds: Vec<(&str, &str)> = report.discrepancies.iter().map(|d| match *d {
+        Discrepancy::Missing(ref entry) => ("missing", entry.name.as_str()),
+        Discrepancy::Extra(ref file) => ("extra", file.file_name.as_str()),
+        Discrepancy::SizeMismatch { ref expected, .. } => ("size", expected.name.as_str()),
+        Discrepancy::Sha1Mismatch { ref expected, .. } => ("sha1", expected.name.as_str()),
+    }).collect();
+    assert_eq!(kinds, vec![("sha1", "a"), ("size", "b"), ("extra", "c"), ("missing", "d")]);
+}
+/// Backblaze sorts by bytes, so "a-b" comes before "a/b" and "Z" before "a".
+#[test]
+fn byte_order() {
+    let report = compare(vec![entry("Z", 1, "ff"), entry("a-b", 1, "aa"), entry("a/b", 1, "ab")],
+                         vec![file("Z", 1, "ff"), file("a-b", 1, "aa"), file("a/b", 1, "ab")]);
+    assert!(report.is_ok());
+}
+#[test]
+fn unsorted_manifest() {
+    let err = compare_manifest(vec![entry("b", 1, "bb"), entry("a", 1, "aa")],
+                               Vec::new().into_iter().map(Ok), None, |_| Ok(None)).unwrap_err();
+    assert_eq!(err.field(), Some("manifest"));
+    let err = compare_manifest(vec![entry("a", 1, "aa"), entry("a", 1, "aa")],
+                               Vec::new().into_iter().map(Ok), None, |_| Ok(None)).unwrap_err();
+    assert_eq!(err.field(), Some("manifest"));
+}
+#[test]
+fn large_files() {
+    let manifest = vec![entry("large", 10, "aa")];
+    let listing = vec![file("large", 10, "none")];
+    let report = compare(manifest.clone(), listing.clone());
+    assert!(report.is_ok());
+    assert_eq!(report.unverified, vec!["large".to_owned()]);
+
+    let mut hashed = Vec::new();
+    let report = compare_manifest(manifest.clone(), listing.clone().into_iter().map(Ok), None,
+                                  |file| {
+        hashed.push(file.file_id.clone());
+        Ok(Some(sha1("aa")))
+    }).unwrap();
+    assert!(report.is_ok());
+    assert!(report.unverified.is_empty());
+    assert_eq!(hashed, vec!["id of large".to_owned()]);
+
+    let report = compare_manifest(manifest, listing.into_iter().map(Ok), None,
+                                  |_| Ok(Some(sha1("bb")))).unwrap();
+    assert_eq!(report.discrepancies.len(), 1);
+}
+#[test]
+fn deadline() {
+    let report = compare_manifest(vec![entry("a", 1, "aa")], vec![file("a", 1, "aa")]
+                                  .into_iter().map(Ok), Some(Instant::now()), |_| Ok(None))
+        .unwrap();
+    assert!(!report.complete);
+    assert!(!report.is_ok());
+    assert_eq!(report.checked, 0);
+}
+#[test]
+fn listing_error() {
+    let listing = vec![Ok(file("a", 1, "aa")),
+                       Err(B2Error::ApiInconsistency("listing failed".to_owned()))];
+    let err = compare_manifest(vec![entry("a", 1, "aa"), entry("b", 1, "bb")], listing, None,
+                               |_| Ok(None)).unwrap_err();
+    match err {
+        B2Error::ApiInconsistency(msg) => assert_eq!(msg, "listing failed"),
+        err => panic!("{}", err)
+    }
+}
+#[test]
+fn declared_large_file_sha1() {
+    let sha1 = "2aae6c35c94fcfb415dbe95f408b9ce91ee846ed";
+    let mut large = file("large", 10, "none");
+    large.file_info = serde_json::from_str(&format!(r#"{{"large_file_sha1": "{}"}}"#, sha1))
+        .unwrap();
+    let report = compare_manifest(vec![entry("large", 10, sha1)], vec![Ok(large)], None,
+                                  |_| panic!("the declared sha1 should be used")).unwrap();
+    assert!(report.is_ok());
+    assert!(report.unverified.is_empty());
+}
+#[test]
+fn unverified_prefix_and_malformed_sha1() {
+    let manifest = vec![entry("malformed", 1, "aa"), entry("prefixed", 1, "bb")];
+    let listing = vec![file("malformed", 1, "not a sha1"),
+                       file("prefixed", 1, &format!("unverified:{}", sha1("BB")))];
+    let report = compare(manifest.clone(), listing.clone());
+    assert!(report.discrepancies.is_empty());
+    assert_eq!(report.unverified, vec!["malformed".to_owned()]);
+
+    let mut hashed = Vec::new();
+    let report = compare_manifest(manifest, listing.into_iter().map(Ok), None, |file| {
+        hashed.push(file.file_name.clone());
+        Ok(Some(sha1("ab")))
+    }).unwrap();
+    assert_eq!(hashed, vec!["malformed".to_owned()]);
+    assert_eq!(report.discrepancies.len(), 1);
+    match report.discrepancies[0] {
+        Discrepancy::Sha1Mismatch { ref expected, .. } => assert_eq!(expected.name, "malformed"),
+        ref other => panic!("{:?}", other)
+    }
+}
@@ -0,0 +1,55 @@
+extern crate backblaze_b2;
+extern crate hyper;
+extern crate serde_json;
+
+mod mock;
+
+use backblaze_b2::B2Error;
+use backblaze_b2::raw::files::*;
+
+use mock::{file, hide};
+
+fn version_at(listing: &[FileVersion], at: u64) -> Option<String> {
+    find_version_at(listing.iter().cloned().map(Ok), "b", at).unwrap()
+        .map(|file| file.file_id)
+}
+
+#[test]
+fn picks_newest_version_before_time() {
+    let listing = vec![file("a", "a1", 5), file("b", "b3", 30), file("b", "b2", 20),
+                       file("b", "b1", 10), file("b/c", "c1", 1)];
+    assert_eq!(version_at(&listing, 25), Some("b2".to_owned()));
+    assert_eq!(version_at(&listing, 20), Some("b2".to_owned()));
+    assert_eq!(version_at(&listing, 10), Some("b1".to_owned()));
+    // a time in the future returns the newest version
+    assert_eq!(version_at(&listing, u64::max_value()), Some("b3".to_owned()));
+    // the file did not exist yet
+    assert_eq!(version_at(&listing, 9), None);
+}
+#[test]
+fn hide_markers() {
+    // the file was hidden between 25 and 30 and again since 50
+    let listing = vec![hide("b", "h2", 50), file("b", "b2", 30), hide("b", "h1", 25),
+                       file("b", "b1", 20)];
+    assert_eq!(version_at(&listing, 60), None);
+    assert_eq!(version_at(&listing, 40), Some("b2".to_owned()));
+    assert_eq!(version_at(&listing, 27), None);
+    assert_eq!(version_at(&listing, 22), Some("b1".to_owned()));
+}
+#[test]
+fn missing_name() {
+    let listing = vec![file("a", "a1", 5), file("c", "c1", 5)];
+    assert_eq!(version_at(&listing, 10), None);
+    assert_eq!(version_at(&[], 10), None);
+}
+#[test]
+fn stops_after_the_name() {
+    let listing = vec![Ok(file("b", "b1", 10)),
+                       Err(B2Error::ApiInconsistency("not reached".to_owned()))];
+    let found = find_version_at(listing, "b", 20).unwrap();
+    assert_eq!(found.unwrap().file_id, "b1");
+
+    let listing = vec![Ok(file("c", "c1", 10)),
+                       Err(B2Error::ApiInconsistency("not reached".to_owned()))];
+    assert!(find_version_at(listing, "b", 20).unwrap().is_none());
+}
@@ -0,0 +1,224 @@
+extern crate backblaze_b2;
+extern crate hyper;
+extern crate serde;
+extern crate serde_json;
+
+use hyper::status::StatusCode;
+
+use serde::{Serialize, Deserialize};
+use serde_json::value::Value;
+
+use backblaze_b2::{B2Error, B2ErrorMessage};
+use backblaze_b2::raw::buckets::*;
+use backblaze_b2::raw::files::*;
+use backblaze_b2::raw::large_file::ListUnfinishedLargeFilesResponse;
+use backblaze_b2::raw::upload::*;
+
+/// Checks that every field produced when serializing the value is present in the original
+/// document.
+fn assert_fields_present(original: &Value, reserialized: &Value, path: &str) {
+    match (original, reserialized) {
+        (&Value::Object(ref orig), &Value::Object(ref re)) => {
+            for (key, value) in re {
+                let path = format!("{}.{}", path, key);
+                match orig.get(key) {
+                    Some(orig_value) => assert_fields_present(orig_value, value, &path),
+                    None => panic!("{} is not present in the original document", path)
+                }
+            }
+        },
+        (&Value::Array(ref orig), &Value::Array(ref re)) => {
+            assert_eq!(orig.len(), re.len(), "{} changed length", path);
+            for (i, (orig_value, value)) in orig.iter().zip(re).enumerate() {
+                assert_fields_present(orig_value, value, &format!("{}[{}]", path, i));
+            }
+        },
+        _ => {}
+    }
+}
+fn roundtrip<T>(fixture: &str) -> T
+    where T: Serialize, for<'de> T: Deserialize<'de>
+{
+    let original: Value = serde_json::from_str(fixture).unwrap();
+    let parsed: T = serde_json::from_value(original.clone()).unwrap();
+    assert_fields_present(&original, &serde_json::to_value(&parsed).unwrap(), "$");
+    parsed
+}
+
+#[test]
+fn wire_list_buckets() {
+    let original: Value = serde_json::from_str(include_str!("wire/list_buckets.json")).unwrap();
+    let buckets = original["buckets"].to_string();
+    let buckets: Vec<Bucket> = roundtrip(&buckets);
+    assert_eq!(buckets.len(), 2);
+    assert_eq!(buckets[0].bucket_type, BucketType::Private);
+    assert_eq!(buckets[1].bucket_type, BucketType::Public);
+    assert_eq!(buckets[1].lifecycle_rules.len(), 1);
+    assert_eq!(buckets[1].revision, 3);
+    assert_eq!(format!("{}", buckets[1]),
+               "Puppy-Videos (5b232e8875c6214145260818, allPublic, revision 3)");
+}
+#[test]
+fn wire_list_buckets_v2() {
+    let original: Value = serde_json::from_str(include_str!("wire/list_buckets_v2.json")).unwrap();
+    let buckets = original["buckets"].to_string();
+    let buckets: Vec<Bucket> = roundtrip(&buckets);
+    assert!(buckets[0].is_file_lock_enabled());
+    assert!(!buckets[1].is_file_lock_enabled());
+    assert_eq!(buckets[0].options, Some(vec!["s3".to_owned()]));
+    let replication = buckets[0].replication_configuration.as_ref().unwrap();
+    let source = replication.value.as_ref().unwrap().as_replication_source.as_ref().unwrap();
+    assert_eq!(source.replication_rules[0].destination_bucket_id, buckets[1].bucket_id);
+    let replication = buckets[1].replication_configuration.as_ref().unwrap();
+    assert!(replication.value.as_ref().unwrap().as_replication_source.is_none());
+    let encryption = buckets[1].default_server_side_encryption.as_ref().unwrap();
+    assert!(!encryption.is_client_authorized_to_read);
+}
+#[test]
+fn wire_create_bucket() {
+    let bucket: Bucket = roundtrip(include_str!("wire/create_bucket.json"));
+    assert_eq!(bucket.bucket_name, "rust-b2-test-bucket");
+    assert_eq!(bucket.bucket_info["abc"], "test");
+}
+#[test]
+fn wire_get_upload_url() {
+    let auth: UploadAuthorization = roundtrip(include_str!("wire/get_upload_url.json"));
+    assert_eq!(auth.bucket_id, "4a48fe8875c6214145260818");
+}
+#[test]
+fn wire_get_file_info() {
+    let file: MoreFileInfo = roundtrip(include_str!("wire/get_file_info.json"));
+    assert_eq!(file.action, FileType::File);
+    assert_eq!(file.content_length, 122573);
+    assert_eq!(file.file_info["src_last_modified_millis"], "1448287946000");
+    assert!(file.check_bucket("4a48fe8875c6214145260818").is_ok());
+    let err = file.check_bucket("e73ede9c9c8412db49f60715").unwrap_err();
+    assert!(err.is_bucket_mismatch());
+}
+#[test]
+fn wire_upload_file() {
+    let file: MoreFileInfo = roundtrip(include_str!("wire/upload_file.json"));
+    assert_eq!(file.file_name, "typing_test.txt");
+    assert_eq!(format!("{}", file), "typing_test.txt \
+        (4_h4a48fe8875c6214145260818_f000000000000472a_d20140104_m032022_c001_v0000123_t0104, \
+        46 bytes, upload)");
+    let file: FileInfo = file.into();
+    assert_eq!(file.content_sha1, "bae5ed658ab3546aee12f23f36392f35dba1ebdd");
+    assert_eq!(format!("{}", file), "typing_test.txt \
+        (4_h4a48fe8875c6214145260818_f000000000000472a_d20140104_m032022_c001_v0000123_t0104, \
+        46 bytes)");
+}
+#[test]
+fn wire_hide_file() {
+    let hide: HideMarkerInfo = roundtrip(include_str!("wire/hide_file.json"));
+    assert_eq!(hide.upload_timestamp, 1437815673000);
+}
+#[test]
+fn wire_list_file_versions() {
+    let versions: Vec<FileVersion> = roundtrip(include_str!("wire/list_file_versions.json"));
+    assert_eq!(versions.len(), 4);
+    match versions[0] {
+        FileVersion::File(ref file) => assert_eq!(file.content_length, 1350),
+        ref other => panic!("expected a file, got {:?}", other)
+    }
+    match versions[1] {
+        FileVersion::HideMarker(ref hide) => assert_eq!(hide.upload_timestamp, 1439083314000),
+        ref other => panic!("expected a hide marker, got {:?}", other)
+    }
+    match versions[2] {
+        FileVersion::UnfinishedLargeFile(ref file) => {
+            assert_eq!(file.file_info["src_last_modified_millis"], "1439083310000")
+        },
+        ref other => panic!("expected an unfinished large file, got {:?}", other)
+    }
+    match versions[3] {
+        FileVersion::Folder(ref folder) => assert_eq!(folder.file_name, "files/photos/"),
+        ref other => panic!("expected a folder, got {:?}", other)
+    }
+}
+#[test]
+fn wire_list_unfinished_large_files() {
+    let listing: ListUnfinishedLargeFilesResponse =
+        roundtrip(include_str!("wire/list_unfinished_large_files.json"));
+    assert_eq!(listing.files.len(), 1);
+    assert_eq!(listing.files[0].file_name, "bigfile.dat");
+    assert_eq!(listing.files[0].bucket_id, Some("e73ede9c9c8412db49f60715".to_owned()));
+    assert_eq!(listing.files[0].file_info["src_last_modified_millis"], "1440456229000");
+    assert!(listing.next_file_id.is_none());
+}
+#[test]
+fn wire_errors() {
+    let errors: Vec<Value> = serde_json::from_str(include_str!("wire/errors.json")).unwrap();
+    let errors: Vec<B2Error> = errors.into_iter().map(|value| {
+        let status = StatusCode::from_u16(value["status"].as_u64().unwrap() as u16);
+        let message: B2ErrorMessage = serde_json::from_value(value).unwrap();
+        B2Error::B2Error(status, message)
+    }).collect();
+    assert!(errors[0].is_bucket_not_found());
+    assert!(errors[1].is_duplicate_bucket_name());
+    assert!(errors[2].is_maximum_bucket_limit());
+    assert!(errors[3].is_invalid_bucket_name());
+    assert!(errors[4].is_invalid_sha1());
+    assert!(errors[5].is_file_already_hidden());
+    assert!(errors[6].is_expired_authentication());
+    assert!(errors[7].is_authorization_issue());
+    assert!(errors[8].is_cap_exceeded());
+    assert!(errors[9].is_file_not_found());
+    assert!(errors[10].is_range_out_of_bounds());
+    assert!(errors[11].is_too_many_requests());
+    assert!(errors[12].is_service_unavilable());
+    assert!(errors[12].should_back_off());
+    assert!(errors[13].is_account_suspended());
+    assert!(!errors[13].should_obtain_new_authentication());
+    assert!(errors[14].is_payment_required());
+    assert!(!errors[14].should_obtain_new_authentication());
+    assert!(errors[15].is_api_version_unsupported());
+    assert!(!errors[0].is_api_version_unsupported());
+}
+#[test]
+fn wire_error_bodies() {
+    let err = B2Error::from_error_body(StatusCode::Unauthorized, &br#"{
+        "status": 401, "code": "expired_auth_token", "message": "Authorization token has expired"
+    }"#[..]);
+    assert!(err.is_expired_authentication());
+    assert_eq!(err.http_status(), Some(401));
+
+    let html = b"<html><body><h1>502 Bad Gateway</h1></body></html>";
+    let err = B2Error::from_error_body(StatusCode::BadGateway, &html[..]);
+    match err {
+        B2Error::UnexpectedResponse { status, ref body, .. } => {
+            assert_eq!(status, StatusCode::BadGateway);
+            assert!(body.contains("502 Bad Gateway"));
+        },
+        ref other => panic!("expected an unexpected response, got {:?}", other)
+    }
+    assert!(err.is_service_unavilable());
+    assert!(!err.should_back_off());
+
+    // only the start of a huge body is kept
+    let huge = vec![b'x'; 1024 * 1024];
+    match B2Error::from_error_body(StatusCode::ServiceUnavailable, &huge[..]) {
+        B2Error::UnexpectedResponse { ref body, .. } => assert!(body.len() <= 1024),
+        ref other => panic!("expected an unexpected response, got {:?}", other)
+    }
+}
+#[test]
+fn bucket_comparison() {
+    let original: Value = serde_json::from_str(include_str!("wire/list_buckets.json")).unwrap();
+    let buckets: Vec<Bucket> = serde_json::from_value(original["buckets"].clone()).unwrap();
+    let bucket = &buckets[1];
+
+    let mut updated = bucket.clone();
+    updated.revision += 1;
+    assert!(updated.same_identity(bucket));
+    assert!(updated.same_settings(bucket));
+    assert_eq!(updated.is_newer_than(bucket), Some(true));
+    assert_eq!(bucket.is_newer_than(bucket), Some(false));
+
+    updated.lifecycle_rules.clear();
+    assert!(!updated.same_settings(bucket));
+
+    assert!(!buckets[0].same_identity(bucket));
+    assert!(!buckets[0].same_settings(bucket));
+    assert_eq!(buckets[0].is_newer_than(bucket), None);
+}